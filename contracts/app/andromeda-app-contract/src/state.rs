@@ -210,6 +210,7 @@ pub fn create_cross_chain_message(
         msg: to_json_binary(&msg)?,
         owner: Some(AndrAddr::from_string(target_chain_info.owner)),
         chain: Some(target_chain_info.chain_name),
+        resolve_env: false,
     };
 
     let cosmos_msg = CosmosMsg::Wasm(WasmMsg::Execute {
@@ -319,6 +320,7 @@ mod test {
                 msg,
                 owner,
                 chain,
+                ..
             } => {
                 assert_eq!(ado_type, "app-contract");
                 assert_eq!(owner, Some(AndrAddr::from_string(target_owner.clone())));
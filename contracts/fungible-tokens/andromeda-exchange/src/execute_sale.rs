@@ -1,5 +1,6 @@
 use andromeda_fungible_tokens::exchange::Sale;
 use andromeda_std::{
+    ado_contract::ADOContract,
     amp::Recipient,
     common::{
         context::ExecuteContext,
@@ -8,6 +9,7 @@ use andromeda_std::{
         schedule::Schedule,
     },
     error::ContractError,
+    os::aos_querier::AOSQuerier,
 };
 use cosmwasm_std::{attr, ensure, Response, Uint128};
 use cw_utils::one_coin;
@@ -141,7 +143,7 @@ pub fn execute_purchase(
     let token_asset = Asset::Cw20Token(token_addr);
     let sub_msg = generate_transfer_message_recipient(
         &deps.as_ref(),
-        token_asset,
+        token_asset.clone(),
         purchased,
         recipient.clone(),
         None,
@@ -162,7 +164,7 @@ pub fn execute_purchase(
         None,
     )?);
 
-    Ok(resp.add_attributes(vec![
+    resp = resp.add_attributes(vec![
         attr("action", "purchase"),
         attr("purchaser", sender),
         attr("recipient", recipient.address.to_string()),
@@ -170,7 +172,25 @@ pub fn execute_purchase(
         attr("purchase_asset", asset_sent.to_string()),
         attr("purchase_asset_amount_send", amount_sent - remainder),
         attr("recipient", sale.recipient.address.to_string()),
-    ]))
+    ]);
+
+    // Human readable amounts are added for assets with metadata in the IBC registry
+    let kernel_address = ADOContract::default().get_kernel_address(deps.storage)?;
+    for (key, asset, amount) in [
+        ("amount_display", &token_asset, purchased),
+        (
+            "purchase_asset_amount_display",
+            &asset_sent,
+            amount_sent - remainder,
+        ),
+    ] {
+        if let Some(display) =
+            AOSQuerier::format_asset_amount(&deps.querier, &kernel_address, asset, amount)
+        {
+            resp = resp.add_attribute(key, display);
+        }
+    }
+    Ok(resp)
 }
 
 pub fn execute_purchase_native(
@@ -76,41 +76,39 @@ impl Querier for WasmMockQuerier {
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
-            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
-                match from_json(msg).unwrap() {
-                    Cw20QueryMsg::Balance { address } => {
-                        let balances: &HashMap<String, Uint128> =
-                            match self.token_querier.balances.get(contract_addr) {
-                                Some(balances) => balances,
-                                None => {
-                                    return SystemResult::Err(SystemError::InvalidRequest {
-                                        error: format!(
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => match from_json(msg) {
+                Ok(Cw20QueryMsg::Balance { address }) => {
+                    let balances: &HashMap<String, Uint128> =
+                        match self.token_querier.balances.get(contract_addr) {
+                            Some(balances) => balances,
+                            None => {
+                                return SystemResult::Err(SystemError::InvalidRequest {
+                                    error: format!(
                                         "No balance info exists for the contract {contract_addr}"
                                     ),
-                                        request: msg.as_slice().into(),
-                                    })
-                                }
-                            };
-
-                        let balance = match balances.get(&address) {
-                            Some(v) => *v,
-                            None => {
-                                return SystemResult::Ok(ContractResult::Ok(
-                                    to_json_binary(&Cw20BalanceResponse {
-                                        balance: Uint128::zero(),
-                                    })
-                                    .unwrap(),
-                                ));
+                                    request: msg.as_slice().into(),
+                                })
                             }
                         };
 
-                        SystemResult::Ok(ContractResult::Ok(
-                            to_json_binary(&Cw20BalanceResponse { balance }).unwrap(),
-                        ))
-                    }
-                    _ => MockAndromedaQuerier::default().handle_query(&self.base, request),
+                    let balance = match balances.get(&address) {
+                        Some(v) => *v,
+                        None => {
+                            return SystemResult::Ok(ContractResult::Ok(
+                                to_json_binary(&Cw20BalanceResponse {
+                                    balance: Uint128::zero(),
+                                })
+                                .unwrap(),
+                            ));
+                        }
+                    };
+
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&Cw20BalanceResponse { balance }).unwrap(),
+                    ))
                 }
-            }
+                _ => MockAndromedaQuerier::default().handle_query(&self.base, request),
+            },
             _ => MockAndromedaQuerier::default().handle_query(&self.base, request),
         }
     }
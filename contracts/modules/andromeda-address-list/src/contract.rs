@@ -8,10 +8,10 @@ use andromeda_std::{
         permissioning::{LocalPermission, Permission},
         InstantiateMsg as BaseInstantiateMsg, MigrateMsg,
     },
-    ado_contract::ADOContract,
+    ado_contract::{permissioning::verify_local_permission, ADOContract},
     amp::AndrAddr,
     andr_execute_fn,
-    common::{context::ExecuteContext, encode_binary, expiration::Expiry, schedule::Schedule},
+    common::{context::ExecuteContext, encode_binary, schedule::Schedule},
     error::ContractError,
 };
 
@@ -33,30 +33,17 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     // If the user provided an actor and permission, save them.
-    if let Some(mut actor_permission) = msg.actor_permission {
+    if let Some(actor_permission) = msg.actor_permission {
         ensure!(
             !actor_permission.actors.is_empty(),
             ContractError::NoActorsProvided {}
         );
-        actor_permission.permission.validate_times(&env)?;
-
-        // If the permission is a whitelist, make sure to set last used time as none
-        actor_permission.permission = if let LocalPermission::Whitelisted {
-            schedule, window, ..
-        } = actor_permission.permission
-        {
-            LocalPermission::Whitelisted {
-                schedule,
-                window,
-                last_used: None,
-            }
-        } else {
-            actor_permission.permission
-        };
+        // Last used and quota usage always start unset
+        let permission = verify_local_permission(&env, actor_permission.permission)?;
 
         for actor in actor_permission.actors {
             let verified_actor = actor.get_raw_address(&deps.as_ref())?;
-            add_actors_permission(deps.storage, verified_actor, &actor_permission.permission)?;
+            add_actors_permission(deps.storage, verified_actor, &permission)?;
         }
     }
 
@@ -131,28 +118,15 @@ fn execute_authorize_permission_actors(
 fn execute_permission_actors(
     ctx: ExecuteContext,
     actors: Vec<AndrAddr>,
-    mut permission: LocalPermission,
+    permission: LocalPermission,
 ) -> Result<Response, ContractError> {
     let ExecuteContext { deps, env, .. } = ctx;
 
     ensure!(!actors.is_empty(), ContractError::NoActorsProvided {});
-    let (start, end) = permission.validate_times(&env)?;
-    let verified_schedule = Schedule::new(Some(Expiry::AtTime(start)), end.map(Expiry::AtTime));
+    let permission = verify_local_permission(&env, permission)?;
 
     for actor in actors.clone() {
         let verified_actor = actor.get_raw_address(&deps.as_ref())?;
-        permission = match permission {
-            LocalPermission::Whitelisted {
-                window, last_used, ..
-            } => LocalPermission::whitelisted(verified_schedule.clone(), window, last_used),
-            LocalPermission::Blacklisted { .. } => {
-                LocalPermission::blacklisted(verified_schedule.clone())
-            }
-            LocalPermission::Limited { uses, .. } => {
-                LocalPermission::limited(verified_schedule.clone(), uses)
-            }
-        };
-
         add_actors_permission(deps.storage, verified_actor, &permission)?;
     }
     let actors_str = actors
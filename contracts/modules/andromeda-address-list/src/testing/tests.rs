@@ -315,3 +315,49 @@ fn test_actor_permission_query() {
     let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
     assert_eq!(err, ContractError::ActorNotFound {});
 }
+
+#[test]
+fn test_permission_actors_quota() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+
+    let operator = deps.api.addr_make("creator");
+    let info = message_info(&operator, &[]);
+    let actor = deps.api.addr_make("actor");
+
+    init(&mut deps, info.clone());
+
+    // Quotas without uses or with an empty window are rejected
+    let msg = ExecuteMsg::PermissionActors {
+        actors: vec![AndrAddr::from_string(actor.clone())],
+        permission: LocalPermission::quota(Schedule::new(None, None), 2, Milliseconds::zero()),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidPermission { .. }));
+
+    // Usage provided by the caller is reset
+    let msg = ExecuteMsg::PermissionActors {
+        actors: vec![AndrAddr::from_string(actor.clone())],
+        permission: LocalPermission::Quota {
+            schedule: Schedule::new(None, None),
+            max_uses: 2,
+            window: Milliseconds::from_seconds(60),
+            recent_uses: vec![Milliseconds(1); 5],
+        },
+    };
+    execute(deps.as_mut(), env, info, msg).unwrap();
+    let permission = PERMISSIONS.load(deps.as_ref().storage, &actor).unwrap();
+    assert_eq!(
+        permission,
+        LocalPermission::quota(
+            Schedule::new(
+                Some(andromeda_std::common::expiration::Expiry::AtTime(
+                    Milliseconds(1571797419879),
+                )),
+                None,
+            ),
+            2,
+            Milliseconds::from_seconds(60),
+        )
+    );
+}
@@ -23,6 +23,7 @@ use andromeda_std::{
         OrderBy,
     },
     error::ContractError,
+    os::aos_querier::AOSQuerier,
 };
 
 #[cfg(not(feature = "library"))]
@@ -462,6 +463,13 @@ fn purchase_tiers(
         .add_attribute("action", "purchase_tiers")
         .add_attribute("payment", format!("{0}{1}", amount, denom))
         .add_attribute("total_cost", total_cost.to_string());
+    // Human readable amounts are added for assets with metadata in the IBC registry
+    let kernel_address = ADOContract::default().get_kernel_address(deps.storage)?;
+    if let Some(display) =
+        AOSQuerier::format_asset_amount(&deps.querier, &kernel_address, &denom, total_cost)
+    {
+        resp = resp.add_attribute("total_cost_display", display);
+    }
 
     if amount > total_cost {
         resp = resp
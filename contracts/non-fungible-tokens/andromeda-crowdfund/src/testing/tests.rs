@@ -674,6 +674,7 @@ mod test {
                     .add_attribute("action", "purchase_tiers")
                     .add_attribute("payment", "1000native:uandr")
                     .add_attribute("total_cost", "100")
+                    .add_attribute("total_cost_display", "0.0001 ANDR")
                     .add_attribute("refunded", "900")
                     .add_message(BankMsg::Send {
                         to_address: buyer.to_string(),
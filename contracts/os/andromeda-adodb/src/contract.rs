@@ -3,7 +3,7 @@ use andromeda_std::ado_base::{InstantiateMsg as BaseInstantiateMsg, MigrateMsg};
 use andromeda_std::ado_contract::ADOContract;
 use andromeda_std::common::encode_binary;
 use andromeda_std::error::ContractError;
-use andromeda_std::os::adodb::{ADOVersion, ExecuteMsg, InstantiateMsg, QueryMsg};
+use andromeda_std::os::adodb::{ADOVersion, ExecuteMsg, InstantiateMsg, QueryMsg, VersionStatus};
 use cosmwasm_std::{
     entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError,
 };
@@ -85,6 +85,40 @@ pub fn execute(
             ado_type,
             publisher,
         } => execute::update_publisher(deps, info, &ADOVersion::from_string(ado_type), publisher),
+        ExecuteMsg::DeprecateVersion {
+            ado_type,
+            version,
+            reason,
+        } => execute::set_version_status(
+            deps,
+            info,
+            ado_type,
+            version,
+            VersionStatus::Deprecated { reason },
+        ),
+        ExecuteMsg::YankVersion {
+            ado_type,
+            version,
+            reason,
+        } => execute::set_version_status(
+            deps,
+            info,
+            ado_type,
+            version,
+            VersionStatus::Yanked { reason },
+        ),
+        ExecuteMsg::RestoreVersion { ado_type, version } => {
+            execute::set_version_status(deps, info, ado_type, version, VersionStatus::Active)
+        }
+        ExecuteMsg::AddMigrationPath {
+            ado_type,
+            from,
+            to,
+            requires_msg,
+        } => execute::add_migration_path(deps, info, ado_type, from, to, requires_msg),
+        ExecuteMsg::RemoveMigrationPath { ado_type, from, to } => {
+            execute::remove_migration_path(deps, info, ado_type, from, to)
+        }
         // Base message
         ExecuteMsg::Ownership(ownership_message) => {
             ADOContract::default().execute_ownership(deps, env, info, ownership_message)
@@ -119,6 +153,19 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             start_after,
             limit,
         )?),
+        QueryMsg::ADOVersionsWithStatus {
+            ado_type,
+            start_after,
+            limit,
+        } => encode_binary(&query::ado_versions_with_status(
+            deps.storage,
+            &ado_type,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::MigrationPath { ado_type, from, to } => {
+            encode_binary(&query::migration_path(deps, ado_type, from, to)?)
+        }
         // QueryMsg::UnpublishedADOVersions { ado_type } => {
         //     encode_binary(&query::unpublished_ado_versions(deps.storage, &ado_type)?)
         // }
@@ -1,13 +1,13 @@
 use crate::state::{
     read_code_id, remove_code_id, save_action_fees, store_code_id, ACTION_FEES, ADO_TYPE,
-    PUBLISHER, UNPUBLISHED_CODE_IDS, UNPUBLISHED_VERSIONS,
+    MIGRATION_PATHS, PUBLISHER, UNPUBLISHED_CODE_IDS, UNPUBLISHED_VERSIONS, VERSION_STATUS,
 };
 
 use andromeda_std::ado_contract::ADOContract;
 
 use andromeda_std::error::ContractError;
-use andromeda_std::os::adodb::{ADOVersion, ActionFee};
-use cosmwasm_std::{attr, ensure, DepsMut, Env, MessageInfo, Response};
+use andromeda_std::os::adodb::{ADOVersion, ActionFee, VersionStatus};
+use cosmwasm_std::{attr, ensure, Deps, DepsMut, Env, MessageInfo, Response};
 
 #[allow(clippy::too_many_arguments)]
 pub fn publish(
@@ -136,8 +136,9 @@ pub fn unpublish(
 
     remove_code_id(deps.storage, &ado_version, code_id)?;
 
-    // Remove publisher for this version
+    // Remove publisher and status for this version
     PUBLISHER.remove(deps.storage, ado_version.as_str());
+    VERSION_STATUS.remove(deps.storage, ado_version.as_str());
 
     // Add the unpublished code id to the list
     UNPUBLISHED_CODE_IDS.save(deps.storage, code_id, &true)?;
@@ -241,3 +242,163 @@ pub fn update_publisher(
         attr("publisher", publisher),
     ]))
 }
+
+pub fn set_version_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    ado_type: String,
+    version: String,
+    status: VersionStatus,
+) -> Result<Response, ContractError> {
+    ensure!(
+        ADOContract::default().is_owner_or_operator(deps.storage, info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+    ensure!(
+        semver::Version::parse(&version).is_ok(),
+        ContractError::InvalidADOVersion {
+            msg: Some("Provided version is not valid semver".to_string())
+        }
+    );
+
+    let ado_version = ADOVersion::from_type(ado_type).with_version(version);
+    ensure!(
+        read_code_id(deps.storage, &ado_version).is_ok(),
+        ContractError::InvalidADOVersion {
+            msg: Some("Version not already published".to_string())
+        }
+    );
+
+    let mut res = Response::default();
+    match &status {
+        VersionStatus::Active => {
+            VERSION_STATUS.remove(deps.storage, ado_version.as_str());
+            res = res.add_attribute("action", "restore_version");
+        }
+        VersionStatus::Deprecated { reason } => {
+            ensure!(
+                !reason.trim().is_empty(),
+                ContractError::InvalidADOVersion {
+                    msg: Some("Deprecation reason can't be empty".to_string())
+                }
+            );
+            VERSION_STATUS.save(deps.storage, ado_version.as_str(), &status)?;
+            res = res.add_attributes(vec![
+                attr("action", "deprecate_version"),
+                attr("reason", reason),
+            ]);
+        }
+        VersionStatus::Yanked { reason } => {
+            VERSION_STATUS.save(deps.storage, ado_version.as_str(), &status)?;
+            res = res.add_attribute("action", "yank_version");
+            if let Some(reason) = reason {
+                res = res.add_attribute("reason", reason);
+            }
+        }
+    }
+
+    Ok(res.add_attribute("ado_type", ado_version.into_string()))
+}
+
+/// Validates the versions of a migration path, returning the normalised source and target versions
+fn validate_migration_path(
+    deps: Deps,
+    info: &MessageInfo,
+    ado_type: &str,
+    from: Vec<String>,
+    to: String,
+) -> Result<(Vec<String>, String), ContractError> {
+    ensure!(
+        !from.is_empty(),
+        ContractError::InvalidADOVersion {
+            msg: Some("No source versions provided".to_string())
+        }
+    );
+    let to = semver::Version::parse(&to)
+        .ok()
+        .ok_or(ContractError::InvalidADOVersion {
+            msg: Some("Provided version is not valid semver".to_string()),
+        })?;
+    let target = ADOVersion::from_type(ado_type).with_version(to.to_string());
+    ensure!(
+        read_code_id(deps.storage, &target).is_ok(),
+        ContractError::InvalidADOVersion {
+            msg: Some("Version not already published".to_string())
+        }
+    );
+
+    // The publisher of the target version can declare which versions migrate to it
+    let is_publisher = PUBLISHER
+        .may_load(deps.storage, target.as_str())?
+        .is_some_and(|publisher| publisher == info.sender.as_str());
+    ensure!(
+        is_publisher
+            || ADOContract::default().is_owner_or_operator(deps.storage, info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+
+    let from =
+        from.iter()
+            .map(|version| {
+                let version = semver::Version::parse(version).ok().ok_or(
+                    ContractError::InvalidADOVersion {
+                        msg: Some("Provided version is not valid semver".to_string()),
+                    },
+                )?;
+                ensure!(
+                    version < to,
+                    ContractError::InvalidADOVersion {
+                        msg: Some(format!("Cannot migrate from {version} to {to}"))
+                    }
+                );
+                Ok(version.to_string())
+            })
+            .collect::<Result<Vec<String>, ContractError>>()?;
+    Ok((from, to.to_string()))
+}
+
+pub fn add_migration_path(
+    deps: DepsMut,
+    info: MessageInfo,
+    ado_type: String,
+    from: Vec<String>,
+    to: String,
+    requires_msg: bool,
+) -> Result<Response, ContractError> {
+    let (from, to) = validate_migration_path(deps.as_ref(), &info, &ado_type, from, to)?;
+    for version in &from {
+        MIGRATION_PATHS.save(
+            deps.storage,
+            (&ado_type, version.as_str(), to.as_str()),
+            &requires_msg,
+        )?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "add_migration_path"),
+        attr("ado_type", ado_type),
+        attr("from", from.join(",")),
+        attr("to", to),
+        attr("requires_msg", requires_msg.to_string()),
+    ]))
+}
+
+pub fn remove_migration_path(
+    deps: DepsMut,
+    info: MessageInfo,
+    ado_type: String,
+    from: Vec<String>,
+    to: String,
+) -> Result<Response, ContractError> {
+    let (from, to) = validate_migration_path(deps.as_ref(), &info, &ado_type, from, to)?;
+    for version in &from {
+        MIGRATION_PATHS.remove(deps.storage, (&ado_type, version.as_str(), to.as_str()));
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "remove_migration_path"),
+        attr("ado_type", ado_type),
+        attr("from", from.join(",")),
+        attr("to", to),
+    ]))
+}
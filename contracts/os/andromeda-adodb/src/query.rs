@@ -1,17 +1,20 @@
 use crate::state::{
-    read_code_id, read_latest_code_id, ACTION_FEES, ADO_TYPE, CODE_ID, PUBLISHER,
+    find_migration_path, has_migration_paths, read_code_id, read_latest_code_id,
+    read_version_status, resolve_code_id, ACTION_FEES, ADO_TYPE, CODE_ID, PUBLISHER,
     UNPUBLISHED_CODE_IDS,
 };
 
-use andromeda_std::error::ContractError;
-use andromeda_std::os::adodb::{ADOMetadata, ADOVersion, ActionFee, IsUnpublishedCodeIdResponse};
+use andromeda_std::error::{from_semver, ContractError};
+use andromeda_std::os::adodb::{
+    ADOMetadata, ADOVersion, ADOVersionInfo, ActionFee, IsUnpublishedCodeIdResponse, MigrationStep,
+};
 use cosmwasm_std::{Deps, Order, StdResult, Storage};
 
 use cw_storage_plus::Bound;
 use semver::Version;
 
 pub fn code_id(deps: Deps, key: String) -> Result<u64, ContractError> {
-    let code_id = read_code_id(deps.storage, &ADOVersion::from_string(key))?;
+    let code_id = resolve_code_id(deps.storage, &ADOVersion::from_string(key))?;
     Ok(code_id)
 }
 
@@ -85,6 +88,26 @@ pub fn ado_versions(
     Ok(versions)
 }
 
+pub fn ado_versions_with_status(
+    storage: &dyn Storage,
+    ado_type: &str,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ADOVersionInfo>, ContractError> {
+    let versions = ado_versions(storage, ado_type, start_after, limit)?;
+    versions
+        .into_iter()
+        .map(|version| {
+            let ado_version = ADOVersion::from_string(version);
+            Ok(ADOVersionInfo {
+                code_id: read_code_id(storage, &ado_version)?,
+                status: read_version_status(storage, &ado_version),
+                version: ado_version.into_string(),
+            })
+        })
+        .collect()
+}
+
 // pub fn unpublished_ado_versions(
 //     storage: &dyn Storage,
 //     ado_type: &str,
@@ -127,3 +150,18 @@ pub fn action_fee_by_code_id(
     let ado_version = ADO_TYPE.load(deps.storage, &code_id.to_string())?;
     Ok(ACTION_FEES.may_load(deps.storage, &(ado_version.get_type(), action))?)
 }
+
+pub fn migration_path(
+    deps: Deps,
+    ado_type: String,
+    from: String,
+    to: String,
+) -> Result<Vec<MigrationStep>, ContractError> {
+    let from: Version = from.parse().map_err(from_semver)?;
+    let to: Version = to.parse().map_err(from_semver)?;
+    // ADO types without registered migrations do not restrict their upgrades
+    if !has_migration_paths(deps.storage, &ado_type) {
+        return Ok(vec![]);
+    }
+    find_migration_path(deps.storage, &ado_type, &from, &to)
+}
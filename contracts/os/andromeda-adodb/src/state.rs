@@ -1,10 +1,11 @@
 use andromeda_std::{
-    error::ContractError,
-    os::adodb::{ADOVersion, ActionFee},
+    error::{from_semver, ContractError},
+    os::adodb::{ADOVersion, ActionFee, MigrationStep, VersionStatus},
 };
-use cosmwasm_std::{ensure, Api, StdResult, Storage};
-use cw_storage_plus::Map;
-use semver::Version;
+use cosmwasm_std::{ensure, Api, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+use semver::{Version, VersionReq};
+use std::collections::{HashMap, VecDeque};
 
 /// Stores a mapping from an ADO type/version to its code ID
 pub const CODE_ID: Map<&str, u64> = Map::new("code_id");
@@ -20,6 +21,10 @@ pub const ADO_TYPE: Map<&str, ADOVersion> = Map::new("ado_type");
 pub const PUBLISHER: Map<&str, String> = Map::new("publisher");
 /// Stores a mapping from an (ADO,Action) to its action fees
 pub const ACTION_FEES: Map<&(String, String), ActionFee> = Map::new("action_fees");
+/// Stores the status of a published ADO version, versions without a status are active
+pub const VERSION_STATUS: Map<&str, VersionStatus> = Map::new("version_status");
+/// Stores the registered migrations for an (ADO type, source version, target version), the value is whether a migrate message is required
+pub const MIGRATION_PATHS: Map<(&str, &str, &str), bool> = Map::new("migration_paths");
 
 pub fn store_code_id(
     storage: &mut dyn Storage,
@@ -135,6 +140,97 @@ pub fn read_code_id(storage: &dyn Storage, ado_version: &ADOVersion) -> StdResul
     }
 }
 
+pub fn read_version_status(storage: &dyn Storage, ado_version: &ADOVersion) -> VersionStatus {
+    VERSION_STATUS
+        .may_load(storage, ado_version.as_str())
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+fn is_yanked(storage: &dyn Storage, ado_version: &ADOVersion) -> bool {
+    matches!(
+        read_version_status(storage, ado_version),
+        VersionStatus::Yanked { .. }
+    )
+}
+
+/// Gets all published versions of an ADO type along with their code IDs
+pub fn read_published_versions(
+    storage: &dyn Storage,
+    ado_type: &str,
+) -> StdResult<Vec<(Version, u64)>> {
+    // All versions have @ as starting point, A has a higher ascii value than @
+    let start = format!("{ado_type}@");
+    let end = format!("{ado_type}A");
+    CODE_ID
+        .range(
+            storage,
+            Some(Bound::inclusive(start.as_str())),
+            Some(Bound::exclusive(end.as_str())),
+            Order::Ascending,
+        )
+        .filter_map(|item| match item {
+            Ok((key, code_id)) => ADOVersion::from_string(key)
+                .get_version()
+                .parse::<Version>()
+                .ok()
+                .map(|version| Ok((version, code_id))),
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+/// Resolves the code ID used to instantiate an ADO version.
+///
+/// The version may be exact, `latest` or a semver range such as `^2.1`.
+/// Yanked versions cannot be instantiated and are skipped when resolving `latest` or a range.
+pub fn resolve_code_id(
+    storage: &dyn Storage,
+    ado_version: &ADOVersion,
+) -> Result<u64, ContractError> {
+    let version = ado_version.get_version();
+    if version != "latest" && Version::parse(&version).is_ok() {
+        ensure!(
+            !is_yanked(storage, ado_version),
+            ContractError::InvalidADOVersion {
+                msg: Some(format!("{} has been yanked", ado_version.as_str()))
+            }
+        );
+        return Ok(CODE_ID.load(storage, ado_version.as_str())?);
+    }
+
+    let requirement = if version == "latest" {
+        let (latest_version, code_id) = read_latest_code_id(storage, ado_version.get_type())?;
+        if !is_yanked(storage, &ado_version.with_version(latest_version)) {
+            return Ok(code_id);
+        }
+        VersionReq::STAR
+    } else {
+        VersionReq::parse(&version).map_err(|_| ContractError::InvalidADOVersion {
+            msg: Some(format!("Invalid version requirement {version}")),
+        })?
+    };
+
+    read_published_versions(storage, &ado_version.get_type())?
+        .into_iter()
+        .filter(|(published_version, _)| {
+            requirement.matches(published_version)
+                && !is_yanked(
+                    storage,
+                    &ado_version.with_version(published_version.to_string()),
+                )
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, code_id)| code_id)
+        .ok_or(ContractError::InvalidADOVersion {
+            msg: Some(format!(
+                "No published version of {} matches {version}",
+                ado_version.get_type()
+            )),
+        })
+}
+
 pub fn read_latest_code_id(storage: &dyn Storage, ado_type: String) -> StdResult<(String, u64)> {
     LATEST_VERSION.load(storage, &ado_type)
 }
@@ -146,7 +242,7 @@ pub fn save_action_fees(
     fees: Vec<ActionFee>,
 ) -> Result<(), ContractError> {
     for action_fee in fees {
-        action_fee.validate_asset(api)?;
+        action_fee.validate(api)?;
         ACTION_FEES.save(
             storage,
             &(ado_version.get_type(), action_fee.clone().action),
@@ -156,3 +252,70 @@ pub fn save_action_fees(
 
     Ok(())
 }
+
+/// Whether any migrations are registered for the ADO type
+pub fn has_migration_paths(storage: &dyn Storage, ado_type: &str) -> bool {
+    MIGRATION_PATHS
+        .sub_prefix(ado_type)
+        .keys_raw(storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+}
+
+/// Finds the shortest sequence of registered migrations between two versions of an ADO type.
+/// Migrations to versions that have since been unpublished are ignored.
+pub fn find_migration_path(
+    storage: &dyn Storage,
+    ado_type: &str,
+    from: &Version,
+    to: &Version,
+) -> Result<Vec<MigrationStep>, ContractError> {
+    let ado_version = ADOVersion::from_type(ado_type);
+    let target = to.to_string();
+    // Maps each reached version to the version it was reached from
+    let mut previous: HashMap<String, (String, bool)> = HashMap::new();
+    let mut queue = VecDeque::from([from.to_string()]);
+
+    while let Some(current) = queue.pop_front() {
+        if current == target {
+            break;
+        }
+        for item in MIGRATION_PATHS.prefix((ado_type, current.as_str())).range(
+            storage,
+            None,
+            None,
+            Order::Ascending,
+        ) {
+            let (next, requires_msg) = item?;
+            let next_version = Version::parse(&next).map_err(from_semver)?;
+            if next_version > *to
+                || previous.contains_key(&next)
+                || !CODE_ID.has(storage, ado_version.with_version(&next).as_str())
+            {
+                continue;
+            }
+            previous.insert(next.clone(), (current.clone(), requires_msg));
+            queue.push_back(next);
+        }
+    }
+
+    let mut steps = vec![];
+    let mut current = target;
+    while current != from.to_string() {
+        let (prev, requires_msg) =
+            previous
+                .get(&current)
+                .cloned()
+                .ok_or(ContractError::InvalidMigration {
+                    prev: from.to_string(),
+                })?;
+        steps.push(MigrationStep {
+            code_id: CODE_ID.load(storage, ado_version.with_version(&current).as_str())?,
+            version: current,
+            requires_msg,
+        });
+        current = prev;
+    }
+    steps.reverse();
+    Ok(steps)
+}
@@ -1,12 +1,15 @@
 #[cfg(test)]
 use andromeda_std::testing::mock_querier::{mock_dependencies_custom, MOCK_KERNEL_CONTRACT};
-use cosmwasm_std::{from_json, Uint128};
+use cosmwasm_std::{from_json, Deps, Uint128};
 
 use crate::contract::{execute, instantiate, query};
 use crate::state::{ACTION_FEES, CODE_ID, LATEST_VERSION, PUBLISHER, UNPUBLISHED_CODE_IDS};
 
 use andromeda_std::error::ContractError;
-use andromeda_std::os::adodb::{ADOVersion, ActionFee, ExecuteMsg, InstantiateMsg, QueryMsg};
+use andromeda_std::os::adodb::{
+    ADOVersion, ADOVersionInfo, ActionFee, ExecuteMsg, InstantiateMsg, MigrationStep, QueryMsg,
+    VersionStatus,
+};
 
 use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
 use rstest::rstest;
@@ -52,12 +55,14 @@ fn test_publish() {
             amount: Uint128::from(1u128),
             asset: format!("cw20:{}", cw20_addr),
             receiver: None,
+            ..Default::default()
         },
         ActionFee {
             action: "action2".to_string(),
             amount: Uint128::from(2u128),
             asset: "native:uusd".to_string(),
             receiver: None,
+            ..Default::default()
         },
     ];
 
@@ -214,12 +219,14 @@ fn test_unpublish() {
             amount: Uint128::from(1u128),
             asset: format!("cw20:{}", cw20_addr),
             receiver: None,
+            ..Default::default()
         },
         ActionFee {
             action: "action2".to_string(),
             amount: Uint128::from(2u128),
             asset: "native:uusd".to_string(),
             receiver: None,
+            ..Default::default()
         },
     ];
     let ado_version = ADOVersion::from_type("ado_type").with_version("0.1.0");
@@ -495,12 +502,14 @@ fn test_update_action_fees() {
             amount: Uint128::from(1u128),
             asset: format!("cw20:{}", cw20_addr),
             receiver: None,
+            ..Default::default()
         },
         ActionFee {
             action: "action2".to_string(),
             amount: Uint128::from(2u128),
             asset: "native:uusd".to_string(),
             receiver: None,
+            ..Default::default()
         },
     ];
 
@@ -849,3 +858,306 @@ fn test_version_updates(
         .unwrap();
     assert_eq!(code_id, 2u64);
 }
+
+#[test]
+fn test_version_ranges_and_status() {
+    let owner = String::from("owner");
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner_addr = deps.api.addr_make(owner.as_str());
+    let info = message_info(&owner_addr, &[]);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner_addr, &[]),
+        InstantiateMsg {
+            kernel_address: MOCK_KERNEL_CONTRACT.to_string(),
+            owner: None,
+        },
+    )
+    .unwrap();
+
+    for (code_id, version) in [(1, "2.0.0"), (2, "2.1.0"), (3, "2.1.3"), (4, "3.0.0")] {
+        let msg = ExecuteMsg::Publish {
+            ado_type: "splitter".to_string(),
+            version: version.to_string(),
+            code_id,
+            action_fees: None,
+            publisher: Some(owner.clone()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    fn query_code_id(deps: Deps, key: &str) -> Result<u64, ContractError> {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::CodeId {
+                key: key.to_string(),
+            },
+        )?;
+        Ok(from_json(res)?)
+    }
+
+    assert_eq!(query_code_id(deps.as_ref(), "splitter@^2.1").unwrap(), 3);
+    assert_eq!(query_code_id(deps.as_ref(), "splitter@~2.0").unwrap(), 1);
+    assert_eq!(
+        query_code_id(deps.as_ref(), "splitter@>=2.0.0, <2.1.3").unwrap(),
+        2
+    );
+    assert_eq!(query_code_id(deps.as_ref(), "splitter").unwrap(), 4);
+    assert!(query_code_id(deps.as_ref(), "splitter@^4").is_err());
+    assert!(query_code_id(deps.as_ref(), "splitter@not_a_range").is_err());
+
+    // Only the owner or an operator can change a version's status
+    let msg = ExecuteMsg::DeprecateVersion {
+        ado_type: "splitter".to_string(),
+        version: "2.1.3".to_string(),
+        reason: "Use 3.0.0".to_string(),
+    };
+    let attacker = deps.api.addr_make("attacker");
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&attacker, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Deprecated versions can still be resolved
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(query_code_id(deps.as_ref(), "splitter@^2.1").unwrap(), 3);
+    assert_eq!(query_code_id(deps.as_ref(), "splitter@2.1.3").unwrap(), 3);
+
+    // Yanked versions can't be instantiated and are skipped by ranges and latest
+    for version in ["2.1.3", "3.0.0"] {
+        let msg = ExecuteMsg::YankVersion {
+            ado_type: "splitter".to_string(),
+            version: version.to_string(),
+            reason: None,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+    assert!(query_code_id(deps.as_ref(), "splitter@2.1.3").is_err());
+    assert_eq!(query_code_id(deps.as_ref(), "splitter@^2.1").unwrap(), 2);
+    assert_eq!(query_code_id(deps.as_ref(), "splitter").unwrap(), 2);
+    assert_eq!(query_code_id(deps.as_ref(), "splitter@latest").unwrap(), 2);
+
+    // Yanked code IDs remain registered so existing instances can migrate to them
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::ADOType { code_id: 3 }).unwrap();
+    let ado_version: Option<ADOVersion> = from_json(res).unwrap();
+    assert_eq!(ado_version.unwrap().as_str(), "splitter@2.1.3");
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::ADOVersionsWithStatus {
+            ado_type: "splitter".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let versions: Vec<ADOVersionInfo> = from_json(res).unwrap();
+    assert_eq!(
+        versions,
+        vec![
+            ADOVersionInfo {
+                version: "splitter@3.0.0".to_string(),
+                code_id: 4,
+                status: VersionStatus::Yanked { reason: None },
+            },
+            ADOVersionInfo {
+                version: "splitter@2.1.3".to_string(),
+                code_id: 3,
+                status: VersionStatus::Yanked { reason: None },
+            },
+            ADOVersionInfo {
+                version: "splitter@2.1.0".to_string(),
+                code_id: 2,
+                status: VersionStatus::Active,
+            },
+            ADOVersionInfo {
+                version: "splitter@2.0.0".to_string(),
+                code_id: 1,
+                status: VersionStatus::Active,
+            },
+        ]
+    );
+
+    // Restoring a version makes it available again
+    let msg = ExecuteMsg::RestoreVersion {
+        ado_type: "splitter".to_string(),
+        version: "3.0.0".to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(query_code_id(deps.as_ref(), "splitter").unwrap(), 4);
+
+    // Unpublished versions can't be given a status
+    let msg = ExecuteMsg::YankVersion {
+        ado_type: "splitter".to_string(),
+        version: "1.0.0".to_string(),
+        reason: Some("Broken".to_string()),
+    };
+    let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidADOVersion {
+            msg: Some("Version not already published".to_string())
+        }
+    );
+}
+
+#[test]
+fn test_migration_path() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let publisher = deps.api.addr_make("publisher");
+    let info = message_info(&owner, &[]);
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        InstantiateMsg {
+            kernel_address: MOCK_KERNEL_CONTRACT.to_string(),
+            owner: None,
+        },
+    )
+    .unwrap();
+
+    for (code_id, version) in [(1, "1.0.0"), (2, "1.1.0"), (3, "1.2.0"), (4, "2.0.0")] {
+        let msg = ExecuteMsg::Publish {
+            ado_type: "splitter".to_string(),
+            version: version.to_string(),
+            code_id,
+            action_fees: None,
+            publisher: Some(publisher.to_string()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let add_path = |from: &[&str], to: &str, requires_msg: bool| ExecuteMsg::AddMigrationPath {
+        ado_type: "splitter".to_string(),
+        from: from.iter().map(|version| version.to_string()).collect(),
+        to: to.to_string(),
+        requires_msg,
+    };
+
+    let attacker = deps.api.addr_make("attacker");
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&attacker, &[]),
+        add_path(&["1.0.0"], "1.1.0", false),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Migrations can't downgrade
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        add_path(&["1.2.0"], "1.1.0", false),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidADOVersion {
+            msg: Some("Cannot migrate from 1.2.0 to 1.1.0".to_string())
+        }
+    );
+
+    // Both the owner and the publisher of the target version can add paths
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        add_path(&["1.0.0"], "1.1.0", false),
+    )
+    .unwrap();
+    let publisher_info = message_info(&publisher, &[]);
+    for msg in [
+        add_path(&["1.1.0"], "1.2.0", true),
+        add_path(&["1.1.0", "1.2.0"], "2.0.0", false),
+    ] {
+        execute(deps.as_mut(), env.clone(), publisher_info.clone(), msg).unwrap();
+    }
+
+    let query_path = |deps: Deps, from: &str, to: &str| {
+        query(
+            deps,
+            mock_env(),
+            QueryMsg::MigrationPath {
+                ado_type: "splitter".to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+        )
+        .map(|res| from_json::<Vec<MigrationStep>>(res).unwrap())
+    };
+
+    let steps = query_path(deps.as_ref(), "1.0.0", "2.0.0").unwrap();
+    assert_eq!(
+        steps,
+        vec![
+            MigrationStep {
+                version: "1.1.0".to_string(),
+                code_id: 2,
+                requires_msg: false,
+            },
+            MigrationStep {
+                version: "2.0.0".to_string(),
+                code_id: 4,
+                requires_msg: false,
+            },
+        ]
+    );
+
+    // Removing the shortcut forces the migration through 1.2.0
+    let msg = ExecuteMsg::RemoveMigrationPath {
+        ado_type: "splitter".to_string(),
+        from: vec!["1.1.0".to_string()],
+        to: "2.0.0".to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let steps = query_path(deps.as_ref(), "1.0.0", "2.0.0").unwrap();
+    assert_eq!(
+        steps
+            .iter()
+            .map(|step| (step.code_id, step.requires_msg))
+            .collect::<Vec<_>>(),
+        vec![(2, false), (3, true), (4, false)]
+    );
+
+    // Unpublished versions are skipped
+    let msg = ExecuteMsg::Unpublish {
+        ado_type: "splitter".to_string(),
+        version: "1.2.0".to_string(),
+    };
+    execute(deps.as_mut(), env, info, msg).unwrap();
+    let err = query_path(deps.as_ref(), "1.0.0", "2.0.0").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidMigration {
+            prev: "1.0.0".to_string()
+        }
+    );
+
+    // ADO types without registered migrations have no path
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::MigrationPath {
+            ado_type: "crowdfund".to_string(),
+            from: "1.0.0".to_string(),
+            to: "2.0.0".to_string(),
+        },
+    )
+    .unwrap();
+    assert!(from_json::<Vec<MigrationStep>>(res).unwrap().is_empty());
+}
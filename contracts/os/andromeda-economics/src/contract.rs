@@ -68,7 +68,11 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Deposit { address } => execute::deposit_native(deps, info, address),
-        ExecuteMsg::PayFee { payee, action } => execute::pay_fee(deps, env, info, payee, action),
+        ExecuteMsg::PayFee {
+            payee,
+            action,
+            funds,
+        } => execute::pay_fee(deps, env, info, payee, action, funds),
         ExecuteMsg::Withdraw { amount, asset } => {
             execute::withdraw_native(deps, info, amount, asset)
         }
@@ -76,6 +80,17 @@ pub fn execute(
             execute::withdraw_cw20(deps, info, amount, asset)
         }
         ExecuteMsg::Receive(cw20msg) => cw20_receive(deps, env, info, cw20msg),
+        ExecuteMsg::RegisterSponsorship {
+            ados,
+            actions,
+            users,
+            user_caps,
+            expiration,
+        } => execute::register_sponsorship(
+            deps, env, info, ados, actions, users, user_caps, expiration,
+        ),
+        ExecuteMsg::RemoveSponsorship {} => execute::remove_sponsorship(deps, info),
+        ExecuteMsg::ClaimRevenue { assets } => execute::claim_revenue(deps, info, assets),
         // Base message
         ExecuteMsg::Ownership(ownership_message) => {
             ADOContract::default().execute_ownership(deps, env, info, ownership_message)
@@ -110,6 +125,28 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
         QueryMsg::Balance { address, asset } => {
             Ok(to_json_binary(&query::balance(deps, address, asset)?)?)
         }
+        QueryMsg::Sponsorship { sponsor } => encode_binary(&query::sponsorship(deps, sponsor)?),
+        QueryMsg::Revenue { recipient } => encode_binary(&query::revenue(deps, recipient)?),
+        QueryMsg::ADOTypeRevenue { ado_type, action } => {
+            encode_binary(&query::ado_type_revenue(deps, ado_type, action)?)
+        }
+        QueryMsg::RevenueReport {
+            recipient,
+            start,
+            end,
+            bucket_size,
+        } => encode_binary(&query::revenue_report(
+            deps,
+            recipient,
+            start,
+            end,
+            bucket_size,
+        )?),
+        QueryMsg::SponsoredAmount {
+            sponsor,
+            user,
+            asset,
+        } => encode_binary(&query::sponsored_amount(deps, sponsor, user, asset)?),
         // Base queries
         QueryMsg::Version {} => encode_binary(&ADOContract::default().query_version(deps)?),
         QueryMsg::Type {} => encode_binary(&ADOContract::default().query_type(deps)?),
@@ -1,14 +1,24 @@
 use andromeda_std::{
-    ado_contract::ADOContract, amp::AndrAddr, common::reply::ReplyId, error::ContractError,
-    os::aos_querier::AOSQuerier,
+    ado_contract::ADOContract,
+    amp::AndrAddr,
+    common::{expiration::Expiry, reply::ReplyId, Milliseconds},
+    error::ContractError,
+    os::{
+        adodb::{validate_asset_string, ActionFee},
+        aos_querier::AOSQuerier,
+        economics::{AssetCap, Sponsorship},
+    },
 };
 use cosmwasm_std::{
-    attr, coin, ensure, to_json_binary, Addr, BankMsg, CosmosMsg, DepsMut, Empty, Env, MessageInfo,
-    Response, Storage, SubMsg, Uint128, WasmMsg,
+    attr, coin, ensure, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, Response, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
-use crate::state::BALANCES;
+use crate::state::{
+    ACTION_REVENUE, BALANCES, DAILY_REVENUE, DAY_MILLISECONDS, FREE_CALLS, RECIPIENT_REVENUE,
+    SPONSORED_AMOUNTS, SPONSORS, SPONSORSHIPS, UNCLAIMED_REVENUE,
+};
 
 pub fn cw20_deposit(
     deps: DepsMut,
@@ -116,17 +126,165 @@ pub(crate) fn spend_balance(
     Ok(remainder)
 }
 
+/// Records a call by the payee, returning whether it is covered by the fee's free calls for the current period
+fn use_free_call(
+    storage: &mut dyn Storage,
+    env: &Env,
+    payee: &Addr,
+    ado_type: &str,
+    fee: &ActionFee,
+) -> Result<bool, ContractError> {
+    let Some(free_calls) = &fee.free_calls else {
+        return Ok(false);
+    };
+    let now = Milliseconds::from_nanos(env.block.time.nanos());
+    let key = (payee, ado_type, fee.action.as_str());
+    let (period_start, used) = match FREE_CALLS.may_load(storage, key)? {
+        Some((period_start, used)) if now < period_start.plus_milliseconds(free_calls.period) => {
+            (period_start, used)
+        }
+        _ => (now, 0),
+    };
+    if used >= free_calls.calls {
+        return Ok(false);
+    }
+    FREE_CALLS.save(storage, key, &(period_start, used + 1))?;
+    Ok(true)
+}
+
+/// Gets the discount of the first fee tier whose address list permits the payee
+fn tier_discount(deps: Deps, env: &Env, payee: &Addr, fee: &ActionFee) -> Decimal {
+    fee.tiers
+        .iter()
+        .find(|tier| {
+            AOSQuerier::get_permission(&deps.querier, &tier.address_list, payee.as_str())
+                .is_ok_and(|permission| permission.is_permissioned(env, true))
+        })
+        .map_or(Decimal::zero(), |tier| tier.discount)
+}
+
+/// Calculates the amount owed after adding the percentage of the funds sent in the fee's asset and applying the discount
+///
+/// The percentage is converted to alternative assets at the ratio of their fixed amounts,
+/// returns `None` if it cannot be converted as the fee's fixed amount is zero
+fn fee_amount(
+    fee: &ActionFee,
+    amount: Uint128,
+    funds: &[Coin],
+    discount: Decimal,
+) -> Option<Uint128> {
+    let percent_fee = fee.percent_of_funds.map_or(Uint128::zero(), |percent| {
+        funds
+            .iter()
+            .filter(|coin| format!("native:{}", coin.denom) == fee.asset)
+            .map(|coin| coin.amount)
+            .sum::<Uint128>()
+            .mul_floor(percent)
+    });
+    let percent_fee = if percent_fee.is_zero() || amount == fee.amount {
+        percent_fee
+    } else if fee.amount.is_zero() {
+        return None;
+    } else {
+        percent_fee.multiply_ratio(amount, fee.amount)
+    };
+    Some((amount + percent_fee).mul_floor(Decimal::one() - discount))
+}
+
+/// Finds a sponsor of the ADO covering the action for the payee, with a sufficient balance and remaining cap
+#[allow(clippy::too_many_arguments)]
+fn find_sponsor(
+    deps: Deps,
+    env: &Env,
+    ado: &Addr,
+    action: &str,
+    payee: &Addr,
+    fee_asset: &str,
+    asset: &str,
+    amount: Uint128,
+) -> Result<Option<Addr>, ContractError> {
+    for sponsor in SPONSORS
+        .prefix(ado)
+        .keys(deps.storage, None, None, Order::Ascending)
+    {
+        let sponsor = sponsor?;
+        let sponsorship = SPONSORSHIPS.load(deps.storage, &sponsor)?;
+        let is_expired = sponsorship
+            .expiration
+            .is_some_and(|expiration| expiration.is_expired(&env.block));
+        let is_excluded = sponsorship
+            .actions
+            .as_ref()
+            .is_some_and(|actions| !actions.iter().any(|a| a == action))
+            || sponsorship
+                .users
+                .as_ref()
+                .is_some_and(|users| !users.contains(payee));
+        if is_expired || is_excluded {
+            continue;
+        }
+
+        if let Some(cap) = sponsorship
+            .user_caps
+            .iter()
+            .find(|cap| cap.asset == fee_asset)
+        {
+            let sponsored = SPONSORED_AMOUNTS
+                .may_load(deps.storage, (&sponsor, payee, fee_asset))?
+                .unwrap_or_default();
+            if sponsored + amount > cap.amount {
+                continue;
+            }
+        }
+
+        let balance = BALANCES
+            .load(deps.storage, (sponsor.clone(), asset.to_string()))
+            .unwrap_or_default();
+        if balance >= amount {
+            return Ok(Some(sponsor));
+        }
+    }
+    Ok(None)
+}
+
+/// Adds a collected fee to the recipient's unclaimed revenue and the revenue ledgers
+fn record_revenue(
+    storage: &mut dyn Storage,
+    env: &Env,
+    recipient: &Addr,
+    ado_type: &str,
+    action: &str,
+    asset: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let add =
+        |total: Option<Uint128>| -> StdResult<Uint128> { Ok(total.unwrap_or_default() + amount) };
+    UNCLAIMED_REVENUE.update(storage, (recipient, asset), add)?;
+    RECIPIENT_REVENUE.update(storage, (recipient, asset), add)?;
+    ACTION_REVENUE.update(storage, (ado_type, action, asset), add)?;
+    let day = Milliseconds::from_nanos(env.block.time.nanos()).milliseconds() / DAY_MILLISECONDS;
+    DAILY_REVENUE.update(storage, (recipient, day, asset), add)?;
+    Ok(())
+}
+
 /// Charges a fee depending on the sending ADO and the action being performed.
 /// Sender must be an ADO contract else this will error.
 ///
+/// Collected fees are added to the unclaimed revenue of the fee's receiver, or the ADO publisher if there is no receiver.
+///
+/// Calls covered by the fee's free calls are not charged, otherwise the discount of the first matching tier is applied.
+/// The fee is paid in the first accepted asset the payer has a sufficient balance of.
+///
 /// Fees are charged in the following order:
-/// 1. Payee
+/// 1. A sponsor of the sending ADO
+/// 2. Payee
 pub fn pay_fee(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     payee: Addr,
     action: String,
+    funds: Vec<Coin>,
 ) -> Result<Response, ContractError> {
     let mut resp = Response::default();
 
@@ -136,7 +294,7 @@ pub fn pay_fee(
         attr("payee", payee.to_string()),
     ];
 
-    let contract_info = deps.querier.query_wasm_contract_info(info.sender);
+    let contract_info = deps.querier.query_wasm_contract_info(info.sender.clone());
     if let Ok(contract_info) = contract_info {
         let code_id = contract_info.code_id;
         let adodb_addr = ADOContract::default().get_adodb_address(deps.storage, &deps.querier)?;
@@ -159,7 +317,67 @@ pub fn pay_fee(
             None => Ok(resp),
             Some(fee) => {
                 fee.validate_asset(deps.api)?;
-                let asset = fee.get_asset_string()?;
+
+                if use_free_call(deps.storage, &env, &payee, &ado_type, &fee)? {
+                    return Ok(resp.add_attribute("free_call", "true"));
+                }
+
+                let discount = tier_discount(deps.as_ref(), &env, &payee, &fee);
+                if !discount.is_zero() {
+                    resp = resp.add_attribute("fee_discount", discount.to_string());
+                }
+
+                let mut accepted_assets = vec![];
+                for (fee_asset, amount) in std::iter::once((fee.asset.clone(), fee.amount)).chain(
+                    fee.alternative_assets
+                        .iter()
+                        .map(|fee_asset| (fee_asset.asset.clone(), fee_asset.amount)),
+                ) {
+                    let Some(amount) = fee_amount(&fee, amount, &funds, discount) else {
+                        continue;
+                    };
+                    let asset = fee_asset
+                        .split_once(':')
+                        .map(|(_, asset)| asset.to_string())
+                        .ok_or(ContractError::InvalidAsset {
+                            asset: fee_asset.clone(),
+                        })?;
+                    accepted_assets.push((fee_asset, asset, amount));
+                }
+
+                // Sponsors pay before the payee, each in the first accepted asset they can afford
+                let mut payment = None;
+                for (fee_asset, asset, amount) in &accepted_assets {
+                    let sponsor = find_sponsor(
+                        deps.as_ref(),
+                        &env,
+                        &info.sender,
+                        &action,
+                        &payee,
+                        fee_asset,
+                        asset,
+                        *amount,
+                    )?;
+                    if let Some(sponsor) = sponsor {
+                        payment = Some((sponsor, fee_asset.clone(), asset.clone(), *amount));
+                        break;
+                    }
+                }
+                if payment.is_none() {
+                    payment = accepted_assets
+                        .into_iter()
+                        .find_map(|(fee_asset, asset, amount)| {
+                            let balance = BALANCES
+                                .load(deps.storage, (payee.clone(), asset.clone()))
+                                .unwrap_or_default();
+                            (balance >= amount).then(|| (payee.clone(), fee_asset, asset, amount))
+                        });
+                }
+                let (payer, fee_asset, asset, amount) =
+                    payment.ok_or(ContractError::InsufficientFunds {})?;
+                if amount.is_zero() {
+                    return Ok(resp);
+                }
 
                 // Removing ADO/App payments temporarily pending discussion
                 // Charge ADO first
@@ -181,9 +399,18 @@ pub fn pay_fee(
 
                 // Next charge the payee
                 // if remainder > Uint128::zero() {
-                let remainder = spend_balance(deps.storage, &payee, asset.to_string(), fee.amount)?;
+                let remainder = spend_balance(deps.storage, &payer, asset.to_string(), amount)?;
                 // }
 
+                if payer != payee {
+                    SPONSORED_AMOUNTS.update(
+                        deps.storage,
+                        (&payer, &payee, fee_asset.as_str()),
+                        |sponsored| -> StdResult<_> { Ok(sponsored.unwrap_or_default() + amount) },
+                    )?;
+                    resp = resp.add_attribute("sponsor", payer.to_string());
+                }
+
                 // If balance remaining then not enough funds to pay fee
                 ensure!(
                     remainder == Uint128::zero(),
@@ -200,20 +427,18 @@ pub fn pay_fee(
                     )?;
                     deps.api.addr_validate(&publisher)?
                 };
-                let receiver_balance = BALANCES
-                    .load(
-                        deps.as_ref().storage,
-                        (recipient.clone(), asset.to_string()),
-                    )
-                    .unwrap_or_default();
-                BALANCES.save(
+                record_revenue(
                     deps.storage,
-                    (recipient.clone(), asset.to_string()),
-                    &(receiver_balance + fee.amount),
+                    &env,
+                    &recipient,
+                    &ado_type,
+                    &action,
+                    &fee_asset,
+                    amount,
                 )?;
 
                 resp = resp
-                    .add_attribute("paid_fee", format!("{}{}", fee.amount, fee.asset))
+                    .add_attribute("paid_fee", format!("{}{}", amount, fee_asset))
                     .add_attribute("fee_recipient", recipient.to_string());
                 Ok(resp)
             }
@@ -323,3 +548,155 @@ pub fn withdraw_cw20(
 
     Ok(resp)
 }
+
+#[allow(clippy::too_many_arguments)]
+pub fn register_sponsorship(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ados: Vec<AndrAddr>,
+    actions: Option<Vec<String>>,
+    users: Option<Vec<AndrAddr>>,
+    user_caps: Vec<AssetCap>,
+    expiration: Option<Expiry>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        !ados.is_empty(),
+        ContractError::InvalidParameter {
+            error: Some("No ADOs provided".to_string())
+        }
+    );
+    let ados = ados
+        .iter()
+        .map(|ado| {
+            let ado = ado.get_raw_address(&deps.as_ref())?;
+            // Only the owner of an ADO can sponsor its fees
+            let owner = AOSQuerier::ado_owner_getter(&deps.querier, &ado)?;
+            ensure!(owner == info.sender, ContractError::Unauthorized {});
+            Ok(ado)
+        })
+        .collect::<Result<Vec<Addr>, ContractError>>()?;
+    let users = users
+        .map(|users| {
+            users
+                .iter()
+                .map(|user| user.get_raw_address(&deps.as_ref()))
+                .collect::<Result<Vec<Addr>, ContractError>>()
+        })
+        .transpose()?;
+    for cap in &user_caps {
+        validate_asset_string(deps.api, &cap.asset)?;
+    }
+    let expiration = expiration.map(|expiration| expiration.get_time(&env.block));
+    ensure!(
+        !expiration.is_some_and(|expiration| expiration.is_expired(&env.block)),
+        ContractError::ExpirationInPast {}
+    );
+
+    clear_sponsorship(deps.storage, &info.sender)?;
+    for ado in &ados {
+        SPONSORS.save(deps.storage, (ado, &info.sender), &Empty {})?;
+    }
+    SPONSORSHIPS.save(
+        deps.storage,
+        &info.sender,
+        &Sponsorship {
+            ados,
+            actions,
+            users,
+            user_caps,
+            expiration,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "register_sponsorship"),
+        attr("sponsor", info.sender),
+    ]))
+}
+
+pub fn remove_sponsorship(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure!(
+        SPONSORSHIPS.has(deps.storage, &info.sender),
+        ContractError::InvalidParameter {
+            error: Some("No sponsorship registered".to_string())
+        }
+    );
+    clear_sponsorship(deps.storage, &info.sender)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "remove_sponsorship"),
+        attr("sponsor", info.sender),
+    ]))
+}
+
+/// Removes the sponsor's sponsorship and its ADO index entries
+fn clear_sponsorship(storage: &mut dyn Storage, sponsor: &Addr) -> Result<(), ContractError> {
+    if let Some(sponsorship) = SPONSORSHIPS.may_load(storage, sponsor)? {
+        for ado in &sponsorship.ados {
+            SPONSORS.remove(storage, (ado, sponsor));
+        }
+        SPONSORSHIPS.remove(storage, sponsor);
+    }
+    Ok(())
+}
+
+/// Sends the sender's unclaimed revenue for the given assets, or for all assets if none are provided
+pub fn claim_revenue(
+    deps: DepsMut,
+    info: MessageInfo,
+    assets: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let unclaimed = match assets {
+        // Each asset is only paid out once, however often it is listed
+        Some(mut assets) => {
+            assets.sort();
+            assets.dedup();
+            assets
+                .into_iter()
+                .map(|asset| {
+                    let amount = UNCLAIMED_REVENUE
+                        .may_load(deps.storage, (&info.sender, asset.as_str()))?
+                        .unwrap_or_default();
+                    Ok((asset, amount))
+                })
+                .collect::<StdResult<Vec<(String, Uint128)>>>()?
+        }
+        None => UNCLAIMED_REVENUE
+            .prefix(&info.sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(String, Uint128)>>>()?,
+    };
+
+    let mut resp = Response::default().add_attributes(vec![
+        attr("action", "claim_revenue"),
+        attr("recipient", info.sender.to_string()),
+    ]);
+    let mut native_funds = vec![];
+    for (asset, amount) in unclaimed {
+        if amount.is_zero() {
+            continue;
+        }
+        match asset.split_once(':') {
+            Some(("native", denom)) => native_funds.push(coin(amount.u128(), denom)),
+            Some(("cw20", address)) => {
+                resp = resp.add_submessage(cw20_withdraw_msg(amount, address, info.sender.clone()))
+            }
+            _ => return Err(ContractError::InvalidAsset { asset }),
+        }
+        UNCLAIMED_REVENUE.remove(deps.storage, (&info.sender, asset.as_str()));
+        resp = resp.add_attribute("claimed", format!("{amount}{asset}"));
+    }
+    ensure!(
+        !native_funds.is_empty() || !resp.messages.is_empty(),
+        ContractError::InsufficientFunds {}
+    );
+
+    if !native_funds.is_empty() {
+        resp = resp.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: native_funds,
+        });
+    }
+    Ok(resp)
+}
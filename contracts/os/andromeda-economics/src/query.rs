@@ -1,7 +1,22 @@
-use andromeda_std::{amp::AndrAddr, error::ContractError};
-use cosmwasm_std::{Deps, Uint128};
+use std::collections::BTreeMap;
 
-use crate::state::BALANCES;
+use andromeda_std::{
+    amp::AndrAddr,
+    common::Milliseconds,
+    error::ContractError,
+    os::economics::{
+        AssetAmount, RevenueBucket, RevenueReportResponse, RevenueResponse, Sponsorship,
+    },
+};
+use cosmwasm_std::{ensure, Addr, Deps, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Map, PrefixBound};
+
+use crate::state::{
+    ACTION_REVENUE, BALANCES, DAILY_REVENUE, DAY_MILLISECONDS, RECIPIENT_REVENUE,
+    SPONSORED_AMOUNTS, SPONSORSHIPS, UNCLAIMED_REVENUE,
+};
+
+const MAX_REPORT_BUCKETS: u64 = 100;
 
 pub fn balance(deps: Deps, address: AndrAddr, asset: String) -> Result<Uint128, ContractError> {
     let addr = address.get_raw_address(&deps)?;
@@ -10,3 +25,131 @@ pub fn balance(deps: Deps, address: AndrAddr, asset: String) -> Result<Uint128,
         .unwrap_or_default();
     Ok(balance)
 }
+
+pub fn sponsorship(deps: Deps, sponsor: AndrAddr) -> Result<Option<Sponsorship>, ContractError> {
+    let sponsor = sponsor.get_raw_address(&deps)?;
+    Ok(SPONSORSHIPS.may_load(deps.storage, &sponsor)?)
+}
+
+pub fn sponsored_amount(
+    deps: Deps,
+    sponsor: AndrAddr,
+    user: AndrAddr,
+    asset: String,
+) -> Result<Uint128, ContractError> {
+    let sponsor = sponsor.get_raw_address(&deps)?;
+    let user = user.get_raw_address(&deps)?;
+    let amount = SPONSORED_AMOUNTS
+        .may_load(deps.storage, (&sponsor, &user, asset.as_str()))?
+        .unwrap_or_default();
+    Ok(amount)
+}
+
+fn asset_amounts(
+    storage: &dyn Storage,
+    map: Map<(&Addr, &str), Uint128>,
+    recipient: &Addr,
+) -> StdResult<Vec<AssetAmount>> {
+    map.prefix(recipient)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(asset, amount)| AssetAmount { asset, amount }))
+        .collect()
+}
+
+pub fn revenue(deps: Deps, recipient: AndrAddr) -> Result<RevenueResponse, ContractError> {
+    let recipient = recipient.get_raw_address(&deps)?;
+    Ok(RevenueResponse {
+        earned: asset_amounts(deps.storage, RECIPIENT_REVENUE, &recipient)?,
+        unclaimed: asset_amounts(deps.storage, UNCLAIMED_REVENUE, &recipient)?,
+    })
+}
+
+pub fn ado_type_revenue(
+    deps: Deps,
+    ado_type: String,
+    action: Option<String>,
+) -> Result<Vec<AssetAmount>, ContractError> {
+    let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+    let entries: Vec<(String, Uint128)> = match action {
+        Some(action) => ACTION_REVENUE
+            .prefix((ado_type.as_str(), action.as_str()))
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?,
+        None => ACTION_REVENUE
+            .sub_prefix(ado_type.as_str())
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|((_, asset), amount)| (asset, amount)))
+            .collect::<StdResult<_>>()?,
+    };
+    for (asset, amount) in entries {
+        *totals.entry(asset).or_default() += amount;
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(asset, amount)| AssetAmount { asset, amount })
+        .collect())
+}
+
+pub fn revenue_report(
+    deps: Deps,
+    recipient: AndrAddr,
+    start: Milliseconds,
+    end: Milliseconds,
+    bucket_size: Milliseconds,
+) -> Result<RevenueReportResponse, ContractError> {
+    let recipient = recipient.get_raw_address(&deps)?;
+    let bucket_days = bucket_size.milliseconds() / DAY_MILLISECONDS;
+    ensure!(
+        bucket_days > 0 && bucket_size.milliseconds() % DAY_MILLISECONDS == 0,
+        ContractError::InvalidParameter {
+            error: Some("Bucket size must be a multiple of a day".to_string())
+        }
+    );
+    ensure!(
+        start < end,
+        ContractError::InvalidParameter {
+            error: Some("Start must be before end".to_string())
+        }
+    );
+    // Buckets are aligned to the start of the day containing `start`
+    let start_day = start.milliseconds() / DAY_MILLISECONDS;
+    let end_day = end.milliseconds().div_ceil(DAY_MILLISECONDS);
+    let bucket_count = (end_day - start_day).div_ceil(bucket_days);
+    ensure!(
+        bucket_count <= MAX_REPORT_BUCKETS,
+        ContractError::InvalidParameter {
+            error: Some(format!(
+                "A report can have at most {MAX_REPORT_BUCKETS} buckets"
+            ))
+        }
+    );
+
+    let mut buckets: Vec<BTreeMap<String, Uint128>> = vec![BTreeMap::new(); bucket_count as usize];
+    for item in DAILY_REVENUE.prefix_range(
+        deps.storage,
+        Some(PrefixBound::inclusive((&recipient, start_day))),
+        Some(PrefixBound::exclusive((&recipient, end_day))),
+        Order::Ascending,
+    ) {
+        let ((_, day, asset), amount) = item?;
+        let bucket = ((day - start_day) / bucket_days) as usize;
+        *buckets[bucket].entry(asset).or_default() += amount;
+    }
+
+    let buckets = buckets
+        .into_iter()
+        .enumerate()
+        .map(|(index, revenue)| {
+            let bucket_start = (start_day + index as u64 * bucket_days) * DAY_MILLISECONDS;
+            RevenueBucket {
+                start: Milliseconds(bucket_start),
+                end: Milliseconds(bucket_start + bucket_size.milliseconds()),
+                revenue: revenue
+                    .into_iter()
+                    .map(|(asset, amount)| AssetAmount { asset, amount })
+                    .collect(),
+            }
+        })
+        .collect();
+    Ok(RevenueReportResponse { buckets })
+}
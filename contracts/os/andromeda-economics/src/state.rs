@@ -1,5 +1,25 @@
-use cosmwasm_std::{Addr, Uint128};
+use andromeda_std::{common::Milliseconds, os::economics::Sponsorship};
+use cosmwasm_std::{Addr, Empty, Uint128};
 use cw_storage_plus::Map;
 
 /// Contains all balances for an address
 pub const BALANCES: Map<(Addr, String), Uint128> = Map::new("balances");
+/// Contains the start of the current period and the number of free calls used for a (payee, ADO type, action)
+pub const FREE_CALLS: Map<(&Addr, &str, &str), (Milliseconds, u32)> = Map::new("free_calls");
+/// Contains the sponsorship registered by each sponsor
+pub const SPONSORSHIPS: Map<&Addr, Sponsorship> = Map::new("sponsorships");
+/// Contains the sponsors of each ADO
+pub const SPONSORS: Map<(&Addr, &Addr), Empty> = Map::new("sponsors");
+/// Contains the amount of an asset paid by a sponsor on behalf of a user
+pub const SPONSORED_AMOUNTS: Map<(&Addr, &Addr, &str), Uint128> = Map::new("sponsored_amounts");
+
+/// Revenue is recorded in daily buckets
+pub const DAY_MILLISECONDS: u64 = 86_400_000;
+/// Contains the fee revenue a recipient has not claimed, keyed by (recipient, asset)
+pub const UNCLAIMED_REVENUE: Map<(&Addr, &str), Uint128> = Map::new("unclaimed_revenue");
+/// Contains the total fee revenue earned by a recipient, keyed by (recipient, asset)
+pub const RECIPIENT_REVENUE: Map<(&Addr, &str), Uint128> = Map::new("recipient_revenue");
+/// Contains the total fee revenue for an (ADO type, action, asset)
+pub const ACTION_REVENUE: Map<(&str, &str, &str), Uint128> = Map::new("action_revenue");
+/// Contains the fee revenue earned by a recipient each day, keyed by (recipient, day, asset)
+pub const DAILY_REVENUE: Map<(&Addr, u64, &str), Uint128> = Map::new("daily_revenue");
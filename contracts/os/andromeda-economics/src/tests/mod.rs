@@ -3,16 +3,24 @@ use andromeda_std::error::ContractError;
 use andromeda_std::testing::mock_querier::MOCK_ADO_PUBLISHER;
 #[cfg(test)]
 use andromeda_std::testing::mock_querier::{
-    mock_dependencies_custom, MOCK_ACTION, MOCK_KERNEL_CONTRACT,
+    mock_dependencies_custom, MOCK_ACTION, MOCK_KERNEL_CONTRACT, MOCK_TIERED_ACTION,
+    UNWHITELISTED_ADDRESS,
 };
-use cosmwasm_std::{coin, to_json_binary, Addr, BankMsg, CosmosMsg, Uint128};
+use cosmwasm_std::{coin, from_json, to_json_binary, Addr, BankMsg, CosmosMsg, Deps, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, query};
 use crate::execute::{cw20_withdraw_msg, spend_balance};
-use crate::state::BALANCES;
+use crate::state::{
+    ACTION_REVENUE, BALANCES, DAILY_REVENUE, DAY_MILLISECONDS, SPONSORED_AMOUNTS, SPONSORS,
+    SPONSORSHIPS, UNCLAIMED_REVENUE,
+};
 
-use andromeda_std::os::economics::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
+use andromeda_std::common::{expiration::Expiry, Milliseconds};
+use andromeda_std::os::economics::{
+    AssetAmount, AssetCap, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg,
+    RevenueReportResponse, RevenueResponse,
+};
 
 use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
 
@@ -133,6 +141,7 @@ fn test_pay_fee() {
     let msg = ExecuteMsg::PayFee {
         payee: payee.clone(),
         action: MOCK_ACTION.to_string(),
+        funds: vec![],
     };
 
     // Paying fee without funds
@@ -156,12 +165,474 @@ fn test_pay_fee() {
         .unwrap();
     assert_eq!(balance, Uint128::from(0u128));
 
-    // Check publisher balance
+    // Check publisher revenue
     let publisher = Addr::unchecked(MOCK_ADO_PUBLISHER);
-    let balance = BALANCES
-        .load(deps.as_ref().storage, (publisher, "uusd".to_string()))
+    let revenue = UNCLAIMED_REVENUE
+        .load(deps.as_ref().storage, (&publisher, "native:uusd"))
         .unwrap_or_default();
-    assert_eq!(balance, Uint128::from(10u128));
+    assert_eq!(revenue, Uint128::from(10u128));
+}
+
+#[test]
+fn test_pay_tiered_fee() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let mut env = mock_env();
+    let creator = deps.api.addr_make("creator");
+    let info = message_info(&creator, &[]);
+    let payee = deps.api.addr_make("payee");
+    let publisher = Addr::unchecked(MOCK_ADO_PUBLISHER);
+    let balance = |deps: Deps, addr: &Addr, asset: &str| {
+        BALANCES
+            .load(deps.storage, (addr.clone(), asset.to_string()))
+            .unwrap_or_default()
+    };
+    let revenue = |deps: Deps, asset: &str| {
+        UNCLAIMED_REVENUE
+            .load(deps.storage, (&publisher, asset))
+            .unwrap_or_default()
+    };
+
+    let msg = ExecuteMsg::PayFee {
+        payee: payee.clone(),
+        action: MOCK_TIERED_ACTION.to_string(),
+        funds: vec![coin(100, "uusd")],
+    };
+
+    // The first call in each period is free
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "free_call" && attr.value == "true"));
+
+    // 10uusd plus 10% of the 100uusd sent, halved by the payee's tier discount
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (payee.clone(), "uusd".to_string()),
+            &Uint128::from(10u128),
+        )
+        .unwrap();
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(balance(deps.as_ref(), &payee, "uusd"), Uint128::zero());
+    assert_eq!(revenue(deps.as_ref(), "native:uusd"), Uint128::from(10u128));
+
+    // Falls back to the alternative asset when the payee has no uusd
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (payee.clone(), "uandr".to_string()),
+            &Uint128::from(15u128),
+        )
+        .unwrap();
+    let msg = ExecuteMsg::PayFee {
+        payee: payee.clone(),
+        action: MOCK_TIERED_ACTION.to_string(),
+        funds: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(
+        balance(deps.as_ref(), &payee, "uandr"),
+        Uint128::from(5u128)
+    );
+    assert_eq!(
+        revenue(deps.as_ref(), "native:uandr"),
+        Uint128::from(10u128)
+    );
+
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    // Free calls reset each period
+    env.block.time = env.block.time.plus_days(1);
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        balance(deps.as_ref(), &payee, "uandr"),
+        Uint128::from(5u128)
+    );
+
+    // Payees outside the tier's address list pay the full fee
+    let unlisted = Addr::unchecked(UNWHITELISTED_ADDRESS);
+    let msg = ExecuteMsg::PayFee {
+        payee: unlisted.clone(),
+        action: MOCK_TIERED_ACTION.to_string(),
+        funds: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (unlisted.clone(), "uusd".to_string()),
+            &Uint128::from(10u128),
+        )
+        .unwrap();
+    execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(balance(deps.as_ref(), &unlisted, "uusd"), Uint128::zero());
+    assert_eq!(revenue(deps.as_ref(), "native:uusd"), Uint128::from(20u128));
+}
+
+#[test]
+fn test_pay_percent_fee_in_alternative_asset() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let creator = deps.api.addr_make("creator");
+    let info = message_info(&creator, &[]);
+    let payee = deps.api.addr_make("payee");
+    let publisher = Addr::unchecked(MOCK_ADO_PUBLISHER);
+
+    let msg = ExecuteMsg::PayFee {
+        payee: payee.clone(),
+        action: MOCK_TIERED_ACTION.to_string(),
+        funds: vec![coin(100, "uusd")],
+    };
+    // Use up the free call
+    execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+    // 10% of the 100uusd sent is 10uusd, converted to 20uandr at the 10uusd to 20uandr ratio of the fixed amounts,
+    // 20uandr plus 20uandr halved by the payee's tier discount
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (payee.clone(), "uandr".to_string()),
+            &Uint128::from(19u128),
+        )
+        .unwrap();
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (payee.clone(), "uandr".to_string()),
+            &Uint128::from(20u128),
+        )
+        .unwrap();
+    execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        BALANCES
+            .load(deps.as_ref().storage, (payee, "uandr".to_string()))
+            .unwrap(),
+        Uint128::zero()
+    );
+    assert_eq!(
+        UNCLAIMED_REVENUE
+            .load(deps.as_ref().storage, (&publisher, "native:uandr"))
+            .unwrap(),
+        Uint128::from(20u128)
+    );
+}
+
+#[test]
+fn test_sponsorship() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let mut env = mock_env();
+    let ado = deps.api.addr_make("creator");
+    let ado_info = message_info(&ado, &[]);
+    // The mock querier reports "owner" as the owner of every ADO
+    let sponsor = Addr::unchecked("owner");
+    let payee = deps.api.addr_make("payee");
+    let other_payee = deps.api.addr_make("other_payee");
+
+    let msg = ExecuteMsg::RegisterSponsorship {
+        ados: vec![AndrAddr::from_string(ado.to_string())],
+        actions: Some(vec![MOCK_ACTION.to_string()]),
+        users: Some(vec![AndrAddr::from_string(payee.to_string())]),
+        user_caps: vec![AssetCap {
+            asset: "uusd".to_string(),
+            amount: Uint128::from(15u128),
+        }],
+        expiration: Some(Expiry::FromNow(Milliseconds::from_seconds(86_400))),
+    };
+    // Caps must be in the same format as fee assets
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sponsor, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAsset {
+            asset: "uusd".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::RegisterSponsorship {
+        ados: vec![AndrAddr::from_string(ado.to_string())],
+        actions: Some(vec![MOCK_ACTION.to_string()]),
+        users: Some(vec![AndrAddr::from_string(payee.to_string())]),
+        user_caps: vec![AssetCap {
+            asset: "native:uusd".to_string(),
+            amount: Uint128::from(15u128),
+        }],
+        expiration: Some(Expiry::FromNow(Milliseconds::from_seconds(86_400))),
+    };
+    let attacker = deps.api.addr_make("attacker");
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&attacker, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), message_info(&sponsor, &[]), msg).unwrap();
+
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (sponsor.clone(), "uusd".to_string()),
+            &Uint128::from(100u128),
+        )
+        .unwrap();
+
+    // The sponsor pays the payee's fee
+    let pay_fee = |payee: &Addr| ExecuteMsg::PayFee {
+        payee: payee.clone(),
+        action: MOCK_ACTION.to_string(),
+        funds: vec![],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        ado_info.clone(),
+        pay_fee(&payee),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "sponsor" && attr.value == sponsor.as_str()));
+    let balance = BALANCES
+        .load(deps.as_ref().storage, (sponsor.clone(), "uusd".to_string()))
+        .unwrap();
+    assert_eq!(balance, Uint128::from(90u128));
+
+    let sponsored = SPONSORED_AMOUNTS
+        .load(deps.as_ref().storage, (&sponsor, &payee, "native:uusd"))
+        .unwrap();
+    assert_eq!(sponsored, Uint128::from(10u128));
+
+    // The user's cap has been reached
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        ado_info.clone(),
+        pay_fee(&payee),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    // Users that aren't sponsored pay their own fees
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        ado_info.clone(),
+        pay_fee(&other_payee),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    // Expired sponsorships no longer pay fees
+    let msg = ExecuteMsg::RegisterSponsorship {
+        ados: vec![AndrAddr::from_string(ado.to_string())],
+        actions: None,
+        users: None,
+        user_caps: vec![],
+        expiration: Some(Expiry::FromNow(Milliseconds::from_seconds(86_400))),
+    };
+    execute(deps.as_mut(), env.clone(), message_info(&sponsor, &[]), msg).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        ado_info.clone(),
+        pay_fee(&other_payee),
+    )
+    .unwrap();
+    env.block.time = env.block.time.plus_days(1);
+    let err = execute(deps.as_mut(), env.clone(), ado_info, pay_fee(&other_payee)).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    execute(
+        deps.as_mut(),
+        env,
+        message_info(&sponsor, &[]),
+        ExecuteMsg::RemoveSponsorship {},
+    )
+    .unwrap();
+    assert!(!SPONSORSHIPS.has(deps.as_ref().storage, &sponsor));
+    assert!(!SPONSORS.has(deps.as_ref().storage, (&ado, &sponsor)));
+}
+
+#[test]
+fn test_revenue_claim() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let creator = deps.api.addr_make("creator");
+    let info = message_info(&creator, &[]);
+    let payee = deps.api.addr_make("payee");
+    let publisher = Addr::unchecked(MOCK_ADO_PUBLISHER);
+
+    BALANCES
+        .save(
+            deps.as_mut().storage,
+            (payee.clone(), "uusd".to_string()),
+            &Uint128::from(20u128),
+        )
+        .unwrap();
+    let msg = ExecuteMsg::PayFee {
+        payee: payee.clone(),
+        action: MOCK_ACTION.to_string(),
+        funds: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let action_revenue = ACTION_REVENUE
+        .load(
+            deps.as_ref().storage,
+            ("ADOType", MOCK_ACTION, "native:uusd"),
+        )
+        .unwrap();
+    assert_eq!(action_revenue, Uint128::from(20u128));
+
+    let today = env.block.time.seconds() * 1000 / DAY_MILLISECONDS;
+    let daily_revenue = DAILY_REVENUE
+        .load(deps.as_ref().storage, (&publisher, today, "native:uusd"))
+        .unwrap();
+    assert_eq!(daily_revenue, Uint128::from(20u128));
+
+    let report: RevenueReportResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RevenueReport {
+                recipient: AndrAddr::from_string(MOCK_ADO_PUBLISHER),
+                start: Milliseconds(today * DAY_MILLISECONDS),
+                end: Milliseconds((today + 2) * DAY_MILLISECONDS),
+                bucket_size: Milliseconds(DAY_MILLISECONDS),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(report.buckets.len(), 2);
+    assert_eq!(
+        report.buckets[0].revenue,
+        vec![AssetAmount {
+            asset: "native:uusd".to_string(),
+            amount: Uint128::from(20u128),
+        }]
+    );
+    assert!(report.buckets[1].revenue.is_empty());
+
+    // Bucket sizes must be whole days
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RevenueReport {
+            recipient: AndrAddr::from_string(MOCK_ADO_PUBLISHER),
+            start: Milliseconds(0),
+            end: Milliseconds(DAY_MILLISECONDS),
+            bucket_size: Milliseconds(1000),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidParameter { .. }));
+
+    let publisher_info = message_info(&publisher, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        publisher_info.clone(),
+        ExecuteMsg::ClaimRevenue { assets: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: publisher.to_string(),
+            amount: vec![coin(20, "uusd")],
+        })
+    );
+    assert!(!UNCLAIMED_REVENUE.has(deps.as_ref().storage, (&publisher, "native:uusd")));
+
+    // Lifetime revenue is kept after claiming
+    let revenue: RevenueResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Revenue {
+                recipient: AndrAddr::from_string(MOCK_ADO_PUBLISHER),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(revenue.earned[0].amount, Uint128::from(20u128));
+    assert!(revenue.unclaimed.is_empty());
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        publisher_info,
+        ExecuteMsg::ClaimRevenue { assets: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+}
+
+#[test]
+fn test_claim_revenue_duplicate_assets() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let publisher = deps.api.addr_make("publisher");
+    let cw20_asset = format!("cw20:{}", deps.api.addr_make("cw20"));
+    let storage = deps.as_mut().storage;
+    UNCLAIMED_REVENUE
+        .save(
+            storage,
+            (&publisher, cw20_asset.as_str()),
+            &Uint128::new(10),
+        )
+        .unwrap();
+    UNCLAIMED_REVENUE
+        .save(storage, (&publisher, "native:uusd"), &Uint128::new(20))
+        .unwrap();
+
+    // Listing an asset twice only pays out its balance once
+    let res = execute(
+        deps.as_mut(),
+        env,
+        message_info(&publisher, &[]),
+        ExecuteMsg::ClaimRevenue {
+            assets: Some(vec![
+                cw20_asset.clone(),
+                "native:uusd".to_string(),
+                cw20_asset.clone(),
+                "native:uusd".to_string(),
+            ]),
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0],
+        cw20_withdraw_msg(
+            Uint128::new(10),
+            cw20_asset.strip_prefix("cw20:").unwrap(),
+            publisher.clone()
+        )
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: publisher.to_string(),
+            amount: vec![coin(20, "uusd")],
+        })
+    );
+    assert!(!UNCLAIMED_REVENUE.has(deps.as_ref().storage, (&publisher, cw20_asset.as_str())));
 }
 
 // Temporarily disabled
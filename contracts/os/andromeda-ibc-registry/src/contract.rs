@@ -1,14 +1,16 @@
-use crate::state::REGISTRY;
+use crate::state::{AssetMetadataEntry, ASSET_METADATA, DENOM_CHAINS, DENOM_TRACES, REGISTRY};
 use andromeda_std::ado_base::permissioning::{LocalPermission, Permission};
 use andromeda_std::common::schedule::Schedule;
 use andromeda_std::os::ibc_registry::{
-    verify_denom, AllDenomInfoResponse, DenomInfo, DenomInfoResponse, ExecuteMsg, IBCDenomInfo,
-    InstantiateMsg, QueryMsg,
+    verify_denom, AllDenomInfoResponse, AssetMetadata, AssetMetadataBatchResponse,
+    AssetMetadataResponse, DenomChainsResponse, DenomInfo, DenomInfoResponse, DenomTrace,
+    ExecuteMsg, IBCDenomInfo, IBCDenomResponse, InstantiateMsg, QueryMsg,
 };
 use andromeda_std::{
     ado_base::{InstantiateMsg as BaseInstantiateMsg, MigrateMsg},
     ado_contract::ADOContract,
-    common::{context::ExecuteContext, encode_binary},
+    amp::AndrAddr,
+    common::{context::ExecuteContext, denom::Asset, encode_binary},
     error::ContractError,
 };
 #[cfg(not(feature = "library"))]
@@ -24,6 +26,8 @@ use std::collections::HashSet;
 const CONTRACT_NAME: &str = "crates.io:andromeda-ibc-registry";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const STORE_DENOM_INFO: &str = "StoreDenomInfo";
+const SET_ASSET_METADATA: &str = "SetAssetMetadata";
+const MAX_ASSET_METADATA_BATCH: usize = 50;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -52,19 +56,30 @@ pub fn instantiate(
         .get_raw_address(&deps.as_ref())?
         .into_string();
 
-    ADOContract::default().permission_action(deps.storage, STORE_DENOM_INFO, None)?;
+    for action in [STORE_DENOM_INFO, SET_ASSET_METADATA] {
+        permission_service_action(deps.storage, action, service_address.clone())?;
+    }
+
+    Ok(resp.add_attribute("service_address", service_address))
+}
+
+/// Permissions the action and whitelists the service address for it
+fn permission_service_action(
+    storage: &mut dyn Storage,
+    action: &str,
+    service_address: String,
+) -> Result<(), ContractError> {
+    ADOContract::default().permission_action(storage, action, None)?;
     ADOContract::set_permission(
-        deps.storage,
-        STORE_DENOM_INFO,
-        service_address.clone(),
+        storage,
+        action,
+        service_address,
         Permission::Local(LocalPermission::whitelisted(
             Schedule::new(None, None),
             None,
             None,
         )),
-    )?;
-
-    Ok(resp.add_attribute("service_address", service_address))
+    )
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -79,6 +94,11 @@ pub fn execute(
         ExecuteMsg::StoreDenomInfo { ibc_denom_info } => {
             execute_store_denom_info(ctx, ibc_denom_info)
         }
+        ExecuteMsg::RecordDenomTraces { traces } => execute_record_denom_traces(ctx, traces),
+        ExecuteMsg::SetAssetMetadata { asset, metadata } => {
+            execute_set_asset_metadata(ctx, asset, metadata)
+        }
+        ExecuteMsg::RemoveAssetMetadata { asset } => execute_remove_asset_metadata(ctx, asset),
         _ => ADOContract::default().execute(ctx, msg),
     }
 }
@@ -113,7 +133,7 @@ pub fn execute_store_denom_info(
         }
 
         // Store the denom info
-        REGISTRY.save(ctx.deps.storage, denom, &info.denom_info)?;
+        store_ibc_denom(ctx.deps.storage, &info.denom_info)?;
     }
 
     Ok(Response::new().add_attributes(vec![
@@ -122,6 +142,161 @@ pub fn execute_store_denom_info(
     ]))
 }
 
+/// Records the denom traces reported by the kernel for a relayed ICS20 transfer
+pub fn execute_record_denom_traces(
+    ctx: ExecuteContext,
+    traces: Vec<DenomTrace>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        ctx.info.sender == ctx.contract.get_kernel_address(ctx.deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+    ensure!(!traces.is_empty(), ContractError::NoDenomInfoProvided {});
+
+    let mut res = Response::new().add_attribute("action", "record_denom_traces");
+    for trace in traces {
+        ensure!(
+            !trace.chain.is_empty(),
+            ContractError::InvalidParameter {
+                error: Some("Chain cannot be empty".to_string()),
+            }
+        );
+        // Denoms without a path are native to the chain
+        if trace.denom_info.path.is_empty() {
+            ensure!(
+                trace.denom == trace.denom_info.base_denom,
+                ContractError::InvalidDenom {
+                    msg: Some("A denom without a path must be its base denom".to_string()),
+                }
+            );
+        } else {
+            verify_denom(&trace.denom, &trace.denom_info)?;
+            store_ibc_denom(ctx.deps.storage, &trace.denom_info)?;
+        }
+
+        DENOM_CHAINS.save(
+            ctx.deps.storage,
+            (
+                trace.denom_info.base_denom.as_str(),
+                trace.chain.as_str(),
+                trace.denom_info.path.as_str(),
+            ),
+            &trace,
+        )?;
+        res = res.add_attribute("denom_trace", format!("{}:{}", trace.chain, trace.denom));
+    }
+
+    Ok(res)
+}
+
+/// Sets the metadata of an asset.
+///
+/// Publishing metadata for a new asset requires the `SetAssetMetadata` permission,
+/// existing entries can only be updated by their publisher or the owner.
+pub fn execute_set_asset_metadata(
+    mut ctx: ExecuteContext,
+    asset: Asset,
+    metadata: AssetMetadata,
+) -> Result<Response, ContractError> {
+    let sender = ctx.info.sender.clone();
+    metadata.validate()?;
+    let asset = asset_key(ctx.deps.as_ref(), &asset)?;
+
+    let publisher = match ASSET_METADATA.may_load(ctx.deps.storage, &asset)? {
+        Some(entry) => {
+            ensure_publisher_or_owner(&ctx, &entry)?;
+            entry.publisher
+        }
+        None => {
+            ctx.contract.is_permissioned_strict(
+                ctx.deps.branch(),
+                ctx.env.clone(),
+                SET_ASSET_METADATA,
+                sender.clone(),
+            )?;
+            sender.clone()
+        }
+    };
+    ASSET_METADATA.save(
+        ctx.deps.storage,
+        &asset,
+        &AssetMetadataEntry {
+            metadata,
+            publisher,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_asset_metadata"),
+        attr("asset", asset),
+        attr("sender", sender),
+    ]))
+}
+
+pub fn execute_remove_asset_metadata(
+    ctx: ExecuteContext,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    let asset = asset_key(ctx.deps.as_ref(), &asset)?;
+    let entry = ASSET_METADATA.load(ctx.deps.storage, &asset)?;
+    ensure_publisher_or_owner(&ctx, &entry)?;
+    ASSET_METADATA.remove(ctx.deps.storage, &asset);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_asset_metadata"),
+        attr("asset", asset),
+    ]))
+}
+
+fn ensure_publisher_or_owner(
+    ctx: &ExecuteContext,
+    entry: &AssetMetadataEntry,
+) -> Result<(), ContractError> {
+    ensure!(
+        ctx.info.sender == entry.publisher
+            || ctx
+                .contract
+                .is_contract_owner(ctx.deps.storage, ctx.info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+    Ok(())
+}
+
+/// Returns the key of an asset in `native:<denom>` or `cw20:<address>` format.
+/// IBC denoms are lowercased to match the denom registry.
+fn asset_key(deps: Deps, asset: &Asset) -> Result<String, ContractError> {
+    let asset = match asset {
+        Asset::NativeToken(denom) => {
+            ensure!(
+                !denom.is_empty(),
+                ContractError::InvalidAsset {
+                    asset: asset.to_string()
+                }
+            );
+            if denom.starts_with("ibc/") {
+                Asset::NativeToken(denom.to_lowercase())
+            } else {
+                asset.clone()
+            }
+        }
+        Asset::Cw20Token(address) => {
+            Asset::Cw20Token(AndrAddr::from_string(address.get_raw_address(&deps)?))
+        }
+    };
+    Ok(asset.to_string())
+}
+
+fn store_ibc_denom(storage: &mut dyn Storage, denom_info: &DenomInfo) -> Result<(), ContractError> {
+    let denom = denom_info.get_ibc_denom();
+    REGISTRY.save(storage, denom.to_lowercase(), denom_info)?;
+    DENOM_TRACES.save(
+        storage,
+        (denom_info.base_denom.as_str(), denom_info.path.as_str()),
+        &denom,
+    )?;
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
@@ -129,6 +304,16 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
         QueryMsg::AllDenomInfo { limit, start_after } => {
             encode_binary(&get_all_denom_info(deps.storage, limit, start_after)?)
         }
+        QueryMsg::IBCDenom { base_denom, path } => {
+            encode_binary(&get_ibc_denom(deps.storage, base_denom, path)?)
+        }
+        QueryMsg::DenomChains { base_denom } => {
+            encode_binary(&get_denom_chains(deps.storage, base_denom)?)
+        }
+        QueryMsg::AssetMetadata { asset } => encode_binary(&get_asset_metadata(deps, asset)?),
+        QueryMsg::AssetMetadataBatch { assets } => {
+            encode_binary(&get_asset_metadata_batch(deps, assets)?)
+        }
     }
 }
 
@@ -168,9 +353,84 @@ pub fn get_all_denom_info(
     })
 }
 
+pub fn get_ibc_denom(
+    storage: &dyn Storage,
+    base_denom: String,
+    path: String,
+) -> Result<IBCDenomResponse, ContractError> {
+    let denom = DENOM_TRACES.load(storage, (base_denom.as_str(), path.as_str()))?;
+    Ok(IBCDenomResponse { denom })
+}
+
+pub fn get_denom_chains(
+    storage: &dyn Storage,
+    base_denom: String,
+) -> Result<DenomChainsResponse, ContractError> {
+    let chains = DENOM_CHAINS
+        .sub_prefix(base_denom.as_str())
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, trace)| trace))
+        .collect::<Result<Vec<DenomTrace>, StdError>>()?;
+    Ok(DenomChainsResponse { chains })
+}
+
+pub fn get_asset_metadata(
+    deps: Deps,
+    asset: Asset,
+) -> Result<AssetMetadataResponse, ContractError> {
+    let asset = asset_key(deps, &asset)?;
+    let entry = ASSET_METADATA.load(deps.storage, &asset)?;
+    Ok(AssetMetadataResponse {
+        asset,
+        metadata: entry.metadata,
+        publisher: entry.publisher,
+    })
+}
+
+pub fn get_asset_metadata_batch(
+    deps: Deps,
+    assets: Vec<Asset>,
+) -> Result<AssetMetadataBatchResponse, ContractError> {
+    ensure!(
+        assets.len() <= MAX_ASSET_METADATA_BATCH,
+        ContractError::InvalidParameter {
+            error: Some(format!(
+                "At most {MAX_ASSET_METADATA_BATCH} assets can be queried at once"
+            )),
+        }
+    );
+    let mut response = vec![];
+    for asset in assets {
+        let asset = asset_key(deps, &asset)?;
+        if let Some(entry) = ASSET_METADATA.may_load(deps.storage, &asset)? {
+            response.push(AssetMetadataResponse {
+                asset,
+                metadata: entry.metadata,
+                publisher: entry.publisher,
+            });
+        }
+    }
+    Ok(AssetMetadataBatchResponse { assets: response })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    ADOContract::default().migrate(deps, env, CONTRACT_NAME, CONTRACT_VERSION)
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let resp =
+        ADOContract::default().migrate(deps.branch(), env, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Registries from before asset metadata only permission the service address to store denom info
+    let contract = ADOContract::default();
+    let actions = contract.query_permissioned_actions(deps.as_ref())?.actions;
+    if actions.iter().any(|action| action == SET_ASSET_METADATA) {
+        return Ok(resp);
+    }
+    let service_addresses = contract
+        .query_permissioned_actors(deps.as_ref(), STORE_DENOM_INFO, None, None, None)?
+        .actors;
+    for service_address in service_addresses {
+        permission_service_action(deps.storage, SET_ASSET_METADATA, service_address)?;
+    }
+    Ok(resp)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -1,4 +1,20 @@
-use andromeda_std::os::ibc_registry::DenomInfo;
+use andromeda_std::os::ibc_registry::{AssetMetadata, DenomInfo, DenomTrace};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
 use cw_storage_plus::Map;
 
+#[cw_serde]
+pub struct AssetMetadataEntry {
+    pub metadata: AssetMetadata,
+    pub publisher: Addr,
+}
+
 pub const REGISTRY: Map<String, DenomInfo> = Map::new("registry");
+/// Maps a base denom and trace path to its `ibc/<hash>` denom
+pub const DENOM_TRACES: Map<(&str, &str), String> = Map::new("denom_traces");
+/// Maps a base denom, a chain and a trace path to the denom's trace on that chain
+///
+/// A base denom can reach the same chain through several paths, each with its own denom
+pub const DENOM_CHAINS: Map<(&str, &str, &str), DenomTrace> = Map::new("denom_chains");
+/// Maps an asset in `native:<denom>` or `cw20:<address>` format to its metadata
+pub const ASSET_METADATA: Map<&str, AssetMetadataEntry> = Map::new("asset_metadata");
@@ -14,6 +14,12 @@ pub fn make_ack_success() -> Binary {
     to_json_binary(&res).unwrap()
 }
 
+/// Acknowledges a packet with data for the sending chain
+pub fn make_ack_result(data: Binary) -> Binary {
+    let res = Ack::Result(data);
+    to_json_binary(&res).unwrap()
+}
+
 pub fn make_ack_fail(err: String) -> Binary {
     let res = Ack::Error(err);
     to_json_binary(&res).unwrap()
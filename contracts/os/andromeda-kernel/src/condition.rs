@@ -0,0 +1,82 @@
+use andromeda_cw_json::JSON;
+use andromeda_std::{
+    amp::messages::{AMPCondition, ConditionOperator},
+    error::ContractError,
+};
+use cosmwasm_std::{Decimal256, Deps, QueryRequest, WasmQuery};
+use serde_cw_value::Value;
+use std::str::FromStr;
+
+/// Queries the condition's target and compares the value found at the condition's path against the expected value.
+///
+/// Returns `Ok(false)` if the path does not exist within the query response.
+pub fn evaluate_condition(deps: Deps, condition: &AMPCondition) -> Result<bool, ContractError> {
+    let target = condition.target.get_raw_address(&deps)?;
+    let json: JSON = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: target.into_string(),
+        msg: condition.query.clone(),
+    }))?;
+
+    let Some(value) = json
+        .get(&condition.path)
+        .map_err(|err| ContractError::ParsingError {
+            err: err.to_string(),
+        })?
+    else {
+        return Ok(false);
+    };
+    let actual = value_to_string(value)?;
+
+    match condition.operator {
+        ConditionOperator::Equal => Ok(values_equal(&actual, &condition.expected)),
+        ConditionOperator::NotEqual => Ok(!values_equal(&actual, &condition.expected)),
+        ConditionOperator::GreaterThan => {
+            Ok(parse_numeric(&actual)? > parse_numeric(&condition.expected)?)
+        }
+        ConditionOperator::GreaterThanOrEqual => {
+            Ok(parse_numeric(&actual)? >= parse_numeric(&condition.expected)?)
+        }
+        ConditionOperator::LessThan => {
+            Ok(parse_numeric(&actual)? < parse_numeric(&condition.expected)?)
+        }
+        ConditionOperator::LessThanOrEqual => {
+            Ok(parse_numeric(&actual)? <= parse_numeric(&condition.expected)?)
+        }
+    }
+}
+
+/// Converts a queried value to its string representation, strings are returned without quotes
+fn value_to_string(value: &Value) -> Result<String, ContractError> {
+    let value = match value {
+        Value::String(value) => value.clone(),
+        Value::Bool(value) => value.to_string(),
+        Value::U8(value) => value.to_string(),
+        Value::U16(value) => value.to_string(),
+        Value::U32(value) => value.to_string(),
+        Value::U64(value) => value.to_string(),
+        Value::I8(value) => value.to_string(),
+        Value::I16(value) => value.to_string(),
+        Value::I32(value) => value.to_string(),
+        Value::I64(value) => value.to_string(),
+        _ => JSON::try_from(value.clone())
+            .and_then(String::try_from)
+            .map_err(|err| ContractError::ParsingError {
+                err: err.to_string(),
+            })?,
+    };
+    Ok(value)
+}
+
+/// Compares numeric values by their value, so that `"100"` equals `"100.0"`, and any other values as strings
+fn values_equal(actual: &str, expected: &str) -> bool {
+    match (Decimal256::from_str(actual), Decimal256::from_str(expected)) {
+        (Ok(actual), Ok(expected)) => actual == expected,
+        _ => actual == expected,
+    }
+}
+
+fn parse_numeric(value: &str) -> Result<Decimal256, ContractError> {
+    Decimal256::from_str(value).map_err(|_| ContractError::ParsingError {
+        err: format!("Cannot compare non-numeric value {value}"),
+    })
+}
@@ -13,8 +13,10 @@ use cosmwasm_std::{
 use crate::execute::handle_receive_cw20;
 // use crate::execute::handle_receive_cw20;
 use crate::ibc::{IBCLifecycleComplete, SudoMsg};
+use crate::recovery::migrate_legacy_recoveries;
 use crate::reply::{
-    on_reply_create_ado, on_reply_ibc_transfer, on_reply_refund_ibc_transfer_with_msg,
+    on_reply_amp_bundle, on_reply_amp_msg, on_reply_create_ado, on_reply_ibc_transfer,
+    on_reply_recovery, on_reply_refund_ibc_transfer_with_msg, on_reply_scheduled_message,
 };
 use crate::state::{CURR_CHAIN, TX_INDEX};
 use crate::{execute, query, sudo};
@@ -59,6 +61,13 @@ pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> Result<Response, Contra
                     msg.clone(),
                 );
             }
+            Some(ReplyId::AMPBundle) => return on_reply_amp_bundle(deps, msg),
+            Some(ReplyId::AMPMsg) => return on_reply_amp_msg(deps, msg),
+            Some(ReplyId::ScheduledAMPMsg) => return on_reply_scheduled_message(msg),
+            // Denom traces are recorded on a best effort basis
+            Some(ReplyId::RecordDenomTraces) => {
+                return Ok(Response::default().add_attribute("denom_traces_recorded", "false"))
+            }
             _ => {
                 return Err(ContractError::Std(StdError::generic_err(format!(
                     "{}:{}",
@@ -72,6 +81,9 @@ pub fn reply(mut deps: DepsMut, env: Env, msg: Reply) -> Result<Response, Contra
     match ReplyId::from_repr(msg.id) {
         Some(ReplyId::CreateADO) => on_reply_create_ado(deps, env, msg),
         Some(ReplyId::IBCTransfer) => on_reply_ibc_transfer(deps, env, msg),
+        Some(ReplyId::AMPBundle) => on_reply_amp_bundle(deps, msg),
+        Some(ReplyId::AMPMsg) => on_reply_amp_msg(deps, msg),
+        Some(ReplyId::Recovery) => on_reply_recovery(deps, msg),
         _ => Ok(Response::default()),
     }
 }
@@ -107,7 +119,8 @@ pub fn execute(
             msg,
             owner,
             chain,
-        } => execute::create(execute_env, ado_type, msg, owner, chain),
+            resolve_env,
+        } => execute::create(execute_env, ado_type, msg, owner, chain, resolve_env),
         ExecuteMsg::AssignChannels {
             ics20_channel_id,
             direct_channel_id,
@@ -120,12 +133,35 @@ pub fn execute(
             chain,
             kernel_address,
         ),
-        ExecuteMsg::Recover {} => execute::recover(execute_env),
+        ExecuteMsg::Recover { denom, recipient } => execute::recover(execute_env, denom, recipient),
+        ExecuteMsg::ScheduleMessage { message, expiry } => {
+            execute::schedule_message(execute_env, message, expiry)
+        }
+        ExecuteMsg::ProcessScheduledMessages { limit } => {
+            execute::process_scheduled_messages(execute_env, limit)
+        }
+        ExecuteMsg::CancelScheduledMessage { id } => {
+            execute::cancel_scheduled_message(execute_env, id)
+        }
+        ExecuteMsg::SetTraceRetention { retention } => {
+            execute::set_trace_retention(execute_env, retention)
+        }
+        ExecuteMsg::SetRateLimit { scope, limit } => {
+            execute::set_rate_limit(execute_env, scope, limit)
+        }
         ExecuteMsg::UpdateChainName { chain_name } => {
             execute::update_chain_name(execute_env, chain_name)
         }
         ExecuteMsg::SetEnv { variable, value } => execute::set_env(execute_env, variable, value),
         ExecuteMsg::UnsetEnv { variable } => execute::unset_env(execute_env, variable),
+        ExecuteMsg::SetScopedEnv {
+            scope,
+            variable,
+            value,
+        } => execute::set_scoped_env(execute_env, scope, variable, value),
+        ExecuteMsg::UnsetScopedEnv { scope, variable } => {
+            execute::unset_scoped_env(execute_env, scope, variable)
+        }
         ExecuteMsg::Internal(msg) => execute::internal(execute_env, msg),
         ExecuteMsg::Ownership(ownership_message) => ADOContract::default().execute_ownership(
             execute_env.deps,
@@ -137,27 +173,28 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
         SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCAck {
             channel,
             sequence,
             ack,
             success,
-        }) => sudo::ibc_lifecycle::receive_ack(deps, channel, sequence, ack, success),
+        }) => sudo::ibc_lifecycle::receive_ack(deps, env, channel, sequence, ack, success),
         SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout { channel, sequence }) => {
-            sudo::ibc_lifecycle::receive_timeout(deps, channel, sequence)
+            sudo::ibc_lifecycle::receive_timeout(deps, env, channel, sequence)
         }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    migrate_legacy_recoveries(deps.storage, env.block.time)?;
     ADOContract::default().migrate(deps, env, CONTRACT_NAME, CONTRACT_VERSION)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::KeyAddress { key } => encode_binary(&query::key_address(deps, key)?),
         QueryMsg::VerifyAddress { address } => {
@@ -165,6 +202,18 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
         }
         QueryMsg::ChannelInfo { chain } => encode_binary(&query::channel_info(deps, chain)?),
         QueryMsg::Recoveries { addr } => encode_binary(&query::recoveries(deps, addr)?),
+        QueryMsg::AllRecoveries {
+            denom,
+            chain,
+            start_after,
+            limit,
+        } => encode_binary(&query::all_recoveries(
+            deps,
+            denom,
+            chain,
+            start_after,
+            limit,
+        )?),
         QueryMsg::ChainName {} => encode_binary(&query::chain_name(deps)?),
         // Base queries
         QueryMsg::Version {} => encode_binary(&ADOContract::default().query_version(deps)?),
@@ -177,5 +226,21 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             encode_binary(&query::pending_packets(deps, channel_id)?)
         }
         QueryMsg::GetEnv { variable } => encode_binary(&query::get_env(deps, variable)?),
+        QueryMsg::GetScopedEnv { scope, variable } => {
+            encode_binary(&query::get_scoped_env(deps, scope, variable)?)
+        }
+        QueryMsg::ScheduledMessages { start_after, limit } => {
+            encode_binary(&query::scheduled_messages(deps, start_after, limit)?)
+        }
+        QueryMsg::PacketTrace { id } => encode_binary(&query::packet_trace(deps, id)?),
+        QueryMsg::PacketTraces {
+            origin,
+            start_after,
+            limit,
+        } => encode_binary(&query::packet_traces(deps, origin, start_after, limit)?),
+        QueryMsg::RateLimitStatus {
+            origin,
+            destination,
+        } => encode_binary(&query::rate_limit_status(deps, env, origin, destination)?),
     }
 }
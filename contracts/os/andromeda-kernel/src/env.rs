@@ -0,0 +1,202 @@
+use crate::state::{ENV_VARIABLES, SCOPED_ENV_VARIABLES};
+use andromeda_std::{
+    error::ContractError,
+    os::kernel::{EnvScope, EnvValue},
+};
+use cosmwasm_std::{ensure, from_json, to_json_binary, Addr, Api, Binary, Deps, Storage};
+use serde_cw_value::Value;
+
+pub const GLOBAL_SCOPE: &str = "global";
+
+/// The storage key of the given scope, app addresses are resolved
+pub fn scope_key(deps: Deps, scope: &EnvScope) -> Result<String, ContractError> {
+    match scope {
+        EnvScope::Global {} => Ok(GLOBAL_SCOPE.to_string()),
+        EnvScope::App { address } => Ok(app_scope_key(&address.get_raw_address(&deps)?)),
+        EnvScope::User { username } => Ok(user_scope_key(username)),
+    }
+}
+
+pub fn app_scope_key(app: &Addr) -> String {
+    format!("app:{app}")
+}
+
+pub fn user_scope_key(username: &str) -> String {
+    format!("user:{username}")
+}
+
+pub fn validate_env_variable(variable: &str) -> Result<(), ContractError> {
+    ensure!(
+        !variable.is_empty(),
+        ContractError::InvalidEnvironmentVariable {
+            msg: "Environment variable name cannot be empty".to_string()
+        }
+    );
+
+    ensure!(
+        variable
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        ContractError::InvalidEnvironmentVariable {
+            msg:
+                "Environment variable name can only contain alphanumeric characters and underscores"
+                    .to_string()
+        }
+    );
+
+    ensure!(
+        variable.len() <= 100,
+        ContractError::InvalidEnvironmentVariable {
+            msg: "Environment variable name length exceeds the maximum allowed length of 100 characters".to_string()
+        }
+    );
+    Ok(())
+}
+
+pub fn validate_env_value(api: &dyn Api, value: &EnvValue) -> Result<(), ContractError> {
+    match value {
+        EnvValue::String(value) => {
+            ensure!(
+                !value.is_empty(),
+                ContractError::InvalidEnvironmentVariable {
+                    msg: "Environment variable value cannot be empty".to_string()
+                }
+            );
+            ensure!(
+                value.len() <= 100,
+                ContractError::InvalidEnvironmentVariable {
+                    msg: "Environment variable value length exceeds the maximum allowed length of 100 characters".to_string()
+                }
+            );
+        }
+        EnvValue::Number(_) => {}
+        EnvValue::Address(address) => {
+            api.addr_validate(address.as_str())?;
+        }
+        EnvValue::Path(path) => path.validate(api)?,
+        EnvValue::Json(json) => {
+            ensure!(
+                json.len() <= 1000,
+                ContractError::InvalidEnvironmentVariable {
+                    msg: "Environment variable value length exceeds the maximum allowed length of 1000 characters".to_string()
+                }
+            );
+            from_json::<Value>(json.as_bytes()).map_err(|_| {
+                ContractError::InvalidEnvironmentVariable {
+                    msg: "Environment variable value is not valid JSON".to_string(),
+                }
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Loads a variable from the first of the given scopes that defines it.
+///
+/// Global variables set through `SetEnv` are treated as strings.
+pub fn load_scoped_env(
+    storage: &dyn Storage,
+    scopes: &[String],
+    variable: &str,
+) -> Result<Option<EnvValue>, ContractError> {
+    let variable = variable.to_ascii_uppercase();
+    for scope in scopes {
+        if let Some(value) =
+            SCOPED_ENV_VARIABLES.may_load(storage, (scope.as_str(), variable.as_str()))?
+        {
+            return Ok(Some(value));
+        }
+        if scope == GLOBAL_SCOPE {
+            if let Some(value) = ENV_VARIABLES.may_load(storage, &variable)? {
+                return Ok(Some(EnvValue::String(value)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Replaces `${VARIABLE}` references within the string values of a JSON message.
+///
+/// A string consisting of a single reference is replaced by the typed value of the variable,
+/// references within longer strings are replaced by the variable's string representation.
+/// Only references to valid variable names are replaced, any other `${...}` content is left untouched.
+pub fn resolve_env_references(
+    storage: &dyn Storage,
+    msg: Binary,
+    scopes: &[String],
+) -> Result<Binary, ContractError> {
+    if !msg.windows(2).any(|window| window == b"${") {
+        return Ok(msg);
+    }
+    let value = resolve_value(storage, scopes, from_json(&msg)?)?;
+    Ok(to_json_binary(&value)?)
+}
+
+fn resolve_value(
+    storage: &dyn Storage,
+    scopes: &[String],
+    value: Value,
+) -> Result<Value, ContractError> {
+    match value {
+        Value::String(value) => resolve_string(storage, scopes, value),
+        Value::Seq(values) => Ok(Value::Seq(
+            values
+                .into_iter()
+                .map(|value| resolve_value(storage, scopes, value))
+                .collect::<Result<_, _>>()?,
+        )),
+        Value::Map(map) => Ok(Value::Map(
+            map.into_iter()
+                .map(|(key, value)| Ok((key, resolve_value(storage, scopes, value)?)))
+                .collect::<Result<_, ContractError>>()?,
+        )),
+        value => Ok(value),
+    }
+}
+
+fn resolve_string(
+    storage: &dyn Storage,
+    scopes: &[String],
+    value: String,
+) -> Result<Value, ContractError> {
+    let load = |variable: &str| -> Result<EnvValue, ContractError> {
+        load_scoped_env(storage, scopes, variable)?.ok_or(
+            ContractError::EnvironmentVariableNotFound {
+                variable: variable.to_string(),
+            },
+        )
+    };
+
+    if let Some(variable) = value
+        .strip_prefix("${")
+        .and_then(|value| value.strip_suffix('}'))
+    {
+        if validate_env_variable(variable).is_ok() {
+            return match load(variable)? {
+                EnvValue::Number(number) => Ok(Value::I64(number)),
+                EnvValue::Json(json) => Ok(from_json(json.as_bytes())?),
+                value => Ok(Value::String(value.to_string())),
+            };
+        }
+    }
+
+    let mut resolved = String::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        let variable = &rest[start + 2..end];
+        // Anything other than a valid variable name is kept as is
+        if validate_env_variable(variable).is_err() {
+            resolved.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        }
+        resolved.push_str(&rest[..start]);
+        resolved.push_str(&load(variable)?.to_string());
+        rest = &rest[end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(Value::String(resolved))
+}
@@ -1,45 +1,114 @@
-use crate::ibc::{get_counterparty_denom, PACKET_LIFETIME};
+use crate::condition::evaluate_condition;
+use crate::env::{
+    app_scope_key, resolve_env_references, scope_key, user_scope_key, validate_env_value,
+    validate_env_variable, GLOBAL_SCOPE,
+};
+use crate::ibc::{
+    get_denom_trace, get_ibc_timeout, ibc_callback_memo, MAX_CROSS_CHAIN_HOPS, PACKET_LIFETIME,
+};
 use crate::query;
+use crate::rate_limit::check_rate_limits;
+use crate::recovery::{record_recovery, take_recoveries, total_amounts};
+use crate::reply::{AMPBundlePayload, RecoveryPayload, ScheduledMessagePayload};
 use crate::state::{
     ADO_OWNER, CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, CHANNEL_TO_EXECUTE_MSG, CURR_CHAIN,
-    ENV_VARIABLES, IBC_FUND_RECOVERY, KERNEL_ADDRESSES, PENDING_MSG_AND_FUNDS, TRIGGER_KEY,
-    TX_INDEX,
+    DEFAULT_ORIGIN_RATE_LIMIT, DESTINATION_RATE_LIMITS, ENV_VARIABLES, KERNEL_ADDRESSES,
+    NEXT_SCHEDULED_MESSAGE_ID, ORIGIN_RATE_LIMITS, PENDING_MSG_AND_FUNDS, SCHEDULED_MESSAGES,
+    SCHEDULE_QUEUE, SCOPED_ENV_VARIABLES, TRACE_RETENTION, TRIGGER_KEY, TX_INDEX,
 };
+use crate::trace::{dispatched_entries, record_trace, traced_sub_msgs};
 use andromeda_std::ado_contract::ADOContract;
 use andromeda_std::amp::addresses::AndrAddr;
-use andromeda_std::amp::messages::{AMPCtx, AMPMsg, AMPPkt, CrossChainHop};
+use andromeda_std::amp::messages::{
+    AMPCtx, AMPMsg, AMPMsgConfig, AMPPkt, CrossChainHop, IBCConfig, IBCFailurePolicy,
+    AMP_HOP_GAS_COST,
+};
 use andromeda_std::amp::{ADO_DB_KEY, VFS_KEY};
 use andromeda_std::common::code_id::get_code_id;
 use andromeda_std::common::context::ExecuteContext;
-use andromeda_std::common::has_coins_merged;
+use andromeda_std::common::expiration::Expiry;
 use andromeda_std::common::message_generators::{
     create_bank_send_msg, create_cw20_send_msg, create_cw20_transfer_msg,
 };
 use andromeda_std::common::reply::ReplyId;
+use andromeda_std::common::{has_coins_merged, Milliseconds};
 use andromeda_std::error::ContractError;
 use andromeda_std::os::aos_querier::AOSQuerier;
 #[cfg(not(target_arch = "wasm32"))]
 use andromeda_std::os::ibc_registry::path_to_hops;
 use andromeda_std::os::kernel::CROSS_CHAIN_ENABLED;
 use andromeda_std::os::kernel::{
-    is_os_contract, ChannelInfo, Cw20HookMsg, ExecuteMsg, IbcExecuteMsg, Ics20PacketInfo,
-    InternalMsg,
+    is_os_contract, ChannelInfo, Cw20HookMsg, EnvScope, EnvValue, ExecuteMsg, ForwardedFunds,
+    IbcExecuteMsg, Ics20PacketInfo, InternalMsg, RateLimit, RateLimitScope, RecoveryReason,
+    ScheduledMessage,
 };
 use cosmwasm_std::{
-    attr, ensure, from_json, to_json_binary, BankMsg, Binary, Coin, CosmosMsg, DepsMut, Env,
-    IbcMsg, MessageInfo, Response, StdAck, StdError, SubMsg, Uint128, WasmMsg,
+    attr, ensure, from_json, to_json_binary, wasm_execute, Addr, BankMsg, Binary, Coin, CosmosMsg,
+    Deps, DepsMut, Empty, Env, IbcMsg, MessageInfo, Order, ReplyOn, Response, StdAck, StdError,
+    Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw20::Cw20ReceiveMsg;
 #[cfg(not(target_arch = "wasm32"))]
 use cw_orch::mock::cw_multi_test::ibc::types::keccak256;
+use cw_storage_plus::Bound;
+
+pub fn send(mut ctx: ExecuteContext, message: AMPMsg) -> Result<Response, ContractError> {
+    let existing_id = ctx.amp_ctx.as_ref().and_then(|pkt| pkt.ctx.get_id());
+    let id = generate_or_validate_packet_id(&mut ctx.deps, &ctx.env, existing_id)?;
+    let origin = ctx
+        .amp_ctx
+        .as_ref()
+        .map_or(ctx.info.sender.to_string(), |pkt| pkt.ctx.get_origin());
+
+    // Packets leaving the chain carry their ID so the receiving kernel continues the trace
+    if message.recipient.get_protocol() == Some("ibc") {
+        let pkt = ctx.amp_ctx.get_or_insert_with(|| {
+            AMPPkt::new(
+                ctx.info.sender.clone(),
+                ctx.env.contract.address.clone(),
+                vec![message.clone()],
+            )
+        });
+        pkt.ctx.id = Some(id.clone());
+    }
+
+    send_traced(ctx, message, &id, &origin)
+}
 
-pub fn send(ctx: ExecuteContext, message: AMPMsg) -> Result<Response, ContractError> {
+/// Sends the message, recording it in the trace of the packet with the given ID
+pub(crate) fn send_traced(
+    mut ctx: ExecuteContext,
+    message: AMPMsg,
+    id: &str,
+    origin: &str,
+) -> Result<Response, ContractError> {
     ensure!(
         has_coins_merged(ctx.info.funds.as_slice(), message.funds.as_slice()),
         ContractError::InsufficientFunds {}
     );
 
-    handle(ctx.deps, ctx.info, ctx.env, ctx.amp_ctx, message)
+    let previous_hops = ctx
+        .amp_ctx
+        .as_ref()
+        .map(|pkt| pkt.ctx.previous_hops.clone())
+        .unwrap_or_default();
+    record_trace(
+        ctx.deps.storage,
+        id,
+        origin,
+        dispatched_entries(&ctx.env, &previous_hops, std::slice::from_ref(&message)),
+    )?;
+
+    let sender = ctx.info.sender.clone();
+    let mut res = handle(
+        ctx.deps.branch(),
+        ctx.info,
+        ctx.env,
+        ctx.amp_ctx,
+        message.clone(),
+    )?;
+    res.messages = traced_sub_msgs(ctx.deps.storage, id, &sender, &message, res.messages)?;
+    Ok(res)
 }
 
 pub fn handle(
@@ -49,6 +118,26 @@ pub fn handle(
     ctx: Option<AMPPkt>,
     message: AMPMsg,
 ) -> Result<Response, ContractError> {
+    if let Some(condition) = &message.config.condition {
+        if !evaluate_condition(deps.as_ref(), condition)? {
+            ensure!(condition.skip_on_unmet, ContractError::UnmetCondition {});
+
+            // The message is skipped and any attached funds are returned to the sender
+            let mut res = Response::default().add_attributes(vec![
+                attr("action", "skip_message"),
+                attr("recipient", message.recipient.to_string()),
+                attr("reason", "unmet_condition"),
+            ]);
+            if !message.funds.is_empty() {
+                res = res.add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: message.funds,
+                });
+            }
+            return Ok(res);
+        }
+    }
+
     match message.recipient.get_protocol() {
         Some("ibc") => handle_ibc(deps, info, env, ctx, message),
         _ => handle_local(deps, info, env, ctx.map(|ctx| ctx.ctx), message),
@@ -65,7 +154,7 @@ In both situations the sender can define the funds that are being attached to th
 pub fn handle_local(
     deps: DepsMut,
     info: MessageInfo,
-    _env: Env,
+    env: Env,
     ctx: Option<AMPCtx>,
     amp_message: AMPMsg,
 ) -> Result<Response, ContractError> {
@@ -76,7 +165,12 @@ pub fn handle_local(
         ref recipient,
     } = amp_message;
 
-    let recipient_addr = recipient.get_raw_address(&deps.as_ref())?;
+    let origin = ctx
+        .as_ref()
+        .map_or(info.sender.to_string(), |ctx| ctx.get_origin());
+    // Paths through restricted directories are resolved on behalf of the message's origin
+    let recipient_addr = recipient.get_raw_address_for(&deps.as_ref(), &origin)?;
+    check_rate_limits(deps.storage, &env, &origin, Some(&recipient_addr), funds)?;
 
     // Handle empty message - send funds only
     if message == &Binary::default() {
@@ -99,7 +193,7 @@ pub fn handle_local(
     let adodb_addr = KERNEL_ADDRESSES.load(deps.storage, ADO_DB_KEY)?;
 
     // Verify recipient is a contract
-    let code_id = get_code_id(&deps, recipient)?;
+    let code_id = get_code_id(&deps, &AndrAddr::from_string(recipient_addr.to_string()))?;
     // Check if the recipient is an ADO
     let ado_type = AOSQuerier::ado_type_getter(&deps.querier, &adodb_addr, code_id)?;
 
@@ -113,19 +207,19 @@ pub fn handle_local(
         );
     }
 
+    let gas_budget = ctx.as_ref().and_then(|ctx| ctx.gas_budget);
+
     // Generate submessage based on whether recipient is an ADO or if the message is direct
-    let sub_msg = if config.direct || ado_type.is_none() {
+    let mut sub_msg = if config.direct || ado_type.is_none() {
         amp_message.generate_sub_msg_direct(recipient_addr, ReplyId::AMPMsg.repr())
     } else {
-        let origin = ctx.map_or(info.sender.to_string(), |ctx| ctx.get_origin());
         let previous_sender = info.sender.to_string();
 
-        AMPPkt::new(origin, previous_sender, vec![amp_message.clone()]).to_sub_msg(
-            recipient_addr,
-            Some(funds.clone()),
-            ReplyId::AMPMsg.repr(),
-        )?
+        let mut packet = AMPPkt::new(origin, previous_sender, vec![amp_message.clone()]);
+        packet.ctx.gas_budget = nested_gas_budget(gas_budget);
+        packet.to_sub_msg(recipient_addr, Some(funds.clone()), ReplyId::AMPMsg.repr())?
     };
+    apply_gas_budget(&mut sub_msg, gas_budget);
 
     Ok(Response::default()
         .add_submessage(sub_msg)
@@ -177,7 +271,7 @@ pub fn handle_receive_cw20(
 pub fn handle_local_cw20(
     deps: DepsMut,
     info: MessageInfo,
-    _env: Env,
+    env: Env,
     ctx: Option<AMPCtx>,
     amp_message: AMPMsg,
 ) -> Result<Response, ContractError> {
@@ -190,9 +284,28 @@ pub fn handle_local_cw20(
         ..
     } = amp_message;
 
+    // CW20 messages with unmet conditions always fail so that the tokens are returned by the token contract
+    if let Some(condition) = &config.condition {
+        ensure!(
+            evaluate_condition(deps.as_ref(), condition)?,
+            ContractError::UnmetCondition {}
+        );
+    }
+
     let token_denom = funds[0].denom.clone();
     let token_amount = funds[0].amount.u128();
-    let recipient_raw_address = recipient.get_raw_address(&deps.as_ref())?;
+    let origin = ctx
+        .as_ref()
+        .map_or(info.sender.to_string(), |ctx| ctx.get_origin());
+    // Paths through restricted directories are resolved on behalf of the message's origin
+    let recipient_raw_address = recipient.get_raw_address_for(&deps.as_ref(), &origin)?;
+    check_rate_limits(
+        deps.storage,
+        &env,
+        &origin,
+        Some(&recipient_raw_address),
+        funds,
+    )?;
 
     // Handle empty message (bank transfer)
     if message == &Binary::default() {
@@ -214,13 +327,17 @@ pub fn handle_local_cw20(
     }
 
     // Verify recipient is contract
-    let recipient_code_id = get_code_id(&deps, recipient)?;
+    let recipient_code_id = get_code_id(
+        &deps,
+        &AndrAddr::from_string(recipient_raw_address.to_string()),
+    )?;
     let adodb_addr = KERNEL_ADDRESSES.load(deps.storage, ADO_DB_KEY)?;
 
     let is_ado =
         AOSQuerier::ado_type_getter(&deps.querier, &adodb_addr, recipient_code_id)?.is_some();
 
-    let (sub_msg, attrs) = if config.direct || !is_ado {
+    let gas_budget = ctx.as_ref().and_then(|ctx| ctx.gas_budget);
+    let (mut sub_msg, attrs) = if config.direct || !is_ado {
         // Direct message
         create_cw20_send_msg(
             &recipient_raw_address,
@@ -231,10 +348,10 @@ pub fn handle_local_cw20(
             ReplyId::AMPMsg.repr(),
         )?
     } else {
-        let origin = ctx.map_or(info.sender.to_string(), |ctx| ctx.get_origin());
         let previous_sender = info.sender.to_string();
 
-        let new_packet = AMPPkt::new(origin, previous_sender, vec![amp_message.clone()]);
+        let mut new_packet = AMPPkt::new(origin, previous_sender, vec![amp_message.clone()]);
+        new_packet.ctx.gas_budget = nested_gas_budget(gas_budget);
 
         create_cw20_send_msg(
             &recipient_raw_address,
@@ -245,10 +362,23 @@ pub fn handle_local_cw20(
             ReplyId::AMPMsg.repr(),
         )?
     };
+    apply_gas_budget(&mut sub_msg, gas_budget);
 
     Ok(res.add_submessage(sub_msg).add_attributes(attrs))
 }
 
+/// The gas budget available to the hops nested within a hop with the given budget
+fn nested_gas_budget(gas_budget: Option<u64>) -> Option<u64> {
+    gas_budget.map(|budget| budget.saturating_sub(AMP_HOP_GAS_COST))
+}
+
+/// Restricts a sub message to the gas budget of the current hop
+fn apply_gas_budget(sub_msg: &mut SubMsg, gas_budget: Option<u64>) {
+    if let Some(budget) = gas_budget {
+        sub_msg.gas_limit = Some(sub_msg.gas_limit.map_or(budget, |limit| limit.min(budget)));
+    }
+}
+
 pub fn trigger_relay(
     ctx: ExecuteContext,
     packet_sequence: u64,
@@ -290,23 +420,245 @@ pub fn trigger_relay(
             ics20_packet_info,
             channel_id,
         ),
-        // This means that the funds have been returned to the contract, time to return the funds to the original sender
-        StdAck::Error(_) => {
-            let refund_msg = CosmosMsg::Bank(BankMsg::Send {
-                to_address: ics20_packet_info.sender.clone(),
-                amount: vec![ics20_packet_info.funds.clone()],
-            });
-            Ok(Response::default()
-                .add_message(refund_msg)
-                .add_attribute("action", "relay_packet")
-                .add_attribute("relay_outcome", "refund")
-                .add_attribute("relay_sequence", packet_sequence.to_string())
+        // This means that the funds have been returned to the contract, time to apply the message's failure policy
+        StdAck::Error(_) => handle_failed_transfer(
+            ctx.deps,
+            ctx.env,
+            packet_sequence,
+            channel_info,
+            ics20_packet_info,
+            channel_id,
+        ),
+    }
+}
+
+/// Applies the failure policy of the message relayed by a failed or timed out ICS20 transfer.
+///
+/// Returns `None` if the transfer does not relay a message or its failure has already been handled.
+pub(crate) fn handle_failed_relay_transfer(
+    deps: DepsMut,
+    env: Env,
+    channel_id: String,
+    sequence: u64,
+) -> Result<Option<Response>, ContractError> {
+    let Some(ics20_packet_info) =
+        CHANNEL_TO_EXECUTE_MSG.may_load(deps.storage, (channel_id.clone(), sequence))?
+    else {
+        return Ok(None);
+    };
+    let chain = ics20_packet_info
+        .recipient
+        .get_chain()
+        .ok_or(ContractError::InvalidPacket {
+            error: Some("Chain not provided".to_string()),
+        })?;
+    let channel_info =
+        CHAIN_TO_CHANNEL
+            .may_load(deps.storage, chain)?
+            .ok_or(ContractError::InvalidPacket {
+                error: Some(format!("Channel not found for chain {}", chain)),
+            })?;
+
+    handle_failed_transfer(
+        deps,
+        env,
+        sequence,
+        channel_info,
+        ics20_packet_info,
+        channel_id,
+    )
+    .map(Some)
+}
+
+/// Handles a failed or timed out ICS20 transfer according to the failure policy of its message.
+///
+/// The transfer is sent once more if the policy allows a retry and the transfer has not been retried yet,
+/// otherwise the funds are refunded to the recovery address or the original sender.
+fn handle_failed_transfer(
+    deps: DepsMut,
+    env: Env,
+    sequence: u64,
+    channel_info: ChannelInfo,
+    ics20_packet_info: Ics20PacketInfo,
+    channel_id: String,
+) -> Result<Response, ContractError> {
+    let chain = ics20_packet_info
+        .recipient
+        .get_chain()
+        .ok_or(ContractError::InvalidPacket {
+            error: Some("Chain not provided in recipient".to_string()),
+        })?
+        .to_string();
+    CHANNEL_TO_EXECUTE_MSG.remove(deps.storage, (channel_id.clone(), sequence));
+
+    let res = Response::default().add_attribute("action", "relay_packet");
+    let policy = ics20_packet_info.ibc_config.as_ref().map_or(
+        IBCFailurePolicy::RefundOrigin,
+        IBCConfig::get_failure_policy,
+    );
+
+    if policy == IBCFailurePolicy::RetryOnce && !ics20_packet_info.retried {
+        // An absolute timeout may have already passed, in which case the funds are refunded
+        if let Ok(timeout) = get_ibc_timeout(&env, ics20_packet_info.ibc_config.as_ref()) {
+            let msg = IbcMsg::Transfer {
+                channel_id: ics20_packet_info.channel.clone(),
+                to_address: channel_info.kernel_address,
+                amount: ics20_packet_info.funds.clone(),
+                timeout,
+                memo: Some(ibc_callback_memo(&env)),
+            };
+            let amount = ics20_packet_info.funds.to_string();
+            // The new sequence is stored by the transfer's reply
+            PENDING_MSG_AND_FUNDS.save(
+                deps.storage,
+                &Ics20PacketInfo {
+                    pending: false,
+                    retried: true,
+                    ..ics20_packet_info
+                },
+            )?;
+
+            return Ok(res
+                .add_submessage(SubMsg::reply_always(msg, ReplyId::IBCTransfer.repr()))
+                .add_attribute("relay_outcome", "retry")
+                .add_attribute("relay_sequence", sequence.to_string())
                 .add_attribute("relay_channel", channel_id)
                 .add_attribute("relay_chain", chain)
-                .add_attribute("recipient", ics20_packet_info.sender)
-                .add_attribute("amount", ics20_packet_info.funds.to_string()))
+                .add_attribute("amount", amount));
         }
     }
+
+    // Forwarded funds are recovered at this hop as the original sender is not on this chain
+    if let Some(forwarded) = ics20_packet_info.forwarded {
+        let funds = ics20_packet_info.funds;
+        let res = res
+            .add_attribute("relay_sequence", sequence.to_string())
+            .add_attribute("relay_channel", channel_id)
+            .add_attribute("relay_chain", chain)
+            .add_attribute("amount", funds.to_string());
+        return match forwarded.recovery_addr {
+            Some(recovery_addr) => {
+                record_recovery(
+                    deps.storage,
+                    env.block.time,
+                    &recovery_addr,
+                    funds,
+                    RecoveryReason::TransferFailed,
+                    Some(channel_id),
+                    Some(sequence),
+                )?;
+                Ok(res
+                    .add_attribute("relay_outcome", "recovery")
+                    .add_attribute("recipient", recovery_addr))
+            }
+            // Funds forwarded by another kernel retrace their route back to the origin chain
+            None if forwarded.previous_hops.len() > 1 => {
+                let recipient = forwarded.original_sender.clone();
+                let sub_msg = return_forwarded_funds(
+                    deps.storage,
+                    &env,
+                    forwarded.original_sender,
+                    forwarded.original_sender_username,
+                    &forwarded.previous_hops,
+                    forwarded.channel,
+                    funds,
+                )?;
+                Ok(res
+                    .add_submessage(sub_msg)
+                    .add_attribute("relay_outcome", "return")
+                    .add_attribute("recipient", recipient))
+            }
+            // The previous hop is the origin chain so the funds are returned over the channel they arrived on
+            None => Ok(res
+                .add_message(IbcMsg::Transfer {
+                    channel_id: forwarded.channel,
+                    to_address: forwarded.original_sender.clone(),
+                    amount: funds,
+                    timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+                    memo: None,
+                })
+                .add_attribute("relay_outcome", "return")
+                .add_attribute("recipient", forwarded.original_sender)),
+        };
+    }
+
+    let recovery_addr = ics20_packet_info
+        .ibc_config
+        .and_then(|ibc_config| ibc_config.recovery_addr);
+    let refund_recipient = match (policy, recovery_addr) {
+        (IBCFailurePolicy::RefundOrigin, _) | (_, None) => ics20_packet_info.sender,
+        (_, Some(recovery_addr)) => recovery_addr.to_string(),
+    };
+    let refund_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: refund_recipient.clone(),
+        amount: vec![ics20_packet_info.funds.clone()],
+    });
+
+    Ok(res
+        .add_message(refund_msg)
+        .add_attribute("relay_outcome", "refund")
+        .add_attribute("relay_sequence", sequence.to_string())
+        .add_attribute("relay_channel", channel_id)
+        .add_attribute("relay_chain", chain)
+        .add_attribute("recipient", refund_recipient)
+        .add_attribute("amount", ics20_packet_info.funds.to_string()))
+}
+
+/// Returns forwarded funds to the kernel of the previous hop, which relays them along the route they arrived by.
+///
+/// The funds are sent without a message so the kernel on the origin chain sends them to the original sender.
+pub(crate) fn return_forwarded_funds(
+    storage: &mut dyn Storage,
+    env: &Env,
+    original_sender: String,
+    original_sender_username: Option<AndrAddr>,
+    previous_hops: &[CrossChainHop],
+    channel: String,
+    funds: Coin,
+) -> Result<SubMsg, ContractError> {
+    let route = previous_hops
+        .iter()
+        .rev()
+        .map(|hop| hop.from_chain.as_str())
+        .collect::<Vec<&str>>();
+    let previous_chain = route.first().ok_or(ContractError::InvalidPacket {
+        error: Some("No previous hops to return the funds to".to_string()),
+    })?;
+    let channel_info = CHAIN_TO_CHANNEL.may_load(storage, previous_chain)?.ok_or(
+        ContractError::InvalidPacket {
+            error: Some(format!("Channel not found for chain {previous_chain}")),
+        },
+    )?;
+
+    let msg = IbcMsg::Transfer {
+        channel_id: channel.clone(),
+        to_address: channel_info.kernel_address,
+        amount: funds.clone(),
+        timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+        memo: Some(ibc_callback_memo(env)),
+    };
+    // The return is relayed as a new route starting at this chain, should it fail the funds are returned here
+    PENDING_MSG_AND_FUNDS.save(
+        storage,
+        &Ics20PacketInfo {
+            sender: env.contract.address.to_string(),
+            recipient: AndrAddr::from_route(&route, original_sender.clone()),
+            message: Binary::default(),
+            funds,
+            channel: channel.clone(),
+            pending: false,
+            ibc_config: None,
+            retried: false,
+            forwarded: Some(ForwardedFunds {
+                original_sender,
+                original_sender_username,
+                previous_hops: vec![],
+                channel,
+                recovery_addr: None,
+            }),
+        },
+    )?;
+    Ok(SubMsg::reply_always(msg, ReplyId::IBCTransfer.repr()))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -349,9 +701,10 @@ fn handle_ibc_transfer_funds_reply(
         })?;
 
     // TODO: We should send denom info to the counterparty chain with amp message
-    let (counterparty_denom, counterparty_denom_info) = get_counterparty_denom(
-        &deps.as_ref(),
-        &ics20_packet_info.funds.denom,
+    let denom_trace = get_denom_trace(&deps.as_ref(), &ics20_packet_info.funds.denom)?;
+    let (counterparty_denom, counterparty_denom_info) = AOSQuerier::get_counterparty_denom(
+        &deps.querier,
+        &denom_trace,
         &ics20_packet_info.channel,
     )?;
     #[allow(unused_assignments, unused_mut)]
@@ -360,7 +713,7 @@ fn handle_ibc_transfer_funds_reply(
     // Funds are not correctly hashed when using cw-orchestrator so instead we construct the denom manually
     #[cfg(not(target_arch = "wasm32"))]
     if counterparty_denom.starts_with("ibc/") {
-        let hops = path_to_hops(counterparty_denom_info.path)?;
+        let hops = path_to_hops(counterparty_denom_info.path.clone())?;
         // cw-orch doesn't correctly hash the denom so we need to manually construct it
         let adjusted_path = hops
             .iter()
@@ -375,33 +728,49 @@ fn handle_ibc_transfer_funds_reply(
         adjusted_funds = Coin::new(ics20_packet_info.funds.amount.u128(), new_denom);
     }
 
-    let mut ctx = AMPCtx::new(ics20_packet_info.sender.clone(), env.contract.address, None);
-
-    // Add the orginal sender's username if it exists
-    let potential_username = ctx.try_add_origin_username(
-        &deps.querier,
-        &KERNEL_ADDRESSES.load(deps.storage, VFS_KEY)?,
-    );
-
-    // Create a new hop to be appended to the context
+    let (original_sender, original_sender_username, mut previous_hops) =
+        match ics20_packet_info.forwarded.clone() {
+            // Forwarded funds keep the routing data from the origin chain
+            Some(ForwardedFunds {
+                original_sender,
+                original_sender_username,
+                previous_hops,
+                ..
+            }) => (original_sender, original_sender_username, previous_hops),
+            None => {
+                let mut ctx =
+                    AMPCtx::new(ics20_packet_info.sender.clone(), env.contract.address, None);
+
+                // Add the orginal sender's username if it exists
+                let potential_username = ctx.try_add_origin_username(
+                    &deps.querier,
+                    &KERNEL_ADDRESSES.load(deps.storage, VFS_KEY)?,
+                );
+                (
+                    ics20_packet_info.sender.clone(),
+                    potential_username.map(AndrAddr::from_string),
+                    vec![],
+                )
+            }
+        };
+    // Create a new hop to be appended to the previous hops
     let hop = CrossChainHop::new(
         &channel,
         CURR_CHAIN.load(deps.storage)?,
         chain.to_string(),
-        ics20_packet_info.sender.clone(),
-        potential_username.clone().map(AndrAddr::from_string),
+        original_sender.clone(),
+        original_sender_username.clone(),
     );
 
-    // Add the new hop to the context
-    ctx.add_hop(hop);
+    previous_hops.push(hop);
 
     let kernel_msg = IbcExecuteMsg::SendMessageWithFunds {
         recipient: AndrAddr::from_string(ics20_packet_info.recipient.clone().get_raw_path()),
         message: ics20_packet_info.message,
         funds: adjusted_funds,
-        original_sender: ics20_packet_info.sender,
-        original_sender_username: potential_username.map(AndrAddr::from_string),
-        previous_hops: ctx.previous_hops,
+        original_sender,
+        original_sender_username,
+        previous_hops,
     };
     let msg = IbcMsg::SendPacket {
         channel_id: channel.clone(),
@@ -440,26 +809,51 @@ pub fn amp_receive(
         }
     );
 
+    if packet.atomic {
+        return handle_atomic_bundle(deps, info, env, packet);
+    }
+
     let msg = packet.messages.first().unwrap();
 
+    let id = generate_or_validate_packet_id(deps, &env, packet.ctx.id.clone())?;
+    record_trace(
+        deps.storage,
+        &id,
+        &packet.ctx.get_origin(),
+        dispatched_entries(&env, &packet.ctx.previous_hops, &packet.messages[..1]),
+    )?;
+
+    // The first message reserves its gas from the packet's budget, the remaining messages share what is left
+    let mut remaining_ctx = packet.ctx.clone();
+    let mut hop_packet = packet.clone();
+    hop_packet.ctx.gas_budget =
+        remaining_ctx.reserve_gas(msg.config.gas_limit, packet.messages.len())?;
+    // Packets leaving the chain carry their ID so the receiving kernel continues the trace
+    if msg.recipient.get_protocol() == Some("ibc") {
+        hop_packet.ctx.id = Some(id.clone());
+    }
+
     let msg_res = handle(
         deps.branch(),
         info.clone(),
         env.clone(),
-        Some(packet.clone()),
+        Some(hop_packet),
         msg.clone(),
     )?;
 
-    res.messages.extend_from_slice(&msg_res.messages);
+    res.messages.extend(traced_sub_msgs(
+        deps.storage,
+        &id,
+        &info.sender,
+        msg,
+        msg_res.messages,
+    )?);
     res.attributes.extend_from_slice(&msg_res.attributes);
     res.events.extend_from_slice(&msg_res.events);
 
     let mut new_pkt = AMPPkt::from_ctx(Some(packet.clone()), env.contract.address.to_string());
-    new_pkt.ctx.id = Some(generate_or_validate_packet_id(
-        deps,
-        &env,
-        packet.ctx.id.clone(),
-    )?);
+    new_pkt.ctx.id = Some(id);
+    new_pkt.ctx.gas_budget = remaining_ctx.gas_budget;
 
     for (idx, message) in packet.messages.iter().enumerate() {
         if idx == 0 {
@@ -492,6 +886,115 @@ pub fn amp_receive(
     Ok(res.add_attribute("action", "handle_amp_packet"))
 }
 
+/// Dispatches every message of an atomic packet within a single sub message sent to the kernel itself.
+///
+/// If any of the messages fail the sub message is reverted as a whole, the reply then refunds the attached funds to the sender.
+fn handle_atomic_bundle(
+    deps: &mut DepsMut,
+    info: MessageInfo,
+    env: Env,
+    packet: AMPPkt,
+) -> Result<Response, ContractError> {
+    ensure!(
+        packet
+            .messages
+            .iter()
+            .all(|message| message.recipient.get_protocol().is_none()),
+        ContractError::InvalidPacket {
+            error: Some("Atomic bundles only support local recipients".to_string())
+        }
+    );
+
+    let message_funds = packet
+        .messages
+        .iter()
+        .flat_map(|m| m.funds.clone())
+        .collect::<Vec<Coin>>();
+    ensure!(
+        has_coins_merged(info.funds.as_slice(), message_funds.as_slice()),
+        ContractError::InsufficientFunds {}
+    );
+
+    let id = generate_or_validate_packet_id(deps, &env, packet.ctx.id.clone())?;
+    record_trace(
+        deps.storage,
+        &id,
+        &packet.ctx.get_origin(),
+        dispatched_entries(&env, &packet.ctx.previous_hops, &packet.messages),
+    )?;
+    let mut bundle = AMPPkt::new_with_ctx(packet.ctx.clone(), packet.messages.clone());
+    bundle.ctx.id = Some(id.clone());
+
+    let payload = AMPBundlePayload {
+        id: id.clone(),
+        sender: info.sender.to_string(),
+        funds: info.funds.clone(),
+        recipients: packet
+            .messages
+            .iter()
+            .map(|message| message.recipient.to_string())
+            .collect(),
+    };
+    let mut sub_msg = SubMsg::reply_always(
+        wasm_execute(
+            env.contract.address,
+            &ExecuteMsg::Internal(InternalMsg::ExecuteBundle { packet: bundle }),
+            info.funds,
+        )?,
+        ReplyId::AMPBundle.repr(),
+    )
+    .with_payload(to_json_binary(&payload)?);
+    // The whole bundle shares the packet's gas budget
+    apply_gas_budget(&mut sub_msg, packet.ctx.gas_budget);
+
+    Ok(Response::default()
+        .add_submessage(sub_msg)
+        .add_attribute("action", "handle_amp_bundle")
+        .add_attribute("id", id))
+}
+
+/// Executes all messages of an atomic bundle, restricted to the kernel itself
+pub fn execute_bundle(ctx: ExecuteContext, packet: AMPPkt) -> Result<Response, ContractError> {
+    let ExecuteContext {
+        mut deps,
+        info,
+        env,
+        ..
+    } = ctx;
+    ensure!(
+        info.sender == env.contract.address,
+        ContractError::Unauthorized {}
+    );
+
+    let mut res = Response::default();
+    // Each message reserves its gas from the bundle's budget, as with non-atomic packets
+    let mut remaining_ctx = packet.ctx.clone();
+    for (idx, message) in packet.messages.iter().enumerate() {
+        // Any unmet condition reverts the whole bundle
+        if let Some(condition) = &message.config.condition {
+            ensure!(
+                evaluate_condition(deps.as_ref(), condition)?,
+                ContractError::UnmetCondition {}
+            );
+        }
+        let mut hop_ctx = packet.ctx.clone();
+        hop_ctx.gas_budget =
+            remaining_ctx.reserve_gas(message.config.gas_limit, packet.messages.len() - idx)?;
+        let msg_res = handle_local(
+            deps.branch(),
+            info.clone(),
+            env.clone(),
+            Some(hop_ctx),
+            message.clone(),
+        )?;
+        res.messages.extend_from_slice(&msg_res.messages);
+        res.attributes.extend_from_slice(&msg_res.attributes);
+        res.events.extend_from_slice(&msg_res.events);
+    }
+
+    Ok(res.add_attribute("action", "execute_bundle"))
+}
+
 pub fn handle_cw20(
     deps: DepsMut,
     info: MessageInfo,
@@ -519,14 +1022,23 @@ pub fn amp_receive_cw20(
         query::verify_address(deps.as_ref(), info.sender.to_string(),)?.verify_address,
         ContractError::Unauthorized {}
     );
+    ensure!(
+        !packet.atomic,
+        ContractError::NotImplemented {
+            msg: Some("Atomic bundles are not supported for CW20".to_string()),
+        }
+    );
 
     let mut new_pkt = AMPPkt::from_ctx(Some(packet.clone()), env.contract.address.to_string());
 
-    new_pkt.ctx.id = Some(generate_or_validate_packet_id(
-        deps,
-        &env,
-        packet.ctx.id.clone(),
-    )?);
+    let id = generate_or_validate_packet_id(deps, &env, packet.ctx.id.clone())?;
+    record_trace(
+        deps.storage,
+        &id,
+        &packet.ctx.get_origin(),
+        dispatched_entries(&env, &packet.ctx.previous_hops, &packet.messages),
+    )?;
+    new_pkt.ctx.id = Some(id);
 
     let mut res = Response::default();
     ensure!(
@@ -536,12 +1048,16 @@ pub fn amp_receive_cw20(
         }
     );
 
-    for message in packet.messages.iter() {
+    let mut remaining_ctx = packet.ctx.clone();
+    for (idx, message) in packet.messages.iter().enumerate() {
+        let mut hop_packet = packet.clone();
+        hop_packet.ctx.gas_budget =
+            remaining_ctx.reserve_gas(message.config.gas_limit, packet.messages.len() - idx)?;
         let msg_res = handle_cw20(
             deps.branch(),
             info.clone(),
             env.clone(),
-            Some(packet.clone()),
+            Some(hop_packet),
             message.clone(),
         )?;
         res.messages.extend_from_slice(&msg_res.messages);
@@ -597,6 +1113,7 @@ pub fn create(
     msg: Binary,
     owner: Option<AndrAddr>,
     chain: Option<String>,
+    resolve_env: bool,
 ) -> Result<Response, ContractError> {
     let ExecuteContext {
         deps, env, info, ..
@@ -653,8 +1170,22 @@ pub fn create(
         let adodb_addr = KERNEL_ADDRESSES.load(deps.storage, ADO_DB_KEY)?;
 
         let ado_owner = owner.unwrap_or(AndrAddr::from_string(info.sender.to_string()));
-        let owner_addr = ado_owner.get_raw_address_from_vfs(&deps.as_ref(), vfs_addr)?;
+        let owner_addr = ado_owner.get_raw_address_from_vfs(&deps.as_ref(), vfs_addr.clone())?;
         let code_id = AOSQuerier::code_id_getter(&deps.querier, &adodb_addr, &ado_type)?;
+
+        let msg = if resolve_env {
+            // Environment variables are resolved from the creating app, then the owner's namespace and finally the global scope
+            let mut scopes = vec![app_scope_key(&info.sender)];
+            if let Ok(Some(username)) =
+                AOSQuerier::get_username(&deps.querier, &vfs_addr, &owner_addr)
+            {
+                scopes.push(user_scope_key(&username));
+            }
+            scopes.push(GLOBAL_SCOPE.to_string());
+            resolve_env_references(deps.storage, msg, &scopes)?
+        } else {
+            msg
+        };
         let wasm_msg = WasmMsg::Instantiate {
             admin: Some(owner_addr.to_string()),
             code_id,
@@ -681,6 +1212,10 @@ pub fn internal(ctx: ExecuteContext, msg: InternalMsg) -> Result<Response, Contr
             address,
             chain,
         } => register_user_cross_chain(ctx, chain, username, address),
+        InternalMsg::ExecuteBundle { packet } => execute_bundle(ctx, packet),
+        InternalMsg::ResolvePathCrossChain { path, chain } => {
+            resolve_path_cross_chain(ctx, chain, path)
+        }
     }
 }
 
@@ -736,6 +1271,45 @@ pub fn register_user_cross_chain(
         .add_message(ibc_msg))
 }
 
+/// Requests the address of a path from the kernel of the given chain, the address is sent to the VFS once acknowledged
+pub fn resolve_path_cross_chain(
+    execute_ctx: ExecuteContext,
+    chain: String,
+    path: String,
+) -> Result<Response, ContractError> {
+    let vfs = KERNEL_ADDRESSES.load(execute_ctx.deps.storage, VFS_KEY)?;
+    ensure!(
+        execute_ctx.info.sender == vfs,
+        ContractError::Unauthorized {}
+    );
+    let channel_id = CHAIN_TO_CHANNEL
+        .may_load(execute_ctx.deps.storage, &chain)?
+        .and_then(|channel_info| channel_info.direct_channel_id)
+        .ok_or(ContractError::InvalidPacket {
+            error: Some(format!("Channel not found for chain {chain}")),
+        })?;
+    let kernel_msg = IbcExecuteMsg::ResolvePath { path: path.clone() };
+    let ibc_msg = IbcMsg::SendPacket {
+        channel_id: channel_id.clone(),
+        data: to_json_binary(&kernel_msg)?,
+        timeout: execute_ctx
+            .env
+            .block
+            .time
+            .plus_seconds(PACKET_LIFETIME)
+            .into(),
+    };
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            attr("action", "resolve_path_cross_chain"),
+            attr("path", path),
+            attr("chain", chain),
+            attr("channel", channel_id),
+        ])
+        .add_message(ibc_msg))
+}
+
 pub fn assign_channels(
     execute_ctx: ExecuteContext,
     ics20_channel_id: Option<String>,
@@ -786,25 +1360,331 @@ pub fn assign_channels(
     ]))
 }
 
-pub fn recover(execute_ctx: ExecuteContext) -> Result<Response, ContractError> {
-    let recoveries = IBC_FUND_RECOVERY
-        .load(execute_ctx.deps.storage, &execute_ctx.info.sender)
-        .unwrap_or_default();
-    IBC_FUND_RECOVERY.remove(execute_ctx.deps.storage, &execute_ctx.info.sender);
+pub fn recover(
+    execute_ctx: ExecuteContext,
+    denom: Option<String>,
+    recipient: Option<AndrAddr>,
+) -> Result<Response, ContractError> {
+    let ExecuteContext {
+        deps, info, env, ..
+    } = execute_ctx;
+    let recipient = recipient.unwrap_or(AndrAddr::from_string(info.sender.to_string()));
+
+    // Funds are sent back over IBC to an address on the given chain
+    let ibc_destination = match recipient.get_chain() {
+        Some(chain) => {
+            let to_address = recipient.get_raw_path();
+            ensure!(
+                !AndrAddr::from_string(to_address).is_vfs_path(),
+                ContractError::InvalidAddress {}
+            );
+            let channel = CHAIN_TO_CHANNEL
+                .may_load(deps.storage, chain)?
+                .and_then(|channel_info| channel_info.ics20_channel_id)
+                .ok_or(ContractError::InvalidPacket {
+                    error: Some(format!("Channel not found for chain {chain}")),
+                })?;
+            Some((channel, to_address.to_string()))
+        }
+        None => None,
+    };
+
+    let records = take_recoveries(deps.storage, &info.sender, denom.as_deref())?;
     ensure!(
-        !recoveries.is_empty(),
+        !records.is_empty(),
         ContractError::Std(StdError::generic_err("No recoveries found"))
     );
+    let recoveries = total_amounts(&records);
 
-    let bank_msg = BankMsg::Send {
-        to_address: execute_ctx.info.sender.to_string(),
-        amount: recoveries,
+    let sub_msgs = match ibc_destination {
+        // IBC hooks report the outcome of the transfers back to the kernel, failed transfers are recovered again
+        Some((channel, to_address)) => recoveries
+            .iter()
+            .map(|amount| {
+                let payload = RecoveryPayload {
+                    owner: info.sender.clone(),
+                    amount: amount.clone(),
+                    channel: channel.clone(),
+                };
+                Ok(SubMsg::reply_always(
+                    IbcMsg::Transfer {
+                        channel_id: channel.clone(),
+                        to_address: to_address.clone(),
+                        amount: amount.clone(),
+                        timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+                        memo: Some(ibc_callback_memo(&env)),
+                    },
+                    ReplyId::Recovery.repr(),
+                )
+                .with_payload(to_json_binary(&payload)?))
+            })
+            .collect::<Result<Vec<SubMsg>, ContractError>>()?,
+        None => {
+            let bank_msg = BankMsg::Send {
+                to_address: recipient
+                    .get_raw_address_for(&deps.as_ref(), info.sender.as_str())?
+                    .to_string(),
+                amount: recoveries.clone(),
+            };
+            vec![SubMsg::reply_always(bank_msg, ReplyId::Recovery.repr())]
+        }
     };
-    let sub_msg = SubMsg::reply_always(bank_msg, ReplyId::Recovery.repr());
 
+    let ids = records
+        .iter()
+        .map(|record| record.id.to_string())
+        .collect::<Vec<String>>();
     Ok(Response::default()
         .add_attribute("action", "recover")
-        .add_submessage(sub_msg))
+        .add_attribute("recipient", recipient)
+        .add_attribute("recovery_ids", ids.join(","))
+        .add_attribute(
+            "amount",
+            recoveries
+                .iter()
+                .map(|coin| coin.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        )
+        .add_submessages(sub_msgs))
+}
+
+pub fn schedule_message(
+    execute_ctx: ExecuteContext,
+    message: AMPMsg,
+    expiry: Expiry,
+) -> Result<Response, ContractError> {
+    let ExecuteContext {
+        deps, info, env, ..
+    } = execute_ctx;
+    ensure!(
+        has_coins_merged(info.funds.as_slice(), message.funds.as_slice()),
+        ContractError::InsufficientFunds {}
+    );
+    // IBC transfers rely on a single pending packet and cannot be released in batches
+    ensure!(
+        message.recipient.get_protocol().is_none() || message.funds.is_empty(),
+        ContractError::InvalidPacket {
+            error: Some("Scheduled IBC messages cannot carry funds".to_string())
+        }
+    );
+    let execute_at = expiry.validate(&env.block)?.get_time(&env.block);
+
+    let id = NEXT_SCHEDULED_MESSAGE_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NEXT_SCHEDULED_MESSAGE_ID.save(deps.storage, &(id + 1))?;
+
+    SCHEDULED_MESSAGES.save(
+        deps.storage,
+        id,
+        &ScheduledMessage {
+            id,
+            sender: info.sender.clone(),
+            message,
+            execute_at,
+        },
+    )?;
+    SCHEDULE_QUEUE.save(deps.storage, (execute_at.milliseconds(), id), &Empty {})?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "schedule_message"),
+        attr("id", id.to_string()),
+        attr("sender", info.sender),
+        attr("execute_at", execute_at.to_string()),
+    ]))
+}
+
+const DEFAULT_PROCESS_LIMIT: u32 = 10;
+const MAX_PROCESS_LIMIT: u32 = 30;
+
+/// Releases due scheduled messages in the order they became due.
+///
+/// Messages that can no longer be dispatched are dropped and their funds refunded to the sender.
+/// Dispatched messages reply on error so that a failing recipient cannot block the queue.
+pub fn process_scheduled_messages(
+    execute_ctx: ExecuteContext,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let ExecuteContext { mut deps, env, .. } = execute_ctx;
+    let limit = limit
+        .unwrap_or(DEFAULT_PROCESS_LIMIT)
+        .min(MAX_PROCESS_LIMIT) as usize;
+    let now = Milliseconds::from_nanos(env.block.time.nanos()).milliseconds();
+
+    let due = SCHEDULE_QUEUE
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::inclusive((now, u64::MAX))),
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<Result<Vec<(u64, u64)>, StdError>>()?;
+
+    let mut res = Response::default();
+    for (execute_at, id) in due.iter() {
+        SCHEDULE_QUEUE.remove(deps.storage, (*execute_at, *id));
+        let ScheduledMessage {
+            sender, message, ..
+        } = SCHEDULED_MESSAGES.load(deps.storage, *id)?;
+        SCHEDULED_MESSAGES.remove(deps.storage, *id);
+
+        let funds = message.funds.clone();
+        let info = MessageInfo {
+            sender: sender.clone(),
+            funds: funds.clone(),
+        };
+        match handle(deps.branch(), info, env.clone(), None, message) {
+            Ok(msg_res) => {
+                let payload = to_json_binary(&ScheduledMessagePayload {
+                    id: *id,
+                    sender: sender.to_string(),
+                    funds,
+                })?;
+                res.messages.extend(
+                    msg_res
+                        .messages
+                        .into_iter()
+                        .map(|sub_msg| wrap_scheduled_sub_msg(sub_msg, &payload)),
+                );
+                res.attributes.extend_from_slice(&msg_res.attributes);
+                res.events.extend_from_slice(&msg_res.events);
+                res = res.add_attribute(format!("scheduled:{id}"), "released");
+            }
+            Err(err) => {
+                if !funds.is_empty() {
+                    res = res.add_message(BankMsg::Send {
+                        to_address: sender.to_string(),
+                        amount: funds,
+                    });
+                }
+                res = res.add_attribute(format!("scheduled:{id}"), format!("refunded: {err}"));
+            }
+        }
+    }
+
+    Ok(res
+        .add_attribute("action", "process_scheduled_messages")
+        .add_attribute("processed", due.len().to_string()))
+}
+
+/// Refunds the scheduled message on failure unless the sub message handles its own reply, such as IBC transfers recording their pending packets
+pub(crate) fn wrap_scheduled_sub_msg(sub_msg: SubMsg, payload: &Binary) -> SubMsg {
+    if sub_msg.reply_on != ReplyOn::Never && sub_msg.id != ReplyId::AMPMsg.repr() {
+        return sub_msg;
+    }
+    SubMsg {
+        id: ReplyId::ScheduledAMPMsg.repr(),
+        reply_on: ReplyOn::Error,
+        payload: payload.clone(),
+        ..sub_msg
+    }
+}
+
+pub fn cancel_scheduled_message(
+    execute_ctx: ExecuteContext,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let ExecuteContext { deps, info, .. } = execute_ctx;
+    let scheduled = SCHEDULED_MESSAGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::Std(StdError::not_found("ScheduledMessage")))?;
+    ensure!(
+        scheduled.sender == info.sender,
+        ContractError::Unauthorized {}
+    );
+
+    SCHEDULED_MESSAGES.remove(deps.storage, id);
+    SCHEDULE_QUEUE.remove(deps.storage, (scheduled.execute_at.milliseconds(), id));
+
+    let mut res = Response::default();
+    if !scheduled.message.funds.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: scheduled.sender.to_string(),
+            amount: scheduled.message.funds,
+        });
+    }
+
+    Ok(res.add_attributes(vec![
+        attr("action", "cancel_scheduled_message"),
+        attr("id", id.to_string()),
+        attr("sender", scheduled.sender),
+    ]))
+}
+
+pub fn set_trace_retention(
+    execute_ctx: ExecuteContext,
+    retention: Option<u64>,
+) -> Result<Response, ContractError> {
+    let contract = ADOContract::default();
+    ensure!(
+        contract.is_contract_owner(execute_ctx.deps.storage, execute_ctx.info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+
+    match retention {
+        Some(retention) => {
+            ensure!(retention > 0, ContractError::InvalidZeroAmount {});
+            TRACE_RETENTION.save(execute_ctx.deps.storage, &retention)?;
+        }
+        None => TRACE_RETENTION.remove(execute_ctx.deps.storage),
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_trace_retention"),
+        attr(
+            "retention",
+            retention.map_or("disabled".to_string(), |retention| retention.to_string()),
+        ),
+    ]))
+}
+
+pub fn set_rate_limit(
+    execute_ctx: ExecuteContext,
+    scope: RateLimitScope,
+    limit: Option<RateLimit>,
+) -> Result<Response, ContractError> {
+    let contract = ADOContract::default();
+    ensure!(
+        contract.is_contract_owner(execute_ctx.deps.storage, execute_ctx.info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+    if let Some(limit) = &limit {
+        ensure!(limit.window > 0, ContractError::InvalidZeroAmount {});
+    }
+
+    let deps = execute_ctx.deps;
+    let scope = match scope {
+        RateLimitScope::Origins {} => {
+            match &limit {
+                Some(limit) => DEFAULT_ORIGIN_RATE_LIMIT.save(deps.storage, limit)?,
+                None => DEFAULT_ORIGIN_RATE_LIMIT.remove(deps.storage),
+            }
+            "origins".to_string()
+        }
+        RateLimitScope::Origin { address } => {
+            match &limit {
+                Some(limit) => ORIGIN_RATE_LIMITS.save(deps.storage, &address, limit)?,
+                None => ORIGIN_RATE_LIMITS.remove(deps.storage, &address),
+            }
+            format!("origin:{address}")
+        }
+        RateLimitScope::Destination { address } => {
+            let address = address.get_raw_address(&deps.as_ref())?;
+            match &limit {
+                Some(limit) => DESTINATION_RATE_LIMITS.save(deps.storage, &address, limit)?,
+                None => DESTINATION_RATE_LIMITS.remove(deps.storage, &address),
+            }
+            format!("destination:{address}")
+        }
+    };
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_rate_limit"),
+        attr("scope", scope),
+        attr("removed", limit.is_none().to_string()),
+    ]))
 }
 
 pub fn update_chain_name(
@@ -838,50 +1718,19 @@ pub fn set_env(
         ContractError::Unauthorized {}
     );
 
-    ensure!(
-        !variable.is_empty(),
-        ContractError::InvalidEnvironmentVariable {
-            msg: "Environment variable name cannot be empty".to_string()
-        }
-    );
-
-    ensure!(
-        variable
-            .chars()
-            .all(|c| c.is_ascii_alphanumeric() || c == '_'),
-        ContractError::InvalidEnvironmentVariable {
-            msg:
-                "Environment variable name can only contain alphanumeric characters and underscores"
-                    .to_string()
-        }
-    );
-
-    ensure!(
-        variable.len() <= 100,
-        ContractError::InvalidEnvironmentVariable {
-            msg: "Environment variable name length exceeds the maximum allowed length of 100 characters".to_string()
-        }
-    );
-
-    ensure!(
-        !value.is_empty(),
-        ContractError::InvalidEnvironmentVariable {
-            msg: "Environment variable value cannot be empty".to_string()
-        }
-    );
-
-    ensure!(
-        value.len() <= 100,
-        ContractError::InvalidEnvironmentVariable {
-            msg: "Environment variable value length exceeds the maximum allowed length of 100 characters".to_string()
-        }
-    );
+    validate_env_variable(&variable)?;
+    validate_env_value(execute_ctx.deps.api, &EnvValue::String(value.clone()))?;
 
     ENV_VARIABLES.save(
         execute_ctx.deps.storage,
         &variable.to_ascii_uppercase(),
         &value,
     )?;
+    // Plain global variables replace typed ones
+    SCOPED_ENV_VARIABLES.remove(
+        execute_ctx.deps.storage,
+        (GLOBAL_SCOPE, &variable.to_ascii_uppercase()),
+    );
     Ok(Response::default()
         .add_attribute("action", "set_env")
         .add_attribute("variable", variable)
@@ -902,11 +1751,92 @@ pub fn unset_env(execute_ctx: ExecuteContext, variable: String) -> Result<Respon
         ContractError::EnvironmentVariableNotFound { variable }
     );
     ENV_VARIABLES.remove(execute_ctx.deps.storage, &variable.to_ascii_uppercase());
+    SCOPED_ENV_VARIABLES.remove(
+        execute_ctx.deps.storage,
+        (GLOBAL_SCOPE, &variable.to_ascii_uppercase()),
+    );
     Ok(Response::default()
         .add_attribute("action", "unset_env")
         .add_attribute("variable", variable))
 }
 
+/// Ensures the sender may manage the variables of the given scope
+fn ensure_can_manage_env(deps: Deps, sender: &Addr, scope: &EnvScope) -> Result<(), ContractError> {
+    let authorized = match scope {
+        EnvScope::Global {} => {
+            ADOContract::default().is_contract_owner(deps.storage, sender.as_str())?
+        }
+        EnvScope::App { address } => {
+            let app = address.get_raw_address(&deps)?;
+            *sender == app || *sender == AOSQuerier::ado_owner_getter(&deps.querier, &app)?
+        }
+        EnvScope::User { username } => {
+            let vfs_addr = KERNEL_ADDRESSES.load(deps.storage, VFS_KEY)?;
+            AOSQuerier::get_address_from_username(&deps.querier, &vfs_addr, username)?.as_ref()
+                == Some(sender)
+        }
+    };
+    ensure!(authorized, ContractError::Unauthorized {});
+    Ok(())
+}
+
+pub fn set_scoped_env(
+    execute_ctx: ExecuteContext,
+    scope: EnvScope,
+    variable: String,
+    value: EnvValue,
+) -> Result<Response, ContractError> {
+    let deps = execute_ctx.deps;
+    ensure_can_manage_env(deps.as_ref(), &execute_ctx.info.sender, &scope)?;
+    validate_env_variable(&variable)?;
+    validate_env_value(deps.api, &value)?;
+
+    let scope = scope_key(deps.as_ref(), &scope)?;
+    let variable_key = variable.to_ascii_uppercase();
+    SCOPED_ENV_VARIABLES.save(
+        deps.storage,
+        (scope.as_str(), variable_key.as_str()),
+        &value,
+    )?;
+    // Global variables remain readable as strings
+    if scope == GLOBAL_SCOPE {
+        ENV_VARIABLES.save(deps.storage, &variable_key, &value.to_string())?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_scoped_env"),
+        attr("scope", scope),
+        attr("variable", variable),
+        attr("value", value.to_string()),
+    ]))
+}
+
+pub fn unset_scoped_env(
+    execute_ctx: ExecuteContext,
+    scope: EnvScope,
+    variable: String,
+) -> Result<Response, ContractError> {
+    let deps = execute_ctx.deps;
+    ensure_can_manage_env(deps.as_ref(), &execute_ctx.info.sender, &scope)?;
+
+    let scope = scope_key(deps.as_ref(), &scope)?;
+    let variable_key = variable.to_ascii_uppercase();
+    ensure!(
+        SCOPED_ENV_VARIABLES.has(deps.storage, (scope.as_str(), variable_key.as_str())),
+        ContractError::EnvironmentVariableNotFound { variable }
+    );
+    SCOPED_ENV_VARIABLES.remove(deps.storage, (scope.as_str(), variable_key.as_str()));
+    if scope == GLOBAL_SCOPE {
+        ENV_VARIABLES.remove(deps.storage, &variable_key);
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "unset_scoped_env"),
+        attr("scope", scope),
+        attr("variable", variable),
+    ]))
+}
+
 /// Generates or validates a packet ID using chain ID, block height and transaction index
 pub(super) fn generate_or_validate_packet_id(
     deps: &mut DepsMut,
@@ -1012,6 +1942,12 @@ fn handle_ibc(
     ctx: Option<AMPPkt>,
     message: AMPMsg,
 ) -> Result<Response, ContractError> {
+    // Cross-chain messages only count towards the limits of their origin
+    let origin = ctx
+        .as_ref()
+        .map_or(info.sender.to_string(), |ctx| ctx.ctx.get_origin());
+    check_rate_limits(deps.storage, &env, &origin, None, &message.funds)?;
+
     // check if chain is provided
     let chain = message
         .recipient
@@ -1058,13 +1994,16 @@ fn build_ibc_packet(
                 "VFSAddressNotFound",
             )))?;
 
-    // Prepare message with appropriate content and config
+    // Prepare message with appropriate content and config, conditions are evaluated on the sending chain
     let amp_msg = AMPMsg::new(
         amp_message.recipient.get_raw_path(),
         amp_message.message.clone(),
         None,
     )
-    .with_config(amp_message.config.clone());
+    .with_config(AMPMsgConfig {
+        condition: None,
+        ..amp_message.config.clone()
+    });
 
     // Set up the context - either create new or use existing
     let mut ctx = if let Some(mut packet) = existing_packet {
@@ -1077,6 +2016,15 @@ fn build_ibc_packet(
         AMPCtx::new(info.sender.clone(), env.contract.address.clone(), None)
     };
 
+    ensure!(
+        ctx.previous_hops.len() < MAX_CROSS_CHAIN_HOPS,
+        ContractError::InvalidPacket {
+            error: Some(format!(
+                "Packets cannot be routed through more than {MAX_CROSS_CHAIN_HOPS} chains"
+            )),
+        }
+    );
+
     // Add username information if available
     let username = ctx.try_add_origin_username(&deps.querier, &vfs_address);
 
@@ -1180,7 +2128,7 @@ pub(crate) fn handle_ibc_direct(
     let msg = IbcMsg::SendPacket {
         channel_id: channel.clone(),
         data: to_json_binary(&kernel_msg)?,
-        timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+        timeout: get_ibc_timeout(&env, amp_message.config.ibc_config.as_ref())?,
     };
 
     Ok(Response::default()
@@ -1227,7 +2175,7 @@ pub(crate) fn handle_ibc_transfer_funds(
         recipient,
         message,
         funds,
-        ..
+        config,
     } = message;
 
     //check if chain is provided
@@ -1235,6 +2183,29 @@ pub(crate) fn handle_ibc_transfer_funds(
         error: Some("Chain not provided in recipient".to_string()),
     })?;
 
+    // Recovery addresses are resolved up front so that failed transfers can always be refunded
+    let ibc_config = config
+        .ibc_config
+        .map(|mut ibc_config| -> Result<IBCConfig, ContractError> {
+            if let Some(recovery_addr) = &ibc_config.recovery_addr {
+                let recovery_addr = recovery_addr.get_raw_address(&deps.as_ref())?;
+                ibc_config.recovery_addr = Some(AndrAddr::from_string(recovery_addr));
+            }
+            Ok(ibc_config)
+        })
+        .transpose()?;
+    if let Some(ibc_config) = &ibc_config {
+        ensure!(
+            ibc_config.get_failure_policy() != IBCFailurePolicy::RefundRecoveryAddr
+                || ibc_config.recovery_addr.is_some(),
+            ContractError::InvalidPacket {
+                error: Some(
+                    "Refunding to the recovery address requires a recovery address".to_string()
+                ),
+            }
+        );
+    }
+
     // We know the channel_info exists, but we need the specific ics20_channel_id
     let channel = channel_info
         .ics20_channel_id
@@ -1260,9 +2231,9 @@ pub(crate) fn handle_ibc_transfer_funds(
         channel_id: channel.clone(),
         to_address: channel_info.kernel_address.clone(),
         amount: coin.clone(),
-        timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+        timeout: get_ibc_timeout(&env, ibc_config.as_ref())?,
         // TODO allow optional memo
-        memo: None,
+        memo: Some(ibc_callback_memo(&env)),
     };
     let mut resp = Response::default();
 
@@ -1276,6 +2247,9 @@ pub(crate) fn handle_ibc_transfer_funds(
             funds: coin,
             channel: channel.clone(),
             pending: false,
+            ibc_config,
+            retried: false,
+            forwarded: None,
         },
     )?;
     resp = resp.add_submessage(SubMsg {
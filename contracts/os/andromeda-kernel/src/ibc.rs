@@ -1,30 +1,41 @@
-use crate::ack::{make_ack_fail, make_ack_success};
+use crate::ack::{make_ack_fail, make_ack_result, make_ack_success, Ack};
 use crate::execute;
-use crate::state::{CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, KERNEL_ADDRESSES, REFUND_DATA};
-use andromeda_std::amp::messages::{AMPCtx, AMPPkt};
+use crate::rate_limit::{check_rate_limits, remote_origin};
+use crate::state::{
+    RelayedTransfer, CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, CURR_CHAIN, KERNEL_ADDRESSES,
+    PENDING_MSG_AND_FUNDS, REFUND_DATA,
+};
+use crate::trace::{received_packet_id, trace_failed_ibc_packet, trace_received_ibc_packet};
+use andromeda_std::amp::messages::{AMPCtx, AMPPkt, IBCConfig, IBCFailurePolicy};
 use andromeda_std::amp::{IBC_REGISTRY_KEY, VFS_KEY};
 use andromeda_std::common::context::ExecuteContext;
 use andromeda_std::common::reply::ReplyId;
 use andromeda_std::error::{ContractError, Never};
 use andromeda_std::os::aos_querier::AOSQuerier;
-use andromeda_std::os::ibc_registry::DenomInfo;
-use andromeda_std::os::kernel::RefundData;
+use andromeda_std::os::ibc_registry::{DenomInfo, DenomTrace, ExecuteMsg as IBCRegistryExecuteMsg};
+use andromeda_std::os::kernel::{ForwardedFunds, RefundData};
 use andromeda_std::os::IBC_VERSION;
 use andromeda_std::{
     amp::{messages::AMPMsg, AndrAddr},
-    os::{kernel::IbcExecuteMsg, vfs::ExecuteMsg as VFSExecuteMsg},
+    os::{
+        kernel::IbcExecuteMsg,
+        vfs::{vfs_resolve_path, ExecuteMsg as VFSExecuteMsg},
+    },
 };
 use cosmwasm_schema::cw_serde;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env,
+    ensure, from_json, to_json_binary, wasm_execute, Addr, Binary, Deps, DepsMut, Empty, Env,
     Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannel, IbcChannelCloseMsg,
-    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg,
-    IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo, SubMsg, WasmMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout, MessageInfo, SubMsg,
+    Timestamp, WasmMsg,
 };
 
 pub const PACKET_LIFETIME: u64 = 604_800u64;
+/// The maximum number of chains a packet may be routed through
+pub const MAX_CROSS_CHAIN_HOPS: usize = 5;
 
 #[cw_serde]
 pub enum IBCLifecycleComplete {
@@ -45,17 +56,71 @@ pub enum SudoMsg {
     IBCLifecycleComplete(IBCLifecycleComplete),
 }
 
+/// Gets the timeout of an IBC packet sent at the current block, defaults to `PACKET_LIFETIME`
+pub fn get_ibc_timeout(
+    env: &Env,
+    ibc_config: Option<&IBCConfig>,
+) -> Result<IbcTimeout, ContractError> {
+    match ibc_config.and_then(|config| config.timeout.as_ref()) {
+        Some(timeout) => {
+            let time = timeout.validate(&env.block)?.get_time(&env.block);
+            Ok(IbcTimeout::with_timestamp(Timestamp::from_nanos(
+                time.nanos(),
+            )))
+        }
+        None => Ok(env.block.time.plus_seconds(PACKET_LIFETIME).into()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_timeout(
     _deps: DepsMut,
-    _env: Env,
-    _msg: IbcPacketTimeoutMsg,
+    env: Env,
+    msg: IbcPacketTimeoutMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
-    // As with ack above, nothing to do here. If we cared about
-    // keeping track of state between the two chains then we'd want to
-    // respond to this likely as it means that the packet in question
-    // isn't going anywhere.
-    Ok(IbcBasicResponse::new().add_attribute("method", "ibc_packet_timeout"))
+    let res = IbcBasicResponse::new().add_attribute("method", "ibc_packet_timeout");
+    Ok(retry_failed_packet(res, &env, &msg.packet))
+}
+
+/// Sends a failed packet once more if the failure policy of its message allows it.
+///
+/// The failure policy of the resent message is cleared so that a packet is retried at most once.
+/// Packets sent over the direct channel carry no funds, refunds are handled alongside the ICS20 transfers instead.
+fn retry_failed_packet(res: IbcBasicResponse, env: &Env, packet: &IbcPacket) -> IbcBasicResponse {
+    let Ok(IbcExecuteMsg::SendMessage { mut amp_packet }) =
+        from_json::<IbcExecuteMsg>(&packet.data)
+    else {
+        return res;
+    };
+    let Some(ibc_config) = amp_packet
+        .messages
+        .first()
+        .and_then(|message| message.config.ibc_config.clone())
+    else {
+        return res;
+    };
+    if ibc_config.get_failure_policy() != IBCFailurePolicy::RetryOnce {
+        return res;
+    }
+
+    // An absolute timeout may have already passed
+    let Ok(timeout) = get_ibc_timeout(env, Some(&ibc_config)) else {
+        return res.add_attribute("retry", "expired");
+    };
+    amp_packet.messages[0].config.ibc_config = Some(IBCConfig {
+        failure_policy: None,
+        ..ibc_config
+    });
+    let Ok(data) = to_json_binary(&IbcExecuteMsg::SendMessage { amp_packet }) else {
+        return res;
+    };
+
+    res.add_message(IbcMsg::SendPacket {
+        channel_id: packet.src.channel_id.clone(),
+        data,
+        timeout,
+    })
+    .add_attribute("retry", "sent")
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -97,28 +162,48 @@ pub fn ibc_channel_close(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_receive(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     msg: IbcPacketReceiveMsg,
 ) -> Result<IbcReceiveResponse, Never> {
+    let packet = msg.packet.clone();
     // Regardless of if our processing of this packet works we need to
     // commit an ACK to the chain. As such, we wrap all handling logic
     // in a seprate function and on error write out an error ack.
-    match do_ibc_packet_receive(deps, env, msg) {
-        Ok(response) => Ok(response),
-        Err(error) => Ok(IbcReceiveResponse::new(make_ack_fail(error.to_string()))
-            .add_attribute("method", "ibc_packet_receive")
-            .add_attribute("error", error.to_string())),
+    match do_ibc_packet_receive(deps.branch(), env.clone(), msg) {
+        Ok(response) => {
+            // Tracing is best effort and must never prevent the ack
+            let _ = trace_received_ibc_packet(deps.storage, &env, &packet);
+            Ok(response)
+        }
+        Err(error) => {
+            let _ = trace_failed_ibc_packet(deps.storage, &env, &packet, error.to_string());
+            Ok(IbcReceiveResponse::new(make_ack_fail(error.to_string()))
+                .add_attribute("method", "ibc_packet_receive")
+                .add_attribute("error", error.to_string()))
+        }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn ibc_packet_ack(
-    _deps: DepsMut,
-    _env: Env,
-    _msg: IbcPacketAckMsg,
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketAckMsg,
 ) -> Result<IbcBasicResponse, ContractError> {
-    Ok(IbcBasicResponse::new())
+    match from_json::<Ack>(&msg.acknowledgement.data) {
+        Ok(Ack::Error(error)) => {
+            let res = IbcBasicResponse::new()
+                .add_attribute("method", "ibc_packet_ack")
+                .add_attribute("error", error);
+            Ok(retry_failed_packet(res, &env, &msg.original_packet))
+        }
+        Ok(Ack::Result(data)) => {
+            let res = IbcBasicResponse::new().add_attribute("method", "ibc_packet_ack");
+            cache_resolved_path(deps.as_ref(), res, &msg.original_packet, &data)
+        }
+        _ => Ok(IbcBasicResponse::new()),
+    }
 }
 pub fn do_ibc_packet_receive(
     mut deps: DepsMut,
@@ -157,23 +242,37 @@ pub fn do_ibc_packet_receive(
                         username.as_str(),
                     )?;
                     if let Some(addr) = username_addr {
-                        let new_amp_packet =
+                        let mut new_amp_packet =
                             AMPPkt::new(addr, env.contract.address, amp_packet.messages.clone());
+                        // Keep the hops taken so far so the packet can be forwarded and traced
+                        new_amp_packet.ctx.previous_hops = amp_packet.ctx.previous_hops.clone();
+                        new_amp_packet.ctx.id = amp_packet.ctx.get_id();
                         execute_env.amp_ctx = Some(new_amp_packet.clone());
                     }
                 }
                 None => {
                     // Added this to keep track of original sender, even if the address is invalid on the receiving chain
-                    let new_amp_packet = AMPPkt::new(
+                    let mut new_amp_packet = AMPPkt::new(
                         amp_packet.ctx.get_origin(),
                         env.contract.address,
                         amp_packet.messages.clone(),
                     );
+                    new_amp_packet.ctx.previous_hops = amp_packet.ctx.previous_hops.clone();
+                    new_amp_packet.ctx.id = amp_packet.ctx.get_id();
                     execute_env.amp_ctx = Some(new_amp_packet);
                 }
             }
 
-            let res = execute::send(execute_env, amp_packet.messages.first().unwrap().clone())?;
+            let message = amp_packet.messages.first().unwrap().clone();
+            let res = match amp_packet.ctx.get_id() {
+                Some(_) => execute::send(execute_env, message)?,
+                None => execute::send_traced(
+                    execute_env,
+                    message,
+                    &received_packet_id(&msg.packet),
+                    &amp_packet.ctx.get_origin(),
+                )?,
+            };
 
             Ok(IbcReceiveResponse::new(make_ack_success())
                 .add_attributes(res.attributes)
@@ -206,12 +305,74 @@ pub fn do_ibc_packet_receive(
                 None
             };
             let msg = AMPMsg::new(recipient.clone(), message, Some(vec![funds.clone()]));
+
+            // Messages without a username on this chain are relayed by the kernel, so they are limited by their original sender
+            if username_addr.is_none() || recipient.get_protocol() == Some("ibc") {
+                let origin_chain = previous_hops
+                    .first()
+                    .map_or(chain.as_str(), |hop| hop.from_chain.as_str());
+                check_rate_limits(
+                    execute_env.deps.storage,
+                    &env,
+                    &remote_origin(origin_chain, &original_sender),
+                    None,
+                    &[funds.clone()],
+                )?;
+            }
+
+            // Refunds must be done via the ICS20 channel
+            let ics20_channel_id = channel_info.ics20_channel_id.ok_or(ContractError::new(
+                "Cannot refund, ICS20 Channel ID not set",
+            ))?;
+
+            // Funds routed to another chain are forwarded through this kernel
+            if recipient.get_protocol() == Some("ibc") {
+                ensure!(
+                    previous_hops.len() < MAX_CROSS_CHAIN_HOPS,
+                    ContractError::InvalidPacket {
+                        error: Some(format!(
+                            "Packets cannot be routed through more than {MAX_CROSS_CHAIN_HOPS} chains"
+                        )),
+                    }
+                );
+                let res = execute::send_traced(
+                    execute_env,
+                    msg,
+                    &received_packet_id(&msg.packet),
+                    &original_sender,
+                )?;
+                // Should the onward transfer fail the funds are recovered at this hop
+                PENDING_MSG_AND_FUNDS.update(deps.storage, |mut ics20_packet_info| {
+                    ics20_packet_info.forwarded = Some(ForwardedFunds {
+                        original_sender,
+                        original_sender_username,
+                        previous_hops,
+                        channel: ics20_channel_id,
+                        recovery_addr: username_addr,
+                    });
+                    Ok::<_, ContractError>(ics20_packet_info)
+                })?;
+
+                return Ok(IbcReceiveResponse::new(make_ack_success())
+                    .add_attribute("action", "forward_funds")
+                    .add_attribute("recipient", recipient.as_str())
+                    .add_attributes(res.attributes)
+                    .add_submessages(res.messages)
+                    .add_events(res.events));
+            }
+
+            // Funds of multi-hop packets can only be returned to the original sender's address on this chain
+            let recovery_addr = if previous_hops.len() > 1 {
+                username_addr.clone()
+            } else {
+                None
+            };
             match username_addr {
                 Some(addr) => {
                     // Add potential username to the context
                     let mut ctx = AMPCtx::new(addr, env.contract.address, original_sender_username);
                     // Add previous hops to the context
-                    for hop in previous_hops {
+                    for hop in previous_hops.clone() {
                         ctx.add_hop(hop);
                     }
 
@@ -224,12 +385,12 @@ pub fn do_ibc_packet_receive(
                 }
             }
 
-            let res = execute::send(execute_env, msg)?;
-
-            // Refunds must be done via the ICS20 channel
-            let ics20_channel_id = channel_info.ics20_channel_id.ok_or(ContractError::new(
-                "Cannot refund, ICS20 Channel ID not set",
-            ))?;
+            let res = execute::send_traced(
+                execute_env,
+                msg,
+                &received_packet_id(&msg.packet),
+                &original_sender,
+            )?;
             // Save refund info
             REFUND_DATA.save(
                 deps.storage,
@@ -237,6 +398,8 @@ pub fn do_ibc_packet_receive(
                     original_sender,
                     funds,
                     channel: ics20_channel_id,
+                    recovery_addr,
+                    previous_hops,
                 },
             )?;
             Ok(IbcReceiveResponse::new(make_ack_success())
@@ -256,6 +419,7 @@ pub fn do_ibc_packet_receive(
         IbcExecuteMsg::RegisterUsername { username, address } => {
             ibc_register_username(execute_env, username, address)
         }
+        IbcExecuteMsg::ResolvePath { path } => ibc_resolve_path(execute_env, path),
     }
 }
 
@@ -265,7 +429,7 @@ pub fn ibc_create_ado(
     ado_type: String,
     msg: Binary,
 ) -> Result<IbcReceiveResponse, ContractError> {
-    let res = execute::create(execute_ctx, ado_type, msg, Some(owner), None)?;
+    let res = execute::create(execute_ctx, ado_type, msg, Some(owner), None, false)?;
     Ok(IbcReceiveResponse::new(make_ack_success())
         .add_attributes(res.attributes)
         .add_events(res.events)
@@ -293,6 +457,56 @@ pub fn ibc_register_username(
     Ok(IbcReceiveResponse::new(make_ack_success()).add_submessage(sub_msg))
 }
 
+pub fn ibc_resolve_path(
+    execute_ctx: ExecuteContext,
+    path: String,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let vfs_address = KERNEL_ADDRESSES.load(execute_ctx.deps.storage, VFS_KEY)?;
+    let addr = vfs_resolve_path(path.clone(), vfs_address, &execute_ctx.deps.querier)?;
+    Ok(
+        IbcReceiveResponse::new(make_ack_result(to_json_binary(&addr)?))
+            .add_attribute("method", "ibc_resolve_path")
+            .add_attribute("path", path)
+            .add_attribute("address", addr),
+    )
+}
+
+/// Sends the address of a path resolved by another chain to the VFS to be cached
+fn cache_resolved_path(
+    deps: Deps,
+    res: IbcBasicResponse,
+    packet: &IbcPacket,
+    data: &Binary,
+) -> Result<IbcBasicResponse, ContractError> {
+    let Ok(IbcExecuteMsg::ResolvePath { path }) = from_json::<IbcExecuteMsg>(&packet.data) else {
+        return Ok(res);
+    };
+    let chain = CHANNEL_TO_CHAIN
+        .may_load(deps.storage, packet.src.channel_id.as_str())?
+        .ok_or(ContractError::InvalidPacket {
+            error: Some(format!(
+                "Chain not found for channel {}",
+                packet.src.channel_id
+            )),
+        })?;
+    let address: Addr = from_json(data)?;
+    let vfs_address = KERNEL_ADDRESSES.load(deps.storage, VFS_KEY)?;
+    let msg = VFSExecuteMsg::CacheRemotePath {
+        chain: chain.clone(),
+        path: path.clone(),
+        address: address.to_string(),
+    };
+    Ok(res
+        .add_message(WasmMsg::Execute {
+            contract_addr: vfs_address.to_string(),
+            msg: to_json_binary(&msg)?,
+            funds: vec![],
+        })
+        .add_attribute("chain", chain)
+        .add_attribute("path", path)
+        .add_attribute("address", address))
+}
+
 pub fn validate_order_and_version(
     channel: &IbcChannel,
     counterparty_version: Option<&str>,
@@ -335,21 +549,64 @@ pub fn validate_order_and_version(
     }))
 }
 
-pub fn get_counterparty_denom(
-    deps: &Deps,
-    denom: &str,
-    src_channel: &str,
-) -> Result<(String, DenomInfo), ContractError> {
+/// Returns the denom trace of a local denom, ibc denoms are looked up in the IBC registry
+pub fn get_denom_trace(deps: &Deps, denom: &str) -> Result<DenomInfo, ContractError> {
     // if denom is ibc denom, get denom trace
-    let denom_trace = if denom.starts_with("ibc/") {
+    if denom.starts_with("ibc/") {
         let ibc_registry_addr = KERNEL_ADDRESSES.load(deps.storage, IBC_REGISTRY_KEY)?;
-        AOSQuerier::denom_trace_getter(&deps.querier, &ibc_registry_addr, denom)?
+        AOSQuerier::denom_trace_getter(&deps.querier, &ibc_registry_addr, denom)
     } else {
         // if not ibc denom, use base denom
-        DenomInfo::new(denom.to_string(), "".to_string())
-    };
+        Ok(DenomInfo::new(denom.to_string(), "".to_string()))
+    }
+}
 
-    let (counterparty_denom, counterparty_denom_trace) =
-        AOSQuerier::get_counterparty_denom(&deps.querier, &denom_trace, src_channel)?;
-    Ok((counterparty_denom, counterparty_denom_trace))
+/// The memo of an ICS20 transfer asking IBC hooks to report its acknowledgement or timeout to the kernel
+pub fn ibc_callback_memo(env: &Env) -> String {
+    format!(r#"{{"ibc_callback":"{}"}}"#, env.contract.address)
+}
+
+/// Gets the denom traces of a relayed ICS20 transfer's funds on this chain and on the chain they were sent to
+pub fn relayed_denom_traces(
+    deps: &Deps,
+    channel: &str,
+    transfer: &RelayedTransfer,
+) -> Result<Vec<DenomTrace>, ContractError> {
+    let denom_trace = get_denom_trace(deps, &transfer.funds.denom)?;
+    let (counterparty_denom, counterparty_denom_info) =
+        AOSQuerier::get_counterparty_denom(&deps.querier, &denom_trace, channel)?;
+    Ok(vec![
+        DenomTrace {
+            chain: CURR_CHAIN.load(deps.storage)?,
+            denom: transfer.funds.denom.clone(),
+            denom_info: denom_trace,
+        },
+        DenomTrace {
+            chain: transfer.chain.clone(),
+            denom: counterparty_denom,
+            denom_info: counterparty_denom_info,
+        },
+    ])
+}
+
+/// Creates a message recording the denom traces of a relayed ICS20 transfer in the IBC registry.
+///
+/// Returns `None` if no IBC registry is registered with the kernel.
+/// The message replies on error so that a failure to record the traces does not fail the relay.
+pub fn record_denom_traces_msg(
+    deps: &Deps,
+    traces: Vec<DenomTrace>,
+) -> Result<Option<SubMsg>, ContractError> {
+    let Some(ibc_registry_addr) = KERNEL_ADDRESSES.may_load(deps.storage, IBC_REGISTRY_KEY)? else {
+        return Ok(None);
+    };
+    let msg = wasm_execute(
+        ibc_registry_addr,
+        &IBCRegistryExecuteMsg::RecordDenomTraces { traces },
+        vec![],
+    )?;
+    Ok(Some(SubMsg::reply_on_error(
+        msg,
+        ReplyId::RecordDenomTraces.repr(),
+    )))
 }
@@ -1,15 +1,20 @@
 pub mod ack;
+mod condition;
 pub mod contract;
+mod env;
 mod execute;
 pub mod ibc;
 #[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
 pub mod mock;
 mod proto;
 mod query;
+mod rate_limit;
+mod recovery;
 pub mod reply;
 mod signature;
 mod state;
 mod sudo;
+mod trace;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod interface;
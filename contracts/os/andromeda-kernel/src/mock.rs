@@ -45,6 +45,7 @@ pub fn mock_create(
         msg: to_json_binary(&msg).unwrap(),
         owner,
         chain,
+        resolve_env: false,
     }
 }
 
@@ -1,19 +1,27 @@
 use andromeda_std::{
-    amp::ADO_DB_KEY,
+    amp::{AndrAddr, ADO_DB_KEY},
     error::ContractError,
     os::{
         aos_querier::AOSQuerier,
         kernel::{
-            ChainNameResponse, ChannelInfoResponse, EnvResponse, PacketInfoAndSequence,
-            PendingPacketResponse, RecoveriesResponse, VerifyAddressResponse,
+            ChainNameResponse, ChannelInfoResponse, EnvResponse, EnvScope, PacketInfoAndSequence,
+            PacketTrace, PacketTracesResponse, PendingPacketResponse, RateLimitStatus,
+            RateLimitStatusResponse, RecoveriesResponse, RecoveryRecord, RecoveryRecordsResponse,
+            ScheduledMessage, ScheduledMessagesResponse, ScopedEnvResponse, VerifyAddressResponse,
         },
     },
 };
-use cosmwasm_std::{Addr, Deps, Order};
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
 
+use crate::env::{load_scoped_env, scope_key};
+use crate::rate_limit::{current_counter, origin_rate_limit};
+use crate::recovery::{owner_recoveries, total_amounts};
 use crate::state::{
-    CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, CHANNEL_TO_EXECUTE_MSG, CURR_CHAIN, ENV_VARIABLES,
-    IBC_FUND_RECOVERY, KERNEL_ADDRESSES,
+    CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, CHANNEL_TO_EXECUTE_MSG, CURR_CHAIN,
+    DESTINATION_RATE_COUNTERS, DESTINATION_RATE_LIMITS, ENV_VARIABLES, KERNEL_ADDRESSES,
+    ORIGIN_RATE_COUNTERS, ORIGIN_TRACES, PACKET_TRACES, RECOVERY_RECORDS, SCHEDULED_MESSAGES,
+    TRACE_SEQUENCES,
 };
 
 pub fn key_address(deps: Deps, key: String) -> Result<Addr, ContractError> {
@@ -62,10 +70,10 @@ pub fn chain_name_by_channel(deps: Deps, channel: String) -> Result<Option<Strin
 }
 
 pub fn recoveries(deps: Deps, addr: Addr) -> Result<RecoveriesResponse, ContractError> {
+    let records = owner_recoveries(deps.storage, &addr)?;
     Ok(RecoveriesResponse {
-        recoveries: IBC_FUND_RECOVERY
-            .may_load(deps.storage, &addr)?
-            .unwrap_or_default(),
+        recoveries: total_amounts(&records),
+        records,
     })
 }
 
@@ -107,3 +115,138 @@ pub fn get_env(deps: Deps, variable: String) -> Result<EnvResponse, ContractErro
         value: ENV_VARIABLES.may_load(deps.storage, &variable.to_ascii_uppercase())?,
     })
 }
+
+pub fn get_scoped_env(
+    deps: Deps,
+    scope: EnvScope,
+    variable: String,
+) -> Result<ScopedEnvResponse, ContractError> {
+    let scope = scope_key(deps, &scope)?;
+    Ok(ScopedEnvResponse {
+        value: load_scoped_env(deps.storage, &[scope], &variable)?,
+    })
+}
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 100;
+
+pub fn scheduled_messages(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<ScheduledMessagesResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let messages = SCHEDULED_MESSAGES
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, message)| message))
+        .collect::<StdResult<Vec<ScheduledMessage>>>()?;
+    Ok(ScheduledMessagesResponse { messages })
+}
+
+pub fn all_recoveries(
+    deps: Deps,
+    denom: Option<String>,
+    chain: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<RecoveryRecordsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let records = RECOVERY_RECORDS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        // Errors are kept so they are returned by the collect below
+        .filter(|item| match item {
+            Ok((_, record)) => {
+                denom.iter().all(|denom| record.amount.denom == *denom)
+                    && chain
+                        .iter()
+                        .all(|chain| record.chain.as_ref() == Some(chain))
+            }
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<Vec<RecoveryRecord>>>()?;
+    Ok(RecoveryRecordsResponse { records })
+}
+
+pub fn packet_trace(deps: Deps, id: String) -> Result<Option<PacketTrace>, ContractError> {
+    Ok(PACKET_TRACES.may_load(deps.storage, &id)?)
+}
+
+pub fn packet_traces(
+    deps: Deps,
+    origin: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<PacketTracesResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let traces = ORIGIN_TRACES
+        .prefix(origin.as_str())
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|sequence| {
+            let id = TRACE_SEQUENCES.load(deps.storage, sequence?)?;
+            PACKET_TRACES.load(deps.storage, &id)
+        })
+        .collect::<StdResult<Vec<PacketTrace>>>()?;
+    Ok(PacketTracesResponse { traces })
+}
+
+pub fn rate_limit_status(
+    deps: Deps,
+    env: Env,
+    origin: Option<String>,
+    destination: Option<AndrAddr>,
+) -> Result<RateLimitStatusResponse, ContractError> {
+    let origin = match origin {
+        Some(origin) => match origin_rate_limit(deps.storage, &origin)? {
+            Some(limit) => {
+                let counter = ORIGIN_RATE_COUNTERS.may_load(deps.storage, &origin)?;
+                Some(RateLimitStatus {
+                    counter: current_counter(&limit, counter, env.block.height),
+                    limit,
+                })
+            }
+            None => None,
+        },
+        None => None,
+    };
+
+    let destination = match destination {
+        Some(destination) => {
+            let destination = destination.get_raw_address(&deps)?;
+            match DESTINATION_RATE_LIMITS.may_load(deps.storage, &destination)? {
+                Some(limit) => {
+                    let counter = DESTINATION_RATE_COUNTERS.may_load(deps.storage, &destination)?;
+                    Some(RateLimitStatus {
+                        counter: current_counter(&limit, counter, env.block.height),
+                        limit,
+                    })
+                }
+                None => None,
+            }
+        }
+        None => None,
+    };
+
+    Ok(RateLimitStatusResponse {
+        origin,
+        destination,
+    })
+}
@@ -0,0 +1,109 @@
+use crate::state::{
+    DEFAULT_ORIGIN_RATE_LIMIT, DESTINATION_RATE_COUNTERS, DESTINATION_RATE_LIMITS,
+    ORIGIN_RATE_COUNTERS, ORIGIN_RATE_LIMITS,
+};
+use andromeda_std::{
+    error::ContractError,
+    os::kernel::{RateLimit, RateLimitCounter},
+};
+use cosmwasm_std::{Addr, Coin, Env, StdResult, Storage};
+
+/// Gets the rate limit that applies to the given origin, origin specific limits take precedence over the default limit
+pub fn origin_rate_limit(storage: &dyn Storage, origin: &str) -> StdResult<Option<RateLimit>> {
+    match ORIGIN_RATE_LIMITS.may_load(storage, origin)? {
+        Some(limit) => Ok(Some(limit)),
+        None => DEFAULT_ORIGIN_RATE_LIMIT.may_load(storage),
+    }
+}
+
+/// Gets the counter of the window containing the given block height, counters of previous windows are reset
+pub fn current_counter(
+    limit: &RateLimit,
+    counter: Option<RateLimitCounter>,
+    height: u64,
+) -> RateLimitCounter {
+    let window_start = height - height % limit.window;
+    match counter {
+        Some(counter) if counter.window_start == window_start => counter,
+        _ => RateLimitCounter {
+            window_start,
+            messages: 0,
+            funds: vec![],
+        },
+    }
+}
+
+/// The origin under which messages received from a sender on another chain are limited
+pub fn remote_origin(chain: &str, sender: &str) -> String {
+    format!("ibc://{chain}/{sender}")
+}
+
+/// Counts a relayed message and its funds against the rate limits of its origin and destination.
+///
+/// The kernel is not limited as an origin, the messages it relays on behalf of remote senders are limited by their [`remote_origin`] when received.
+/// Errors if either limit is exceeded within its current window.
+pub fn check_rate_limits(
+    storage: &mut dyn Storage,
+    env: &Env,
+    origin: &str,
+    destination: Option<&Addr>,
+    funds: &[Coin],
+) -> Result<(), ContractError> {
+    let origin_limit = match origin == env.contract.address.as_str() {
+        true => None,
+        false => origin_rate_limit(storage, origin)?,
+    };
+    if let Some(limit) = origin_limit {
+        let counter = ORIGIN_RATE_COUNTERS.may_load(storage, origin)?;
+        let counter = count_message(&limit, counter, env.block.height, funds).ok_or(
+            ContractError::RateLimitExceeded {
+                scope: format!("origin {origin}"),
+            },
+        )?;
+        ORIGIN_RATE_COUNTERS.save(storage, origin, &counter)?;
+    }
+
+    let Some(destination) = destination else {
+        return Ok(());
+    };
+    if let Some(limit) = DESTINATION_RATE_LIMITS.may_load(storage, destination)? {
+        let counter = DESTINATION_RATE_COUNTERS.may_load(storage, destination)?;
+        let counter = count_message(&limit, counter, env.block.height, funds).ok_or(
+            ContractError::RateLimitExceeded {
+                scope: format!("destination {destination}"),
+            },
+        )?;
+        DESTINATION_RATE_COUNTERS.save(storage, destination, &counter)?;
+    }
+    Ok(())
+}
+
+/// Adds a message to the counter of the current window, returns `None` if the limit is exceeded
+fn count_message(
+    limit: &RateLimit,
+    counter: Option<RateLimitCounter>,
+    height: u64,
+    funds: &[Coin],
+) -> Option<RateLimitCounter> {
+    let mut counter = current_counter(limit, counter, height);
+    counter.messages += 1;
+    for coin in funds {
+        match counter.funds.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(counted) => counted.amount = counted.amount.checked_add(coin.amount).ok()?,
+            None => counter.funds.push(coin.clone()),
+        }
+    }
+
+    let within_limit = limit
+        .max_messages
+        .iter()
+        .all(|max| counter.messages <= *max)
+        && limit.max_funds.iter().all(|max| {
+            counter
+                .funds
+                .iter()
+                .filter(|counted| counted.denom == max.denom)
+                .all(|counted| counted.amount <= max.amount)
+        });
+    within_limit.then_some(counter)
+}
@@ -0,0 +1,108 @@
+use crate::state::{
+    CHANNEL_TO_CHAIN, IBC_FUND_RECOVERY, NEXT_RECOVERY_ID, OWNER_RECOVERIES, RECOVERY_RECORDS,
+};
+use andromeda_std::{
+    error::ContractError,
+    os::kernel::{RecoveryReason, RecoveryRecord},
+};
+use cosmwasm_std::{Addr, Coin, Empty, Order, StdResult, Storage, Timestamp};
+
+/// Records funds to be recovered by the given owner, the chain is derived from the channel
+pub fn record_recovery(
+    storage: &mut dyn Storage,
+    timestamp: Timestamp,
+    owner: &Addr,
+    amount: Coin,
+    reason: RecoveryReason,
+    channel: Option<String>,
+    sequence: Option<u64>,
+) -> Result<u64, ContractError> {
+    let id = NEXT_RECOVERY_ID.may_load(storage)?.unwrap_or(1);
+    NEXT_RECOVERY_ID.save(storage, &(id + 1))?;
+
+    let chain = match &channel {
+        Some(channel) => CHANNEL_TO_CHAIN.may_load(storage, channel)?,
+        None => None,
+    };
+    RECOVERY_RECORDS.save(
+        storage,
+        id,
+        &RecoveryRecord {
+            id,
+            owner: owner.clone(),
+            amount,
+            reason,
+            chain,
+            channel,
+            sequence,
+            timestamp,
+        },
+    )?;
+    OWNER_RECOVERIES.save(storage, (owner, id), &Empty {})?;
+    Ok(id)
+}
+
+/// Removes and returns the owner's recovery records, optionally only those of the given denom
+pub fn take_recoveries(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    denom: Option<&str>,
+) -> Result<Vec<RecoveryRecord>, ContractError> {
+    let records = owner_recoveries(storage, owner)?
+        .into_iter()
+        .filter(|record| denom.iter().all(|denom| record.amount.denom == *denom))
+        .collect::<Vec<RecoveryRecord>>();
+    for record in &records {
+        RECOVERY_RECORDS.remove(storage, record.id);
+        OWNER_RECOVERIES.remove(storage, (owner, record.id));
+    }
+    Ok(records)
+}
+
+pub fn owner_recoveries(storage: &dyn Storage, owner: &Addr) -> StdResult<Vec<RecoveryRecord>> {
+    OWNER_RECOVERIES
+        .prefix(owner)
+        .keys(storage, None, None, Order::Ascending)
+        .map(|id| RECOVERY_RECORDS.load(storage, id?))
+        .collect()
+}
+
+/// Sums the amounts of the given records by denom
+pub fn total_amounts(records: &[RecoveryRecord]) -> Vec<Coin> {
+    let mut total: Vec<Coin> = vec![];
+    for record in records {
+        match total
+            .iter_mut()
+            .find(|coin| coin.denom == record.amount.denom)
+        {
+            Some(coin) => coin.amount += record.amount.amount,
+            None => total.push(record.amount.clone()),
+        }
+    }
+    total
+}
+
+/// Converts the recoveries stored before recovery records were introduced
+pub fn migrate_legacy_recoveries(
+    storage: &mut dyn Storage,
+    timestamp: Timestamp,
+) -> Result<(), ContractError> {
+    let legacy = IBC_FUND_RECOVERY
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, Vec<Coin>)>>>()?;
+    for (owner, amounts) in legacy {
+        for amount in amounts {
+            record_recovery(
+                storage,
+                timestamp,
+                &owner,
+                amount,
+                RecoveryReason::Legacy,
+                None,
+                None,
+            )?;
+        }
+        IBC_FUND_RECOVERY.remove(storage, &owner);
+    }
+    Ok(())
+}
@@ -1,21 +1,67 @@
 use crate::{
+    execute::return_forwarded_funds,
     ibc::PACKET_LIFETIME,
     proto::MsgTransferResponse,
-    state::{ADO_OWNER, CHANNEL_TO_EXECUTE_MSG, PENDING_MSG_AND_FUNDS, REFUND_DATA},
+    recovery::record_recovery,
+    state::{
+        OutgoingPacket, RelayedTransfer, ADO_OWNER, CHANNEL_TO_EXECUTE_MSG, OUTGOING_IBC_PACKETS,
+        PENDING_MSG_AND_FUNDS, REFUND_DATA, RELAYED_TRANSFERS,
+    },
+    trace::{update_trace_entry, update_trace_status},
 };
 use andromeda_std::{
     ado_base::{ownership::OwnershipMessage, AndromedaMsg},
     common::reply::ReplyId,
     common::response::get_reply_address,
     error::ContractError,
-    os::aos_querier::AOSQuerier,
+    os::{
+        aos_querier::AOSQuerier,
+        kernel::{PacketTraceStatus, RecoveryReason},
+    },
 };
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    to_json_string, wasm_execute, Addr, CosmosMsg, DepsMut, Empty, Env, IbcMsg, Reply, Response,
-    SubMsg, SubMsgResponse, SubMsgResult,
+    from_json, to_json_string, wasm_execute, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Empty, Env,
+    Event, IbcMsg, Reply, Response, StdError, Storage, SubMsg, SubMsgResponse, SubMsgResult,
 };
 use prost::Message;
 
+/// Attached as the payload of an atomic bundle's sub message so the reply can summarise its outcome
+#[cw_serde]
+pub struct AMPBundlePayload {
+    pub id: String,
+    pub sender: String,
+    pub funds: Vec<Coin>,
+    pub recipients: Vec<String>,
+}
+
+/// Attached as the payload of a recovery's ICS20 transfer so the funds can be recovered again should the transfer fail
+#[cw_serde]
+pub struct RecoveryPayload {
+    pub owner: Addr,
+    pub amount: Coin,
+    pub channel: String,
+}
+
+/// Attached as the payload of an AMP message's sub messages so the reply can record their result
+#[cw_serde]
+pub struct AMPMsgPayload {
+    pub id: String,
+    /// The index of the message's entry in the packet's trace, `None` if the packet is not traced
+    pub entry: Option<u64>,
+    pub sender: String,
+    pub funds: Vec<Coin>,
+    pub exit_at_error: bool,
+}
+
+/// Attached as the payload of a released scheduled message so its funds can be refunded on failure
+#[cw_serde]
+pub struct ScheduledMessagePayload {
+    pub id: u64,
+    pub sender: String,
+    pub funds: Vec<Coin>,
+}
+
 /// Handles the reply from an ADO creation
 ///
 /// Sends an execute message to assign the new owner to the ADO
@@ -54,13 +100,7 @@ pub fn on_reply_ibc_transfer(
                 error: Some(format!("could not decode response: {b}")),
             })?;
 
-        let pending_execute_msg = PENDING_MSG_AND_FUNDS.load(deps.storage)?;
-        CHANNEL_TO_EXECUTE_MSG.save(
-            deps.storage,
-            (pending_execute_msg.channel.clone(), sequence),
-            &pending_execute_msg,
-        )?;
-        PENDING_MSG_AND_FUNDS.remove(deps.storage);
+        store_sent_transfer(deps.storage, sequence)?;
         return Ok(Response::new()
             .add_attribute("action", "transfer_funds_reply")
             .add_attribute("sequence", sequence.to_string()));
@@ -100,16 +140,7 @@ pub fn on_reply_ibc_transfer(
                 .find(|attr| attr.key == "packet_dst_channel")
                 .map(|attr| attr.value.clone())
                 .unwrap_or_default();
-            let pending_execute_msg = PENDING_MSG_AND_FUNDS.load(deps.storage)?;
-            CHANNEL_TO_EXECUTE_MSG.save(
-                deps.storage,
-                (
-                    pending_execute_msg.channel.clone(),
-                    packet_sequence.parse().unwrap(),
-                ),
-                &pending_execute_msg,
-            )?;
-            PENDING_MSG_AND_FUNDS.remove(deps.storage);
+            store_sent_transfer(deps.storage, packet_sequence.parse().unwrap())?;
             // You can now use these extracted values as needed
             // For example, you might want to store them or include them in the response
             return Ok(Response::new()
@@ -140,6 +171,82 @@ pub fn on_reply_ibc_transfer(
         .add_attribute("reply", to_json_string(&msg)?))
 }
 
+/// Stores the pending message of a sent ICS20 transfer against its sequence, to be relayed once the transfer is acknowledged
+fn store_sent_transfer(storage: &mut dyn Storage, sequence: u64) -> Result<(), ContractError> {
+    let pending_execute_msg = PENDING_MSG_AND_FUNDS.load(storage)?;
+    CHANNEL_TO_EXECUTE_MSG.save(
+        storage,
+        (pending_execute_msg.channel.clone(), sequence),
+        &pending_execute_msg,
+    )?;
+    if let Some(chain) = pending_execute_msg.recipient.get_chain() {
+        RELAYED_TRANSFERS.save(
+            storage,
+            (&pending_execute_msg.channel, sequence),
+            &RelayedTransfer {
+                chain: chain.to_string(),
+                funds: pending_execute_msg.funds.clone(),
+            },
+        )?;
+    }
+    PENDING_MSG_AND_FUNDS.remove(storage);
+    Ok(())
+}
+
+/// Stores the packet of a recovery's ICS20 transfer, a failed acknowledgement or timeout records the recovery again
+pub fn on_reply_recovery(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    // Recoveries sent to an address on this chain are complete
+    if msg.payload.is_empty() {
+        return Ok(Response::default());
+    }
+    let RecoveryPayload {
+        owner,
+        amount,
+        channel,
+    } = from_json(&msg.payload)?;
+    let sequence = transfer_sequence(&msg)?;
+    OUTGOING_IBC_PACKETS.save(
+        deps.storage,
+        (&channel, sequence),
+        &OutgoingPacket {
+            recovery_addr: owner,
+            amount,
+        },
+    )?;
+    Ok(Response::default()
+        .add_attribute("action", "recovery_transfer_reply")
+        .add_attribute("channel", channel)
+        .add_attribute("sequence", sequence.to_string()))
+}
+
+/// Gets the sequence of the packet sent by a successful ICS20 transfer
+#[allow(deprecated)]
+fn transfer_sequence(msg: &Reply) -> Result<u64, ContractError> {
+    if let SubMsgResult::Ok(SubMsgResponse { data: Some(b), .. }) = &msg.result {
+        let MsgTransferResponse { sequence } =
+            MsgTransferResponse::decode(&b[..]).map_err(|_e| ContractError::InvalidPacket {
+                error: Some(format!("could not decode response: {b}")),
+            })?;
+        return Ok(sequence);
+    }
+
+    // When the reply is from a non-wasm32 target, the sequence is pulled from the events
+    if let SubMsgResult::Ok(SubMsgResponse { events, .. }) = &msg.result {
+        let sequence = events
+            .iter()
+            .filter(|event| event.ty == "send_packet")
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == "packet_sequence")
+            .and_then(|attr| attr.value.parse().ok());
+        if let Some(sequence) = sequence {
+            return Ok(sequence);
+        }
+    }
+    Err(ContractError::InvalidPacket {
+        error: Some("Transfer sequence not found".to_string()),
+    })
+}
+
 // Handles the reply from an Execute Msg that was preceded by an ICS20 transfer
 pub fn on_reply_refund_ibc_transfer_with_msg(
     deps: DepsMut,
@@ -148,6 +255,52 @@ pub fn on_reply_refund_ibc_transfer_with_msg(
 ) -> Result<Response, ContractError> {
     let err = msg.result.unwrap_err();
     let refund_data = REFUND_DATA.load(deps.storage)?;
+    REFUND_DATA.remove(deps.storage);
+
+    // Funds of multi-hop packets are recovered at this hop
+    if let Some(recovery_addr) = refund_data.recovery_addr {
+        record_recovery(
+            deps.storage,
+            env.block.time,
+            &recovery_addr,
+            refund_data.funds.clone(),
+            RecoveryReason::ExecutionFailed,
+            Some(refund_data.channel.clone()),
+            None,
+        )?;
+        return Ok(Response::default().add_attributes(vec![
+            ("action", "recover_ibc_transfer_with_msg"),
+            ("recovery_addr", recovery_addr.as_str()),
+            ("amount", refund_data.funds.to_string().as_str()),
+            ("error", err.to_string().as_str()),
+        ]));
+    }
+
+    // Funds forwarded by another kernel retrace their route back to the origin chain
+    if refund_data.previous_hops.len() > 1 {
+        let username = refund_data
+            .previous_hops
+            .first()
+            .and_then(|hop| hop.username.clone());
+        let sub_msg = return_forwarded_funds(
+            deps.storage,
+            &env,
+            refund_data.original_sender.clone(),
+            username,
+            &refund_data.previous_hops,
+            refund_data.channel,
+            refund_data.funds.clone(),
+        )?;
+        return Ok(Response::default()
+            .add_submessage(sub_msg)
+            .add_attributes(vec![
+                ("action", "return_ibc_transfer_with_msg"),
+                ("recipient", refund_data.original_sender.as_str()),
+                ("amount", refund_data.funds.to_string().as_str()),
+                ("error", err.to_string().as_str()),
+            ]));
+    }
+
     // Construct the refund message
     let refund_msg = IbcMsg::Transfer {
         channel_id: refund_data.channel,
@@ -156,7 +309,6 @@ pub fn on_reply_refund_ibc_transfer_with_msg(
         timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
         memo: None,
     };
-    REFUND_DATA.remove(deps.storage);
     Ok(Response::default()
         .add_message(refund_msg)
         .add_attributes(vec![
@@ -166,3 +318,155 @@ pub fn on_reply_refund_ibc_transfer_with_msg(
             ("error", err.to_string().as_str()),
         ]))
 }
+
+/// Handles the reply from an atomic AMP bundle
+///
+/// Emits a summary event listing the outcome of each message, refunding the sender if the bundle was reverted
+pub fn on_reply_amp_bundle(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let AMPBundlePayload {
+        id,
+        sender,
+        funds,
+        recipients,
+    } = from_json(&msg.payload)?;
+
+    let mut res = Response::default();
+    let mut event = Event::new("amp_bundle")
+        .add_attribute("id", id.clone())
+        .add_attribute("sender", sender.clone());
+
+    let outcome = match msg.result {
+        SubMsgResult::Ok(_) => {
+            update_trace_status(
+                deps.storage,
+                &id,
+                recipients.len(),
+                PacketTraceStatus::Success,
+                None,
+            )?;
+            "success"
+        }
+        SubMsgResult::Err(err) => {
+            update_trace_status(
+                deps.storage,
+                &id,
+                recipients.len(),
+                PacketTraceStatus::Error,
+                Some(err.clone()),
+            )?;
+            if !funds.is_empty() {
+                res = res.add_message(BankMsg::Send {
+                    to_address: sender,
+                    amount: funds.clone(),
+                });
+                event = event.add_attributes(
+                    funds
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, fund)| (format!("refund:{idx}"), fund.to_string())),
+                );
+            }
+            event = event.add_attribute("error", err);
+            "reverted"
+        }
+    };
+
+    event = event.add_attribute("outcome", outcome).add_attributes(
+        recipients
+            .iter()
+            .enumerate()
+            .map(|(idx, recipient)| (format!("message:{idx}"), format!("{recipient}:{outcome}"))),
+    );
+
+    Ok(res
+        .add_attribute("action", "amp_bundle_reply")
+        .add_attribute("outcome", outcome)
+        .add_event(event))
+}
+
+/// Handles a failed scheduled message
+///
+/// The failed message is reverted and its funds are refunded to the sender that scheduled it
+pub fn on_reply_scheduled_message(msg: Reply) -> Result<Response, ContractError> {
+    let ScheduledMessagePayload { id, sender, funds } = from_json(&msg.payload)?;
+    let err = msg.result.unwrap_err();
+
+    let mut res = Response::default();
+    if !funds.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: sender.clone(),
+            amount: funds,
+        });
+    }
+
+    Ok(res.add_attributes(vec![
+        ("action", "scheduled_message_failed"),
+        ("id", id.to_string().as_str()),
+        ("recipient", sender.as_str()),
+        ("error", err.as_str()),
+    ]))
+}
+
+/// Handles the reply of an AMP message's sub message
+///
+/// The result is recorded in the packet's trace, failed messages that should not exit at an error are refunded to the sender
+pub fn on_reply_amp_msg(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    // Messages without a payload are neither traced nor recovered from
+    if msg.payload.is_empty() {
+        return match msg.result {
+            SubMsgResult::Ok(_) => Ok(Response::default()),
+            SubMsgResult::Err(err) => Err(ContractError::Std(StdError::generic_err(format!(
+                "{}:{}",
+                msg.id, err
+            )))),
+        };
+    }
+    let AMPMsgPayload {
+        id,
+        entry,
+        sender,
+        funds,
+        exit_at_error,
+    } = from_json(&msg.payload)?;
+
+    match msg.result {
+        SubMsgResult::Ok(_) => {
+            if let Some(entry) = entry {
+                update_trace_entry(deps.storage, &id, entry, PacketTraceStatus::Success, None)?;
+            }
+            Ok(Response::default())
+        }
+        SubMsgResult::Err(err) => {
+            // The failure reverts the transaction, including its trace
+            if exit_at_error {
+                return Err(ContractError::Std(StdError::generic_err(format!(
+                    "{}:{}",
+                    msg.id, err
+                ))));
+            }
+            if let Some(entry) = entry {
+                update_trace_entry(
+                    deps.storage,
+                    &id,
+                    entry,
+                    PacketTraceStatus::Error,
+                    Some(err.clone()),
+                )?;
+            }
+
+            let mut res = Response::default();
+            if !funds.is_empty() {
+                res = res.add_message(BankMsg::Send {
+                    to_address: sender.clone(),
+                    amount: funds,
+                });
+            }
+            Ok(res.add_attributes(vec![
+                ("action", "amp_msg_failed"),
+                ("id", id.as_str()),
+                ("recipient", sender.as_str()),
+                ("error", err.as_str()),
+            ]))
+        }
+    }
+}
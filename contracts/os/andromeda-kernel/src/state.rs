@@ -1,6 +1,9 @@
-use andromeda_std::os::kernel::{ChannelInfo, Ics20PacketInfo, RefundData};
+use andromeda_std::os::kernel::{
+    ChannelInfo, EnvValue, Ics20PacketInfo, PacketTrace, RateLimit, RateLimitCounter,
+    RecoveryRecord, RefundData, ScheduledMessage,
+};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Uint128};
+use cosmwasm_std::{Addr, Coin, Empty, Uint128};
 use cw_storage_plus::{Item, Map};
 
 pub const TRIGGER_KEY: &str = "trigger_key";
@@ -18,6 +21,13 @@ pub struct OutgoingPacket {
     pub amount: Coin,
 }
 
+#[cw_serde]
+pub struct RelayedTransfer {
+    /// The chain the funds were sent to
+    pub chain: String,
+    pub funds: Coin,
+}
+
 pub const KERNEL_ADDRESSES: Map<&str, Addr> = Map::new("kernel_addresses");
 pub const ENV_VARIABLES: Map<&str, String> = Map::new("kernel_env_variables");
 pub const CURR_CHAIN: Item<String> = Item::new("kernel_curr_chain");
@@ -35,7 +45,18 @@ pub const CHANNEL_TO_CHAIN: Map<&str, String> = Map::new("kernel_channel_name");
 /// Removed when a reply is received for the packet
 pub const OUTGOING_IBC_PACKETS: Map<(&String, u64), OutgoingPacket> =
     Map::new("outgoing_ibc_packets");
+/// Relayed ICS20 transfers awaiting their acknowledgement, key is (channel_id, sequence)
+///
+/// The denom traces of the funds are recorded in the IBC registry once the transfer is acknowledged
+pub const RELAYED_TRANSFERS: Map<(&String, u64), RelayedTransfer> = Map::new("relayed_transfers");
+/// Recoverable funds stored before recovery records were introduced, converted to records on migration
 pub const IBC_FUND_RECOVERY: Map<&Addr, Vec<Coin>> = Map::new("ibc_fund_recovery");
+/// The ID to be assigned to the next recovery record
+pub const NEXT_RECOVERY_ID: Item<u64> = Item::new("next_recovery_id");
+/// Recoverable funds by record ID
+pub const RECOVERY_RECORDS: Map<u64, RecoveryRecord> = Map::new("recovery_records");
+/// Recovery record IDs by owner, key is (owner, id)
+pub const OWNER_RECOVERIES: Map<(&Addr, u64), Empty> = Map::new("owner_recoveries");
 
 /// Used to temporarily store the most recent ExecuteMsg with the corresponding Coin to be sent in a reply for ICS20 transfer
 pub const PENDING_MSG_AND_FUNDS: Item<Ics20PacketInfo> = Item::new("pending_execute_msg");
@@ -50,3 +71,37 @@ pub const REFUND_DATA: Item<RefundData> = Item::new("refund_data");
 
 /// Used to differentiate between packets
 pub const TX_INDEX: Item<Uint128> = Item::new("tx_index");
+
+/// The ID to be assigned to the next scheduled message
+pub const NEXT_SCHEDULED_MESSAGE_ID: Item<u64> = Item::new("next_scheduled_message_id");
+/// Scheduled messages by ID
+pub const SCHEDULED_MESSAGES: Map<u64, ScheduledMessage> = Map::new("scheduled_messages");
+/// Queue of scheduled messages ordered by due time, key is (execute_at, id)
+pub const SCHEDULE_QUEUE: Map<(u64, u64), Empty> = Map::new("schedule_queue");
+
+/// The maximum amount of packet traces kept, tracing is disabled if not set
+pub const TRACE_RETENTION: Item<u64> = Item::new("trace_retention");
+/// The sequence to be assigned to the next packet trace
+pub const NEXT_TRACE_SEQUENCE: Item<u64> = Item::new("next_trace_sequence");
+/// Packet traces by packet ID
+pub const PACKET_TRACES: Map<&str, PacketTrace> = Map::new("packet_traces");
+/// Packet IDs by trace sequence, used to prune the oldest traces
+pub const TRACE_SEQUENCES: Map<u64, String> = Map::new("trace_sequences");
+/// Trace sequences by packet origin, key is (origin, sequence)
+pub const ORIGIN_TRACES: Map<(&str, u64), Empty> = Map::new("origin_traces");
+
+/// The default rate limit applied to every origin
+pub const DEFAULT_ORIGIN_RATE_LIMIT: Item<RateLimit> = Item::new("default_origin_rate_limit");
+/// Rate limits of specific origins, origins are not validated as they may be on other chains
+pub const ORIGIN_RATE_LIMITS: Map<&str, RateLimit> = Map::new("origin_rate_limits");
+/// Rate limits of message recipients
+pub const DESTINATION_RATE_LIMITS: Map<&Addr, RateLimit> = Map::new("destination_rate_limits");
+/// Rate limit counters by origin
+pub const ORIGIN_RATE_COUNTERS: Map<&str, RateLimitCounter> = Map::new("origin_rate_counters");
+/// Rate limit counters by message recipient
+pub const DESTINATION_RATE_COUNTERS: Map<&Addr, RateLimitCounter> =
+    Map::new("destination_rate_counters");
+
+/// Typed environment variables, key is (scope, variable)
+pub const SCOPED_ENV_VARIABLES: Map<(&str, &str), EnvValue> =
+    Map::new("kernel_scoped_env_variables");
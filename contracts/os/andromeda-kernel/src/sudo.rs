@@ -1,17 +1,23 @@
 use andromeda_std::error::ContractError;
-use cosmwasm_std::{DepsMut, Response};
+use cosmwasm_std::{DepsMut, Env, Response};
 
 pub mod ibc_lifecycle {
     // As with most IBC Hooks methods these were adapted from:
     // https://github.com/osmosis-labs/osmosis/blob/main/cosmwasm/contracts/crosschain-swaps/src/ibc_lifecycle.rs
-    use cosmwasm_std::Coin;
+    use andromeda_std::os::kernel::RecoveryReason;
 
-    use crate::state::{OutgoingPacket, IBC_FUND_RECOVERY, OUTGOING_IBC_PACKETS};
+    use crate::{
+        execute::handle_failed_relay_transfer,
+        ibc::{record_denom_traces_msg, relayed_denom_traces},
+        recovery::record_recovery,
+        state::{OutgoingPacket, OUTGOING_IBC_PACKETS, RELAYED_TRANSFERS},
+    };
 
     use super::*;
 
     pub fn receive_ack(
-        deps: DepsMut,
+        mut deps: DepsMut,
+        env: Env,
         source_channel: String,
         sequence: u64,
         _ack: String,
@@ -19,6 +25,38 @@ pub mod ibc_lifecycle {
     ) -> Result<Response, ContractError> {
         let response = Response::new().add_attribute("action", "receive_ack");
 
+        // The funds of a relayed transfer are only known on both chains once the transfer has been received
+        if let Some(transfer) =
+            RELAYED_TRANSFERS.may_load(deps.storage, (&source_channel, sequence))?
+        {
+            RELAYED_TRANSFERS.remove(deps.storage, (&source_channel, sequence));
+            if success {
+                // Denom traces are recorded on a best effort basis
+                let Ok(traces) = relayed_denom_traces(&deps.as_ref(), &source_channel, &transfer)
+                else {
+                    return Ok(response.add_attribute("denom_traces_recorded", "false"));
+                };
+                return Ok(response
+                    .add_submessages(record_denom_traces_msg(&deps.as_ref(), traces)?)
+                    .add_attribute("msg", "received relayed transfer ack"));
+            }
+        }
+
+        // The funds of a transfer relaying a message have been returned to the kernel, the message's failure policy decides where they go
+        if !success {
+            if let Some(res) = handle_failed_relay_transfer(
+                deps.branch(),
+                env.clone(),
+                source_channel.clone(),
+                sequence,
+            )? {
+                return Ok(response
+                    .add_attribute("msg", "relayed transfer failed")
+                    .add_submessages(res.messages)
+                    .add_attributes(res.attributes));
+            }
+        }
+
         // Check if there is an inflight packet for the received (channel, sequence)
         let sent_packet =
             OUTGOING_IBC_PACKETS.may_load(deps.storage, (&source_channel, sequence))?;
@@ -37,11 +75,15 @@ pub mod ibc_lifecycle {
             recovery_addr,
             amount,
         } = inflight_packet;
-        IBC_FUND_RECOVERY.update(deps.storage, &recovery_addr, |cur_amount_opt| {
-            let mut recoveries = cur_amount_opt.unwrap_or_default();
-            recoveries.push(amount.clone());
-            Ok::<Vec<Coin>, ContractError>(recoveries)
-        })?;
+        record_recovery(
+            deps.storage,
+            env.block.time,
+            &recovery_addr,
+            amount.clone(),
+            RecoveryReason::TransferFailed,
+            Some(source_channel),
+            Some(sequence),
+        )?;
 
         Ok(response
             .add_attribute("msg", "msg failed")
@@ -50,11 +92,26 @@ pub mod ibc_lifecycle {
     }
 
     pub fn receive_timeout(
-        deps: DepsMut,
+        mut deps: DepsMut,
+        env: Env,
         source_channel: String,
         sequence: u64,
     ) -> Result<Response, ContractError> {
         let response = Response::new().add_attribute("action", "receive_timeout");
+        RELAYED_TRANSFERS.remove(deps.storage, (&source_channel, sequence));
+
+        // The funds of a transfer relaying a message have been returned to the kernel, the message's failure policy decides where they go
+        if let Some(res) = handle_failed_relay_transfer(
+            deps.branch(),
+            env.clone(),
+            source_channel.clone(),
+            sequence,
+        )? {
+            return Ok(response
+                .add_attribute("msg", "relayed transfer timed out")
+                .add_submessages(res.messages)
+                .add_attributes(res.attributes));
+        }
 
         // Check if there is an inflight packet for the received (channel, sequence)
         let sent_packet =
@@ -70,11 +127,15 @@ pub mod ibc_lifecycle {
             recovery_addr,
             amount,
         } = inflight_packet;
-        IBC_FUND_RECOVERY.update(deps.storage, &recovery_addr, |cur_amount_opt| {
-            let mut recoveries = cur_amount_opt.unwrap_or_default();
-            recoveries.push(amount.clone());
-            Ok::<Vec<Coin>, ContractError>(recoveries)
-        })?;
+        record_recovery(
+            deps.storage,
+            env.block.time,
+            &recovery_addr,
+            amount.clone(),
+            RecoveryReason::TransferTimeout,
+            Some(source_channel),
+            Some(sequence),
+        )?;
 
         Ok(response
             .add_attribute("recovery_addr", recovery_addr)
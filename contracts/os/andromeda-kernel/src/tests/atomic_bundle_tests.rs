@@ -0,0 +1,216 @@
+use crate::{
+    contract::{execute, reply},
+    reply::AMPBundlePayload,
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::messages::{AMPMsg, AMPPkt},
+    common::reply::ReplyId,
+    error::ContractError,
+    os::kernel::{ExecuteMsg, InternalMsg},
+    testing::mock_querier::{mock_dependencies_custom, MOCK_APP_CONTRACT, RECEIVER},
+};
+use cosmwasm_std::{
+    coin,
+    testing::{message_info, mock_env},
+    to_json_binary, BankMsg, Binary, CosmosMsg, Event, Reply, SubMsgResponse, SubMsgResult,
+    WasmMsg,
+};
+
+fn bundle_messages() -> Vec<AMPMsg> {
+    vec![
+        AMPMsg::new(RECEIVER, Binary::default(), Some(vec![coin(100, "uandr")])),
+        AMPMsg::new(MOCK_APP_CONTRACT, to_json_binary(&true).unwrap(), None),
+    ]
+}
+
+#[test]
+fn test_atomic_bundle_dispatch() {
+    let (mut deps, _) = setup_kernel();
+    let env = mock_env();
+
+    let packet = AMPPkt::new(
+        env.contract.address.clone(),
+        env.contract.address.clone(),
+        bundle_messages(),
+    )
+    .as_atomic();
+    let funds = vec![coin(100, "uandr")];
+    let info = message_info(&env.contract.address, &funds);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::AMPReceive(packet.clone()),
+    )
+    .unwrap();
+
+    // All messages are dispatched as a single sub message to the kernel itself
+    assert_eq!(res.messages.len(), 1);
+    let sub_msg = &res.messages[0];
+    assert_eq!(sub_msg.id, ReplyId::AMPBundle.repr());
+    let CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr,
+        funds: sent_funds,
+        ..
+    }) = &sub_msg.msg
+    else {
+        panic!("Expected a wasm execute message");
+    };
+    assert_eq!(contract_addr, env.contract.address.as_str());
+    assert_eq!(sent_funds, &funds);
+
+    // The bundle is executed in full by the kernel
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::Internal(InternalMsg::ExecuteBundle { packet }),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+}
+
+#[test]
+fn test_atomic_bundle_gas_budget() {
+    let (mut deps, _) = setup_kernel();
+    let env = mock_env();
+
+    let message = AMPMsg::new(MOCK_APP_CONTRACT, to_json_binary(&true).unwrap(), None);
+    let mut packet = AMPPkt::new(
+        env.contract.address.clone(),
+        env.contract.address.clone(),
+        vec![message.clone(), message],
+    )
+    .as_atomic();
+    packet.ctx.gas_budget = Some(1_000_000);
+
+    // The messages of a bundle share its budget rather than each receiving all of it
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &[]),
+        ExecuteMsg::Internal(InternalMsg::ExecuteBundle { packet }),
+    )
+    .unwrap();
+    let gas_limits: Vec<Option<u64>> = res.messages.iter().map(|msg| msg.gas_limit).collect();
+    assert_eq!(gas_limits, vec![Some(500_000), Some(500_000)]);
+}
+
+#[test]
+fn test_atomic_bundle_unauthorized() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let sender = deps.api.addr_make("sender");
+    let packet = AMPPkt::new(sender.clone(), sender.clone(), bundle_messages()).as_atomic();
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        message_info(&sender, &[]),
+        ExecuteMsg::Internal(InternalMsg::ExecuteBundle { packet }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_atomic_bundle_ibc_recipient() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let packet = AMPPkt::new(
+        env.contract.address.clone(),
+        env.contract.address.clone(),
+        vec![AMPMsg::new(
+            "ibc://osmosis/home/user",
+            to_json_binary(&true).unwrap(),
+            None,
+        )],
+    )
+    .as_atomic();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &[]),
+        ExecuteMsg::AMPReceive(packet),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPacket {
+            error: Some("Atomic bundles only support local recipients".to_string())
+        }
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_atomic_bundle_reply() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let payload = AMPBundlePayload {
+        id: "andromeda.1.0".to_string(),
+        sender: "sender".to_string(),
+        funds: vec![coin(100, "uandr")],
+        recipients: vec!["first".to_string(), "second".to_string()],
+    };
+
+    // Successful bundle
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: ReplyId::AMPBundle.repr(),
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+            payload: to_json_binary(&payload).unwrap(),
+            gas_used: 0,
+        },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.events[0],
+        Event::new("amp_bundle")
+            .add_attribute("id", "andromeda.1.0")
+            .add_attribute("sender", "sender")
+            .add_attribute("outcome", "success")
+            .add_attribute("message:0", "first:success")
+            .add_attribute("message:1", "second:success")
+    );
+
+    // Reverted bundle refunds the sender
+    let res = reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: ReplyId::AMPBundle.repr(),
+            result: SubMsgResult::Err("error".to_string()),
+            payload: to_json_binary(&payload).unwrap(),
+            gas_used: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "sender".to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    assert_eq!(
+        res.events[0],
+        Event::new("amp_bundle")
+            .add_attribute("id", "andromeda.1.0")
+            .add_attribute("sender", "sender")
+            .add_attribute("refund:0", "100uandr")
+            .add_attribute("error", "error")
+            .add_attribute("outcome", "reverted")
+            .add_attribute("message:0", "first:reverted")
+            .add_attribute("message:1", "second:reverted")
+    );
+}
@@ -0,0 +1,127 @@
+use crate::{contract::execute, tests::setup_kernel};
+use andromeda_std::{
+    amp::messages::{AMPCondition, AMPMsg, AMPMsgConfig, ConditionOperator},
+    error::ContractError,
+    os::kernel::ExecuteMsg,
+    testing::mock_querier::{WasmMockQuerier, MOCK_CW20_CONTRACT, RECEIVER},
+};
+use cosmwasm_std::{
+    coin,
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    to_json_binary, BankMsg, Binary, CosmosMsg, OwnedDeps, Response,
+};
+use cw20::Cw20QueryMsg;
+use rstest::rstest;
+
+/// The mock CW20 contract returns a balance of 10 for any address
+fn balance_condition(operator: ConditionOperator, expected: &str) -> AMPCondition {
+    AMPCondition::new(
+        MOCK_CW20_CONTRACT,
+        to_json_binary(&Cw20QueryMsg::Balance {
+            address: RECEIVER.to_string(),
+        })
+        .unwrap(),
+        "balance",
+        operator,
+        expected,
+    )
+}
+
+fn send_with_condition(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    condition: AMPCondition,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_make("sender");
+    let funds = vec![coin(100, "uandr")];
+    let message = AMPMsg::new(RECEIVER, Binary::default(), Some(funds.clone()))
+        .with_config(AMPMsgConfig::default().with_condition(condition));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&sender, &funds),
+        ExecuteMsg::Send { message },
+    )
+}
+
+#[rstest]
+#[case(ConditionOperator::Equal, "10", true)]
+#[case(ConditionOperator::Equal, "10.0", true)]
+#[case(ConditionOperator::Equal, "11", false)]
+#[case(ConditionOperator::Equal, "ten", false)]
+#[case(ConditionOperator::NotEqual, "11", true)]
+#[case(ConditionOperator::NotEqual, "10.00", false)]
+#[case(ConditionOperator::GreaterThan, "9.5", true)]
+#[case(ConditionOperator::GreaterThan, "10", false)]
+#[case(ConditionOperator::GreaterThanOrEqual, "10", true)]
+#[case(ConditionOperator::LessThan, "10", false)]
+#[case(ConditionOperator::LessThanOrEqual, "10.0", true)]
+fn test_conditional_send(
+    #[case] operator: ConditionOperator,
+    #[case] expected: &str,
+    #[case] met: bool,
+) {
+    let (mut deps, _) = setup_kernel();
+    let res = send_with_condition(&mut deps, balance_condition(operator, expected));
+
+    if met {
+        let res = res.unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: RECEIVER.to_string(),
+                amount: vec![coin(100, "uandr")],
+            })
+        );
+    } else {
+        assert_eq!(res.unwrap_err(), ContractError::UnmetCondition {});
+    }
+}
+
+#[test]
+fn test_conditional_send_skip_on_unmet() {
+    let (mut deps, _) = setup_kernel();
+    let sender = deps.api.addr_make("sender");
+    let res = send_with_condition(
+        &mut deps,
+        balance_condition(ConditionOperator::LessThan, "5").skip_on_unmet(),
+    )
+    .unwrap();
+
+    // The message is skipped and the funds are returned to the sender
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "reason" && attr.value == "unmet_condition"));
+}
+
+#[test]
+fn test_conditional_send_invalid() {
+    let (mut deps, _) = setup_kernel();
+
+    // Ordering operators require numeric values
+    let err = send_with_condition(
+        &mut deps,
+        balance_condition(ConditionOperator::GreaterThan, "ten"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ParsingError {
+            err: "Cannot compare non-numeric value ten".to_string()
+        }
+    );
+
+    // Missing paths never satisfy the condition
+    let mut condition = balance_condition(ConditionOperator::Equal, "10");
+    condition.path = "missing".to_string();
+    let err = send_with_condition(&mut deps, condition).unwrap_err();
+    assert_eq!(err, ContractError::UnmetCondition {});
+}
@@ -0,0 +1,113 @@
+use crate::{contract::execute, tests::setup_kernel};
+use andromeda_std::{
+    amp::messages::{AMPCtx, AMPMsg, AMPMsgConfig, AMPPkt, AMP_HOP_GAS_COST},
+    error::ContractError,
+    os::kernel::ExecuteMsg,
+    testing::mock_querier::{WasmMockQuerier, MOCK_APP_CONTRACT, RECEIVER},
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    to_json_binary, BankMsg, Binary, CosmosMsg, Env, OwnedDeps, Response, SubMsg, WasmMsg,
+};
+
+fn setup() -> (OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Env) {
+    let (deps, _) = setup_kernel();
+    (deps, mock_env())
+}
+
+fn receive_with_budget(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    gas_budget: u64,
+    gas_limit: Option<u64>,
+) -> Result<Response, ContractError> {
+    let funds = vec![coin(100, "uandr")];
+    let ctx = AMPCtx::new("origin", env.contract.address.clone(), None).with_gas_budget(gas_budget);
+    let packet = AMPPkt::new_with_ctx(
+        ctx,
+        vec![
+            AMPMsg::new(MOCK_APP_CONTRACT, to_json_binary(&true).unwrap(), None).with_config(
+                AMPMsgConfig {
+                    gas_limit,
+                    ..AMPMsgConfig::default()
+                },
+            ),
+            AMPMsg::new(RECEIVER, Binary::default(), Some(funds.clone())),
+        ],
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &funds),
+        ExecuteMsg::AMPReceive(packet),
+    )
+}
+
+fn sent_packet(sub_msg: &SubMsg) -> AMPPkt {
+    let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &sub_msg.msg else {
+        panic!("Expected a wasm execute message");
+    };
+    let ExecuteMsg::AMPReceive(packet) = from_json::<ExecuteMsg>(msg).unwrap() else {
+        panic!("Expected an AMP packet");
+    };
+    packet
+}
+
+#[test]
+fn test_gas_budget_split_across_hops() {
+    let (mut deps, env) = setup();
+    let res = receive_with_budget(&mut deps, &env, 1_000_000, Some(300_000)).unwrap();
+    assert_eq!(res.messages.len(), 2);
+
+    // The first hop is limited to its reserved gas and its nested hops to the remainder of that reservation
+    assert_eq!(res.messages[0].gas_limit, Some(300_000));
+    assert_eq!(
+        sent_packet(&res.messages[0]).ctx.gas_budget,
+        Some(300_000 - AMP_HOP_GAS_COST)
+    );
+
+    // The remaining messages carry the rest of the packet's budget
+    assert_eq!(sent_packet(&res.messages[1]).ctx.gas_budget, Some(700_000));
+}
+
+#[test]
+fn test_gas_budget_without_gas_limit() {
+    let (mut deps, env) = setup();
+    let res = receive_with_budget(&mut deps, &env, 1_000_000, None).unwrap();
+
+    // A hop without a gas limit reserves its share of the budget, leaving the rest to the remaining messages
+    assert_eq!(res.messages[0].gas_limit, Some(500_000));
+    let packet = sent_packet(&res.messages[1]);
+    assert_eq!(packet.ctx.gas_budget, Some(500_000));
+
+    // The second message still executes within what is left
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &[coin(100, "uandr")]),
+        ExecuteMsg::AMPReceive(packet),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: RECEIVER.to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+}
+
+#[test]
+fn test_gas_budget_exhausted() {
+    let (mut deps, env) = setup();
+    let err = receive_with_budget(&mut deps, &env, 50_000, None).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::GasBudgetExhausted {
+            required: AMP_HOP_GAS_COST,
+            remaining: 50_000
+        }
+    );
+}
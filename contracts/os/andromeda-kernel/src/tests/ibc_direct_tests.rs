@@ -51,6 +51,7 @@ fn test_handle_ibc_direct_success() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     // Create channel info
@@ -106,6 +107,7 @@ fn test_handle_ibc_direct_success() {
                 }
                 IbcExecuteMsg::CreateADO { .. } => panic!("Unexpected CreateADO"),
                 IbcExecuteMsg::RegisterUsername { .. } => panic!("Unexpected RegisterUsername"),
+                IbcExecuteMsg::ResolvePath { .. } => panic!("Unexpected ResolvePath"),
             }
         }
         _ => panic!("Expected IBC SendPacket message"),
@@ -150,6 +152,7 @@ fn test_handle_ibc_direct_empty_message() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     let channel_info = ChannelInfo {
@@ -196,6 +199,7 @@ fn test_handle_ibc_direct_no_direct_channel() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     // Channel info without direct channel
@@ -246,6 +250,7 @@ fn test_handle_ibc_direct_with_existing_context() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     // Create an existing context
@@ -260,6 +265,7 @@ fn test_handle_ibc_direct_with_existing_context() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     let ctx = Some(AMPPkt::new(
@@ -310,6 +316,9 @@ fn test_handle_ibc_direct_with_existing_context() {
                 IbcExecuteMsg::RegisterUsername { .. } => {
                     panic!("Expected SendMessage, got RegisterUsername")
                 }
+                IbcExecuteMsg::ResolvePath { .. } => {
+                    panic!("Expected SendMessage, got ResolvePath")
+                }
             }
         }
         _ => panic!("Expected IBC SendPacket message"),
@@ -338,6 +347,7 @@ fn test_handle_ibc_direct_with_complex_path() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     let channel_info = ChannelInfo {
@@ -379,6 +389,9 @@ fn test_handle_ibc_direct_with_complex_path() {
                 IbcExecuteMsg::RegisterUsername { .. } => {
                     panic!("Unexpected RegisterUsername");
                 }
+                IbcExecuteMsg::ResolvePath { .. } => {
+                    panic!("Unexpected ResolvePath");
+                }
             }
         }
         _ => panic!("Expected IBC SendPacket message"),
@@ -407,6 +420,7 @@ fn test_handle_ibc_direct_with_custom_config() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     let channel_info = ChannelInfo {
@@ -448,6 +462,9 @@ fn test_handle_ibc_direct_with_custom_config() {
                 IbcExecuteMsg::RegisterUsername { .. } => {
                     panic!("Unexpected RegisterUsername");
                 }
+                IbcExecuteMsg::ResolvePath { .. } => {
+                    panic!("Unexpected ResolvePath");
+                }
             }
         }
         _ => panic!("Expected IBC SendPacket message"),
@@ -480,6 +497,7 @@ fn test_handle_ibc_direct_with_funds_attempt() {
         exit_at_error: false,
         gas_limit: None,
         ibc_config: None,
+        condition: None,
     });
 
     let channel_info = ChannelInfo {
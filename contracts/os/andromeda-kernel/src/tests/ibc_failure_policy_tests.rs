@@ -0,0 +1,315 @@
+use crate::{
+    contract::{execute, sudo},
+    execute::handle_ibc_transfer_funds,
+    ibc::{ibc_callback_memo, ibc_packet_timeout, IBCLifecycleComplete, SudoMsg},
+    state::{
+        CHAIN_TO_CHANNEL, CHANNEL_TO_EXECUTE_MSG, KERNEL_ADDRESSES, PENDING_MSG_AND_FUNDS,
+        TRIGGER_KEY,
+    },
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::{
+        messages::{AMPMsg, AMPMsgConfig, AMPPkt, IBCConfig, IBCFailurePolicy},
+        AndrAddr,
+    },
+    common::{expiration::Expiry, reply::ReplyId, Milliseconds},
+    error::ContractError,
+    os::kernel::{ChannelInfo, ExecuteMsg, IbcExecuteMsg, Ics20PacketInfo},
+    testing::mock_querier::{mock_dependencies_custom, WasmMockQuerier},
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_env, mock_ibc_packet_timeout, MockApi, MockStorage},
+    to_json_binary, Addr, BankMsg, Binary, CosmosMsg, IbcMsg, IbcTimeout, OwnedDeps, StdAck,
+};
+use rstest::rstest;
+
+const CHANNEL: &str = "channel-0";
+
+fn channel_info() -> ChannelInfo {
+    ChannelInfo {
+        kernel_address: "juno_kernel".to_string(),
+        ics20_channel_id: Some(CHANNEL.to_string()),
+        direct_channel_id: Some("channel-direct".to_string()),
+        supported_modules: vec![],
+    }
+}
+
+fn setup(
+    ibc_config: Option<IBCConfig>,
+    retried: bool,
+) -> (OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Addr) {
+    let (mut deps, _) = setup_kernel();
+
+    let trigger = deps.api.addr_make("trigger");
+    KERNEL_ADDRESSES
+        .save(deps.as_mut().storage, TRIGGER_KEY, &trigger)
+        .unwrap();
+    CHAIN_TO_CHANNEL
+        .save(deps.as_mut().storage, "juno", &channel_info())
+        .unwrap();
+    CHANNEL_TO_EXECUTE_MSG
+        .save(
+            deps.as_mut().storage,
+            (CHANNEL.to_string(), 1),
+            &Ics20PacketInfo {
+                sender: "sender".to_string(),
+                recipient: AndrAddr::from_string("ibc://juno/recipient"),
+                message: Binary::default(),
+                funds: coin(100, "uandr"),
+                channel: CHANNEL.to_string(),
+                pending: false,
+                ibc_config,
+                retried,
+                forwarded: None,
+            },
+        )
+        .unwrap();
+    (deps, trigger)
+}
+
+#[rstest]
+#[case::default_policy(None, false, "sender")]
+#[case::refund_origin(
+    Some(IBCConfig::new(Some(AndrAddr::from_string("recovery"))).with_failure_policy(IBCFailurePolicy::RefundOrigin)),
+    false,
+    "sender"
+)]
+#[case::refund_recovery(
+    Some(IBCConfig::new(Some(AndrAddr::from_string("recovery"))).with_failure_policy(IBCFailurePolicy::RefundRecoveryAddr)),
+    false,
+    "recovery"
+)]
+#[case::retried_with_recovery(
+    Some(IBCConfig::new(Some(AndrAddr::from_string("recovery"))).with_failure_policy(IBCFailurePolicy::RetryOnce)),
+    true,
+    "recovery"
+)]
+#[case::retried_without_recovery(
+    Some(IBCConfig::new(None).with_failure_policy(IBCFailurePolicy::RetryOnce)),
+    true,
+    "sender"
+)]
+fn test_failed_transfer_refund(
+    #[case] ibc_config: Option<IBCConfig>,
+    #[case] retried: bool,
+    #[case] refund_recipient: &str,
+) {
+    let (mut deps, trigger) = setup(ibc_config, retried);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&trigger, &[]),
+        ExecuteMsg::TriggerRelay {
+            packet_sequence: 1,
+            channel_id: CHANNEL.to_string(),
+            packet_ack: to_json_binary(&StdAck::error("timeout")).unwrap(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: refund_recipient.to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    assert!(!CHANNEL_TO_EXECUTE_MSG.has(deps.as_ref().storage, (CHANNEL.to_string(), 1)));
+}
+
+#[test]
+fn test_failed_transfer_retry() {
+    let ibc_config = IBCConfig::new(None)
+        .with_timeout(Expiry::FromNow(Milliseconds::from_seconds(60)))
+        .with_failure_policy(IBCFailurePolicy::RetryOnce);
+    let (mut deps, trigger) = setup(Some(ibc_config), false);
+    let env = mock_env();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&trigger, &[]),
+        ExecuteMsg::TriggerRelay {
+            packet_sequence: 1,
+            channel_id: CHANNEL.to_string(),
+            packet_ack: to_json_binary(&StdAck::error("ack error")).unwrap(),
+        },
+    )
+    .unwrap();
+
+    // The transfer is sent once more with a refreshed timeout
+    assert_eq!(res.messages[0].id, ReplyId::IBCTransfer.repr());
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: CHANNEL.to_string(),
+            to_address: "juno_kernel".to_string(),
+            amount: coin(100, "uandr"),
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(60)),
+            memo: Some(ibc_callback_memo(&env)),
+        })
+    );
+    let pending = PENDING_MSG_AND_FUNDS.load(deps.as_ref().storage).unwrap();
+    assert!(pending.retried);
+    assert!(!CHANNEL_TO_EXECUTE_MSG.has(deps.as_ref().storage, (CHANNEL.to_string(), 1)));
+}
+
+#[rstest]
+#[case::timeout(IBCLifecycleComplete::IBCTimeout { channel: CHANNEL.to_string(), sequence: 1 })]
+#[case::error_ack(IBCLifecycleComplete::IBCAck {
+    channel: CHANNEL.to_string(),
+    sequence: 1,
+    ack: String::new(),
+    success: false,
+})]
+fn test_failed_transfer_lifecycle(#[case] lifecycle: IBCLifecycleComplete) {
+    let ibc_config = IBCConfig::new(Some(AndrAddr::from_string("recovery")))
+        .with_failure_policy(IBCFailurePolicy::RefundRecoveryAddr);
+    let (mut deps, _) = setup(Some(ibc_config), false);
+
+    // Transfers that time out or fail are handled by the message's failure policy without a relay
+    let res = sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::IBCLifecycleComplete(lifecycle),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "recovery".to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    assert!(!CHANNEL_TO_EXECUTE_MSG.has(deps.as_ref().storage, (CHANNEL.to_string(), 1)));
+}
+
+#[test]
+fn test_transfer_funds_timeout() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let sender = deps.api.addr_make("sender");
+    let recovery = deps.api.addr_make("recovery");
+    let message = AMPMsg::new(
+        "ibc://juno/recipient",
+        Binary::default(),
+        Some(vec![coin(100, "uandr")]),
+    )
+    .with_config(AMPMsgConfig::new(
+        None,
+        None,
+        None,
+        Some(
+            IBCConfig::new(Some(AndrAddr::from_string(recovery.to_string())))
+                .with_timeout(Expiry::AtTime(Milliseconds::from_nanos(
+                    env.block.time.plus_seconds(120).nanos(),
+                )))
+                .with_failure_policy(IBCFailurePolicy::RefundRecoveryAddr),
+        ),
+    ));
+
+    let res = handle_ibc_transfer_funds(
+        deps.as_mut(),
+        message_info(&sender, &[]),
+        env.clone(),
+        None,
+        message.clone(),
+        channel_info(),
+    )
+    .unwrap();
+    let CosmosMsg::Ibc(IbcMsg::Transfer { timeout, .. }) = &res.messages[0].msg else {
+        panic!("Expected an IBC transfer");
+    };
+    assert_eq!(
+        timeout,
+        &IbcTimeout::with_timestamp(env.block.time.plus_seconds(120))
+    );
+    let pending = PENDING_MSG_AND_FUNDS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        pending.ibc_config.unwrap().recovery_addr,
+        Some(AndrAddr::from_string(recovery.to_string()))
+    );
+
+    // Absolute timeouts must be in the future
+    let mut late_env = env.clone();
+    late_env.block.time = env.block.time.plus_seconds(180);
+    let err = handle_ibc_transfer_funds(
+        deps.as_mut(),
+        message_info(&sender, &[]),
+        late_env,
+        None,
+        message,
+        channel_info(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::StartTimeInThePast { .. }));
+
+    // Refunding to the recovery address requires one
+    let message = AMPMsg::new(
+        "ibc://juno/recipient",
+        Binary::default(),
+        Some(vec![coin(100, "uandr")]),
+    )
+    .with_config(AMPMsgConfig::new(
+        None,
+        None,
+        None,
+        Some(IBCConfig::new(None).with_failure_policy(IBCFailurePolicy::RefundRecoveryAddr)),
+    ));
+    let err = handle_ibc_transfer_funds(
+        deps.as_mut(),
+        message_info(&sender, &[]),
+        env,
+        None,
+        message,
+        channel_info(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPacket {
+            error: Some(
+                "Refunding to the recovery address requires a recovery address".to_string()
+            )
+        }
+    );
+}
+
+#[test]
+fn test_direct_packet_retry_once() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let message = AMPMsg::new("/home/recipient", to_json_binary(&true).unwrap(), None).with_config(
+        AMPMsgConfig::new(
+            None,
+            None,
+            None,
+            Some(IBCConfig::new(None).with_failure_policy(IBCFailurePolicy::RetryOnce)),
+        ),
+    );
+    let packet = IbcExecuteMsg::SendMessage {
+        amp_packet: AMPPkt::new("origin", "kernel", vec![message]),
+    };
+    let msg = mock_ibc_packet_timeout("channel-direct", &packet).unwrap();
+
+    let res = ibc_packet_timeout(deps.as_mut(), env.clone(), msg.clone()).unwrap();
+    let CosmosMsg::Ibc(IbcMsg::SendPacket {
+        channel_id, data, ..
+    }) = &res.messages[0].msg
+    else {
+        panic!("Expected the packet to be resent");
+    };
+    assert_eq!(channel_id, &msg.packet.src.channel_id);
+
+    // The resent packet is not retried again
+    let IbcExecuteMsg::SendMessage { amp_packet } = from_json::<IbcExecuteMsg>(data).unwrap()
+    else {
+        panic!("Expected an AMP packet");
+    };
+    let retry_msg =
+        mock_ibc_packet_timeout("channel-direct", &IbcExecuteMsg::SendMessage { amp_packet })
+            .unwrap();
+    let res = ibc_packet_timeout(deps.as_mut(), env, retry_msg).unwrap();
+    assert!(res.messages.is_empty());
+}
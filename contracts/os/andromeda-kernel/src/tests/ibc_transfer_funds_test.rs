@@ -1,14 +1,25 @@
 #[cfg(test)]
 mod ibc_transfer_tests {
+    use crate::contract::{reply as reply_handler, sudo};
     use crate::execute::handle_ibc_transfer_funds;
-    use crate::state::PENDING_MSG_AND_FUNDS;
+    use crate::ibc::{record_denom_traces_msg, IBCLifecycleComplete, SudoMsg};
+    use crate::state::{CURR_CHAIN, KERNEL_ADDRESSES, PENDING_MSG_AND_FUNDS};
     use andromeda_std::amp::messages::{AMPMsg, AMPMsgConfig, AMPPkt};
-    use andromeda_std::amp::AndrAddr;
+    use andromeda_std::amp::{AndrAddr, IBC_REGISTRY_KEY};
     use andromeda_std::common::reply::ReplyId;
     use andromeda_std::error::ContractError;
+    use andromeda_std::os::ibc_registry::{
+        DenomInfo, DenomTrace, ExecuteMsg as IBCRegistryExecuteMsg,
+    };
     use andromeda_std::os::kernel::ChannelInfo;
+    use andromeda_std::testing::mock_querier::{
+        mock_dependencies_custom, MOCK_ANDR_TO_OSMO_IBC_CHANNEL, MOCK_OSMO_TO_ANDR_IBC_CHANNEL,
+    };
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
-    use cosmwasm_std::{Binary, Coin, CosmosMsg, IbcMsg, SubMsg, Uint128};
+    use cosmwasm_std::{
+        coin, wasm_execute, Binary, Coin, CosmosMsg, Event, IbcMsg, Reply, SubMsg, SubMsgResponse,
+        SubMsgResult, Uint128,
+    };
 
     #[test]
     fn test_handle_ibc_transfer_funds_success() {
@@ -476,4 +487,135 @@ mod ibc_transfer_tests {
         let stored_packet = PENDING_MSG_AND_FUNDS.load(&deps.storage).unwrap();
         assert_eq!(stored_packet.sender, sender.to_string()); // Original sender preserved
     }
+
+    #[test]
+    fn test_record_denom_traces_msg() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let traces = vec![DenomTrace {
+            chain: "juno-1".to_string(),
+            denom: "uatom".to_string(),
+            denom_info: DenomInfo::new("uatom".to_string(), "".to_string()),
+        }];
+
+        // Nothing is recorded without an IBC registry
+        let msg = record_denom_traces_msg(&deps.as_ref(), traces.clone()).unwrap();
+        assert!(msg.is_none());
+
+        let ibc_registry = deps.api.addr_make("ibc_registry");
+        KERNEL_ADDRESSES
+            .save(deps.as_mut().storage, IBC_REGISTRY_KEY, &ibc_registry)
+            .unwrap();
+        let msg = record_denom_traces_msg(&deps.as_ref(), traces.clone()).unwrap();
+        assert_eq!(
+            msg,
+            Some(SubMsg::reply_on_error(
+                wasm_execute(
+                    ibc_registry,
+                    &IBCRegistryExecuteMsg::RecordDenomTraces { traces },
+                    vec![],
+                )
+                .unwrap(),
+                ReplyId::RecordDenomTraces.repr(),
+            ))
+        );
+
+        // A failure to record the traces does not fail the relay
+        let reply = Reply {
+            id: ReplyId::RecordDenomTraces.repr(),
+            payload: Binary::default(),
+            gas_used: 0,
+            result: SubMsgResult::Err("registry error".to_string()),
+        };
+        let res = reply_handler(deps.as_mut(), env, reply).unwrap();
+        assert_eq!(res.attributes[0].value, "false");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_record_denom_traces_on_ack() {
+        let mut deps = mock_dependencies_custom(&[]);
+        let env = mock_env();
+        let sender = deps.api.addr_make("sender");
+        let ibc_registry = deps.api.addr_make("ibc_registry");
+        KERNEL_ADDRESSES
+            .save(deps.as_mut().storage, IBC_REGISTRY_KEY, &ibc_registry)
+            .unwrap();
+        CURR_CHAIN
+            .save(deps.as_mut().storage, &"andromeda".to_string())
+            .unwrap();
+
+        let message = AMPMsg {
+            recipient: AndrAddr::from_string("ibc://osmosis/recipient".to_string()),
+            message: Binary::default(),
+            funds: vec![coin(100, "uandr")],
+            config: AMPMsgConfig::default(),
+        };
+        let channel_info = ChannelInfo {
+            direct_channel_id: Some("channel-direct".to_string()),
+            ics20_channel_id: Some(MOCK_ANDR_TO_OSMO_IBC_CHANNEL.to_string()),
+            kernel_address: "osmosis_kernel".to_string(),
+            supported_modules: vec![],
+        };
+        handle_ibc_transfer_funds(
+            deps.as_mut(),
+            message_info(&sender, &[]),
+            env.clone(),
+            None,
+            message,
+            channel_info,
+        )
+        .unwrap();
+
+        // Nothing is recorded when the transfer is sent
+        let reply = Reply {
+            id: ReplyId::IBCTransfer.repr(),
+            payload: Binary::default(),
+            gas_used: 0,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("send_packet").add_attribute("packet_sequence", "1")],
+                data: None,
+                msg_responses: vec![],
+            }),
+        };
+        let res = reply_handler(deps.as_mut(), env.clone(), reply).unwrap();
+        assert!(res.messages.is_empty());
+
+        // The traces on both chains are recorded once the transfer is acknowledged
+        let ack = || {
+            SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCAck {
+                channel: MOCK_ANDR_TO_OSMO_IBC_CHANNEL.to_string(),
+                sequence: 1,
+                ack: String::new(),
+                success: true,
+            })
+        };
+        let res = sudo(deps.as_mut(), env.clone(), ack()).unwrap();
+        let counterparty_denom_info = DenomInfo::new(
+            "uandr".to_string(),
+            format!("transfer/{MOCK_OSMO_TO_ANDR_IBC_CHANNEL}"),
+        );
+        let traces = vec![
+            DenomTrace {
+                chain: "andromeda".to_string(),
+                denom: "uandr".to_string(),
+                denom_info: DenomInfo::new("uandr".to_string(), "".to_string()),
+            },
+            DenomTrace {
+                chain: "osmosis".to_string(),
+                denom: counterparty_denom_info.get_ibc_denom(),
+                denom_info: counterparty_denom_info,
+            },
+        ];
+        assert_eq!(
+            res.messages,
+            vec![record_denom_traces_msg(&deps.as_ref(), traces)
+                .unwrap()
+                .unwrap()]
+        );
+
+        // Each transfer is only recorded once
+        let res = sudo(deps.as_mut(), env, ack()).unwrap();
+        assert!(res.messages.is_empty());
+    }
 }
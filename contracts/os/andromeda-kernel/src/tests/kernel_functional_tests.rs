@@ -1,8 +1,9 @@
 #[cfg(test)]
 use crate::execute::validate_id;
 use crate::{
+    ack::make_ack_result,
     contract::{execute, instantiate, query},
-    ibc::PACKET_LIFETIME,
+    ibc::{ibc_packet_ack, PACKET_LIFETIME},
     state::{
         ADO_OWNER, CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, CHANNEL_TO_EXECUTE_MSG, CURR_CHAIN,
         ENV_VARIABLES, KERNEL_ADDRESSES,
@@ -14,9 +15,12 @@ use andromeda_std::{
         AndrAddr, ADO_DB_KEY, VFS_KEY,
     },
     error::ContractError,
-    os::kernel::{
-        ChannelInfo, ExecuteMsg, IbcExecuteMsg, Ics20PacketInfo, InstantiateMsg, InternalMsg,
-        PendingPacketResponse, QueryMsg,
+    os::{
+        kernel::{
+            ChannelInfo, ExecuteMsg, IbcExecuteMsg, Ics20PacketInfo, InstantiateMsg, InternalMsg,
+            PendingPacketResponse, QueryMsg,
+        },
+        vfs::ExecuteMsg as VFSExecuteMsg,
     },
     testing::mock_querier::{
         mock_dependencies_custom, MOCK_ADODB_CONTRACT, MOCK_FAKE_KERNEL_CONTRACT,
@@ -25,8 +29,12 @@ use andromeda_std::{
 };
 use cosmwasm_std::{
     coin, from_json,
-    testing::{message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
-    to_json_binary, Addr, Binary, CosmosMsg, Env, IbcMsg, OwnedDeps, Uint128,
+    testing::{
+        message_info, mock_dependencies, mock_env, mock_ibc_packet_ack, MockApi, MockQuerier,
+        MockStorage,
+    },
+    to_json_binary, Addr, Binary, CosmosMsg, Env, IbcAcknowledgement, IbcMsg, OwnedDeps, Uint128,
+    WasmMsg,
 };
 use rstest::*;
 
@@ -115,6 +123,7 @@ fn test_create_ado() {
         msg: Binary::default(),
         owner: None,
         chain: None,
+        resolve_env: false,
     };
     let res = execute(deps.as_mut(), env, info.clone(), create_msg).unwrap();
     assert_eq!(1, res.messages.len());
@@ -183,6 +192,93 @@ fn test_register_user_cross_chain() {
     assert_eq!(res.messages.first().unwrap().msg, CosmosMsg::Ibc(expected));
 }
 
+#[test]
+fn test_resolve_path_cross_chain() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let creator = deps.api.addr_make("creator");
+    let env = mock_env();
+    let chain = "chain";
+    let path = "/home/user/app";
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&creator, &[]),
+        InstantiateMsg {
+            owner: None,
+            chain_name: "andromeda".to_string(),
+        },
+    )
+    .unwrap();
+    KERNEL_ADDRESSES
+        .save(
+            deps.as_mut().storage,
+            VFS_KEY,
+            &Addr::unchecked(MOCK_VFS_CONTRACT),
+        )
+        .unwrap();
+    let channel_info = ChannelInfo {
+        kernel_address: MOCK_FAKE_KERNEL_CONTRACT.to_string(),
+        ics20_channel_id: Some("1".to_string()),
+        direct_channel_id: Some("2".to_string()),
+        supported_modules: vec![],
+    };
+    CHAIN_TO_CHANNEL
+        .save(deps.as_mut().storage, chain, &channel_info)
+        .unwrap();
+    CHANNEL_TO_CHAIN
+        .save(deps.as_mut().storage, "2", &chain.to_string())
+        .unwrap();
+
+    let msg = ExecuteMsg::Internal(InternalMsg::ResolvePathCrossChain {
+        path: path.to_string(),
+        chain: chain.to_string(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&creator, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let info = message_info(&Addr::unchecked(MOCK_VFS_CONTRACT), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    let packet_msg = IbcExecuteMsg::ResolvePath {
+        path: path.to_string(),
+    };
+    let expected = IbcMsg::SendPacket {
+        channel_id: "2".to_string(),
+        data: to_json_binary(&packet_msg).unwrap(),
+        timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+    };
+    assert_eq!(res.messages.first().unwrap().msg, CosmosMsg::Ibc(expected));
+
+    // The address in the acknowledgement is cached by the VFS
+    let ack = mock_ibc_packet_ack(
+        "2",
+        &packet_msg,
+        IbcAcknowledgement::new(make_ack_result(
+            to_json_binary(&Addr::unchecked("remote_app")).unwrap(),
+        )),
+    )
+    .unwrap();
+    let res = ibc_packet_ack(deps.as_mut(), env, ack).unwrap();
+    let expected = VFSExecuteMsg::CacheRemotePath {
+        chain: chain.to_string(),
+        path: path.to_string(),
+        address: "remote_app".to_string(),
+    };
+    assert_eq!(
+        res.messages.first().unwrap().msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_VFS_CONTRACT.to_string(),
+            msg: to_json_binary(&expected).unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
 #[test]
 fn test_assign_channels() {
     let mut deps = mock_dependencies_custom(&[]);
@@ -585,6 +681,9 @@ fn test_query_pending_packets(
             funds: coin(amount, "ucosm"),
             channel: channel.to_string(),
             pending: true,
+            ibc_config: None,
+            retried: false,
+            forwarded: None,
         };
         CHANNEL_TO_EXECUTE_MSG
             .save(
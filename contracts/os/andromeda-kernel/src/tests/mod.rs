@@ -1,4 +1,51 @@
+pub mod atomic_bundle_tests;
+pub mod condition_tests;
+pub mod gas_budget_tests;
 pub mod ibc_direct_tests;
+pub mod ibc_failure_policy_tests;
 pub mod ibc_transfer_funds_test;
 pub mod kernel_functional_tests;
+pub mod multi_hop_tests;
+pub mod rate_limit_tests;
+pub mod recovery_tests;
+pub mod scheduled_messages_tests;
+pub mod scoped_env_tests;
 pub mod test_handler;
+pub mod trace_tests;
+
+use andromeda_std::{
+    amp::ADO_DB_KEY,
+    os::kernel::InstantiateMsg,
+    testing::mock_querier::{mock_dependencies_custom, WasmMockQuerier, MOCK_ADODB_CONTRACT},
+};
+use cosmwasm_std::{
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    Addr, OwnedDeps,
+};
+
+use crate::{contract::instantiate, state::KERNEL_ADDRESSES};
+
+/// Instantiates the kernel on the custom mock dependencies with the mock ADODB registered,
+/// returning the dependencies along with the kernel's owner
+pub fn setup_kernel() -> (OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Addr) {
+    let mut deps = mock_dependencies_custom(&[]);
+    let owner = deps.api.addr_make("owner");
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        InstantiateMsg {
+            owner: None,
+            chain_name: "andromeda".to_string(),
+        },
+    )
+    .unwrap();
+    KERNEL_ADDRESSES
+        .save(
+            deps.as_mut().storage,
+            ADO_DB_KEY,
+            &Addr::unchecked(MOCK_ADODB_CONTRACT),
+        )
+        .unwrap();
+    (deps, owner)
+}
@@ -0,0 +1,464 @@
+use crate::{
+    execute::trigger_relay,
+    ibc::{do_ibc_packet_receive, ibc_callback_memo, MAX_CROSS_CHAIN_HOPS},
+    rate_limit::remote_origin,
+    recovery::owner_recoveries,
+    reply::on_reply_refund_ibc_transfer_with_msg,
+    state::{
+        CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, CHANNEL_TO_EXECUTE_MSG, CURR_CHAIN, KERNEL_ADDRESSES,
+        ORIGIN_RATE_LIMITS, PENDING_MSG_AND_FUNDS, REFUND_DATA, TRIGGER_KEY,
+    },
+};
+use andromeda_std::{
+    amp::{
+        messages::{AMPCtx, AMPMsg, AMPPkt, CrossChainHop},
+        AndrAddr, VFS_KEY,
+    },
+    common::{context::ExecuteContext, reply::ReplyId},
+    error::ContractError,
+    os::kernel::{
+        ChannelInfo, ForwardedFunds, IbcExecuteMsg, Ics20PacketInfo, RateLimit, RefundData,
+    },
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{
+        message_info, mock_dependencies, mock_env, mock_ibc_packet_recv, MockApi, MockQuerier,
+    },
+    to_json_binary, Addr, Binary, Coin, CosmosMsg, Env, IbcMsg, IbcTimeout, MemoryStorage,
+    OwnedDeps, Reply, Response, StdAck, SubMsgResult,
+};
+use rstest::rstest;
+
+// Channels on the intermediate chain, osmosis, for the origin chain and the destination chain
+const ORIGIN_DIRECT_CHANNEL: &str = "channel-andr";
+const ORIGIN_ICS20_CHANNEL: &str = "channel-andr-ics20";
+const DESTINATION_DIRECT_CHANNEL: &str = "channel-juno";
+const DESTINATION_ICS20_CHANNEL: &str = "channel-juno-ics20";
+
+fn setup() -> OwnedDeps<MemoryStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    let storage = deps.as_mut().storage;
+    CURR_CHAIN.save(storage, &"osmosis".to_string()).unwrap();
+    KERNEL_ADDRESSES
+        .save(storage, VFS_KEY, &Addr::unchecked("mock_vfs"))
+        .unwrap();
+
+    for (chain, direct_channel, ics20_channel, kernel_address) in [
+        (
+            "andromeda",
+            ORIGIN_DIRECT_CHANNEL,
+            ORIGIN_ICS20_CHANNEL,
+            "andromeda_kernel",
+        ),
+        (
+            "juno",
+            DESTINATION_DIRECT_CHANNEL,
+            DESTINATION_ICS20_CHANNEL,
+            "juno_kernel",
+        ),
+    ] {
+        CHAIN_TO_CHANNEL
+            .save(
+                storage,
+                chain,
+                &ChannelInfo {
+                    kernel_address: kernel_address.to_string(),
+                    ics20_channel_id: Some(ics20_channel.to_string()),
+                    direct_channel_id: Some(direct_channel.to_string()),
+                    supported_modules: vec![],
+                },
+            )
+            .unwrap();
+        CHANNEL_TO_CHAIN
+            .save(storage, direct_channel, &chain.to_string())
+            .unwrap();
+        CHANNEL_TO_CHAIN
+            .save(storage, ics20_channel, &chain.to_string())
+            .unwrap();
+    }
+    deps
+}
+
+fn origin_hop() -> CrossChainHop {
+    CrossChainHop::new(
+        "channel-osmo",
+        "andromeda".to_string(),
+        "osmosis".to_string(),
+        "origin".to_string(),
+        None,
+    )
+}
+
+#[test]
+fn test_forward_message() {
+    let mut deps = setup();
+    let recipient = AndrAddr::from_route(&["juno"], "/home/user/app");
+    let mut ctx = AMPCtx::new("origin", "andromeda_kernel", None);
+    ctx.add_hop(origin_hop());
+    let amp_packet = AMPPkt::new_with_ctx(
+        ctx,
+        vec![AMPMsg::new(
+            recipient,
+            to_json_binary(&"message").unwrap(),
+            None,
+        )],
+    );
+    let msg = mock_ibc_packet_recv(
+        ORIGIN_DIRECT_CHANNEL,
+        &IbcExecuteMsg::SendMessage { amp_packet },
+    )
+    .unwrap();
+
+    let res = do_ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+    let CosmosMsg::Ibc(IbcMsg::SendPacket {
+        channel_id, data, ..
+    }) = &res.messages[0].msg
+    else {
+        panic!("Expected the packet to be forwarded");
+    };
+    assert_eq!(channel_id, DESTINATION_DIRECT_CHANNEL);
+
+    let IbcExecuteMsg::SendMessage { amp_packet } = from_json::<IbcExecuteMsg>(data).unwrap()
+    else {
+        panic!("Expected an AMP packet");
+    };
+    assert_eq!(amp_packet.messages[0].recipient, "/home/user/app");
+    assert_eq!(amp_packet.ctx.get_origin(), "origin");
+    assert_eq!(
+        amp_packet.ctx.previous_hops,
+        vec![
+            origin_hop(),
+            CrossChainHop::new(
+                DESTINATION_DIRECT_CHANNEL,
+                "osmosis".to_string(),
+                "juno".to_string(),
+                "origin".to_string(),
+                None,
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_forward_message_hop_limit() {
+    let mut deps = setup();
+    let mut ctx = AMPCtx::new("origin", "andromeda_kernel", None);
+    for _ in 0..MAX_CROSS_CHAIN_HOPS {
+        ctx.add_hop(origin_hop());
+    }
+    let amp_packet = AMPPkt::new_with_ctx(
+        ctx,
+        vec![AMPMsg::new(
+            "ibc://juno/home/user/app",
+            to_json_binary(&"message").unwrap(),
+            None,
+        )],
+    );
+    let msg = mock_ibc_packet_recv(
+        ORIGIN_DIRECT_CHANNEL,
+        &IbcExecuteMsg::SendMessage { amp_packet },
+    )
+    .unwrap();
+
+    let err = do_ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPacket {
+            error: Some(format!(
+                "Packets cannot be routed through more than {MAX_CROSS_CHAIN_HOPS} chains"
+            ))
+        }
+    );
+}
+
+#[test]
+fn test_forward_funds() {
+    let mut deps = setup();
+    let env = mock_env();
+    let funds = coin(100, "ibc/uandr");
+    let msg = mock_ibc_packet_recv(
+        ORIGIN_DIRECT_CHANNEL,
+        &IbcExecuteMsg::SendMessageWithFunds {
+            recipient: AndrAddr::from_string("ibc://juno/home/user/app"),
+            message: Binary::default(),
+            funds: funds.clone(),
+            original_sender: "origin".to_string(),
+            original_sender_username: None,
+            previous_hops: vec![origin_hop()],
+        },
+    )
+    .unwrap();
+
+    let res = do_ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+    assert_eq!(res.messages[0].id, ReplyId::IBCTransfer.repr());
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: DESTINATION_ICS20_CHANNEL.to_string(),
+            to_address: "juno_kernel".to_string(),
+            amount: funds,
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(604_800)),
+            memo: Some(ibc_callback_memo(&env)),
+        })
+    );
+
+    let pending = PENDING_MSG_AND_FUNDS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        pending.forwarded,
+        Some(ForwardedFunds {
+            original_sender: "origin".to_string(),
+            original_sender_username: None,
+            previous_hops: vec![origin_hop()],
+            channel: ORIGIN_ICS20_CHANNEL.to_string(),
+            recovery_addr: None,
+        })
+    );
+    // Refunds of forwarded funds are handled by the relay instead
+    assert!(!REFUND_DATA.exists(deps.as_ref().storage));
+}
+
+#[test]
+fn test_forward_funds_rate_limit() {
+    let mut deps = setup();
+    ORIGIN_RATE_LIMITS
+        .save(
+            deps.as_mut().storage,
+            &remote_origin("andromeda", "origin"),
+            &RateLimit {
+                window: 60,
+                max_messages: Some(1),
+                max_funds: vec![],
+            },
+        )
+        .unwrap();
+    let forward = |deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, sender: &str| {
+        let msg = mock_ibc_packet_recv(
+            ORIGIN_DIRECT_CHANNEL,
+            &IbcExecuteMsg::SendMessageWithFunds {
+                recipient: AndrAddr::from_string("ibc://juno/home/user/app"),
+                message: Binary::default(),
+                funds: coin(100, "ibc/uandr"),
+                original_sender: sender.to_string(),
+                original_sender_username: None,
+                previous_hops: vec![origin_hop()],
+            },
+        )
+        .unwrap();
+        do_ibc_packet_receive(deps.as_mut(), mock_env(), msg)
+    };
+
+    forward(&mut deps, "origin").unwrap();
+    let err = forward(&mut deps, "origin").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RateLimitExceeded {
+            scope: "origin ibc://andromeda/origin".to_string()
+        }
+    );
+    // Other senders relayed by the same kernel are limited separately
+    forward(&mut deps, "other").unwrap();
+}
+
+#[rstest]
+#[case::recovered_at_hop(Some(Addr::unchecked("origin_on_osmosis")))]
+#[case::returned(None)]
+fn test_forwarded_funds_failure(#[case] recovery_addr: Option<Addr>) {
+    let mut deps = setup();
+    let env = mock_env();
+    let trigger = deps.api.addr_make("trigger");
+    KERNEL_ADDRESSES
+        .save(deps.as_mut().storage, TRIGGER_KEY, &trigger)
+        .unwrap();
+    let funds = coin(100, "ibc/uandr");
+    CHANNEL_TO_EXECUTE_MSG
+        .save(
+            deps.as_mut().storage,
+            (DESTINATION_ICS20_CHANNEL.to_string(), 1),
+            &Ics20PacketInfo {
+                sender: env.contract.address.to_string(),
+                recipient: AndrAddr::from_string("ibc://juno/home/user/app"),
+                message: Binary::default(),
+                funds: funds.clone(),
+                channel: DESTINATION_ICS20_CHANNEL.to_string(),
+                pending: false,
+                ibc_config: None,
+                retried: false,
+                forwarded: Some(ForwardedFunds {
+                    original_sender: "origin".to_string(),
+                    original_sender_username: None,
+                    previous_hops: vec![origin_hop()],
+                    channel: ORIGIN_ICS20_CHANNEL.to_string(),
+                    recovery_addr: recovery_addr.clone(),
+                }),
+            },
+        )
+        .unwrap();
+
+    let res = trigger_relay(
+        ExecuteContext::new(deps.as_mut(), message_info(&trigger, &[]), env.clone()),
+        1,
+        DESTINATION_ICS20_CHANNEL.to_string(),
+        to_json_binary(&StdAck::error("timeout")).unwrap(),
+    )
+    .unwrap();
+
+    match recovery_addr {
+        Some(recovery_addr) => {
+            assert!(res.messages.is_empty());
+            let records = owner_recoveries(deps.as_ref().storage, &recovery_addr).unwrap();
+            assert_eq!(records.len(), 1);
+            assert_eq!(records[0].amount, funds);
+            assert_eq!(records[0].sequence, Some(1));
+        }
+        None => {
+            assert_eq!(
+                res.messages[0].msg,
+                CosmosMsg::Ibc(IbcMsg::Transfer {
+                    channel_id: ORIGIN_ICS20_CHANNEL.to_string(),
+                    to_address: "origin".to_string(),
+                    amount: funds,
+                    timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(604_800)),
+                    memo: None,
+                })
+            );
+        }
+    }
+}
+
+/// Funds sent from andromeda through juno to osmosis, failing on their way back to andromeda
+fn three_hop_route() -> Vec<CrossChainHop> {
+    vec![
+        CrossChainHop::new(
+            "channel-andr-juno",
+            "andromeda".to_string(),
+            "juno".to_string(),
+            "origin".to_string(),
+            None,
+        ),
+        CrossChainHop::new(
+            "channel-osmo",
+            "juno".to_string(),
+            "osmosis".to_string(),
+            "origin".to_string(),
+            None,
+        ),
+    ]
+}
+
+fn assert_returned_through_juno(
+    deps: &OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
+    res: &Response,
+    env: &Env,
+    funds: Coin,
+) {
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, ReplyId::IBCTransfer.repr());
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: DESTINATION_ICS20_CHANNEL.to_string(),
+            to_address: "juno_kernel".to_string(),
+            amount: funds.clone(),
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(604_800)),
+            memo: Some(ibc_callback_memo(env)),
+        })
+    );
+
+    // The juno kernel relays the funds on to the original sender on andromeda
+    let pending = PENDING_MSG_AND_FUNDS.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        pending.recipient,
+        AndrAddr::from_string("ibc://juno/ibc://andromeda/origin")
+    );
+    assert_eq!(pending.message, Binary::default());
+    assert_eq!(pending.funds, funds);
+    assert_eq!(
+        pending.forwarded,
+        Some(ForwardedFunds {
+            original_sender: "origin".to_string(),
+            original_sender_username: None,
+            previous_hops: vec![],
+            channel: DESTINATION_ICS20_CHANNEL.to_string(),
+            recovery_addr: None,
+        })
+    );
+}
+
+#[test]
+fn test_three_hop_forwarded_funds_failure() {
+    let mut deps = setup();
+    let env = mock_env();
+    let trigger = deps.api.addr_make("trigger");
+    KERNEL_ADDRESSES
+        .save(deps.as_mut().storage, TRIGGER_KEY, &trigger)
+        .unwrap();
+    let funds = coin(100, "ibc/uandr");
+    CHANNEL_TO_EXECUTE_MSG
+        .save(
+            deps.as_mut().storage,
+            (ORIGIN_ICS20_CHANNEL.to_string(), 1),
+            &Ics20PacketInfo {
+                sender: env.contract.address.to_string(),
+                recipient: AndrAddr::from_string("ibc://andromeda/home/user/app"),
+                message: Binary::default(),
+                funds: funds.clone(),
+                channel: ORIGIN_ICS20_CHANNEL.to_string(),
+                pending: false,
+                ibc_config: None,
+                retried: false,
+                forwarded: Some(ForwardedFunds {
+                    original_sender: "origin".to_string(),
+                    original_sender_username: None,
+                    previous_hops: three_hop_route(),
+                    channel: DESTINATION_ICS20_CHANNEL.to_string(),
+                    recovery_addr: None,
+                }),
+            },
+        )
+        .unwrap();
+
+    let res = trigger_relay(
+        ExecuteContext::new(deps.as_mut(), message_info(&trigger, &[]), env.clone()),
+        1,
+        ORIGIN_ICS20_CHANNEL.to_string(),
+        to_json_binary(&StdAck::error("timeout")).unwrap(),
+    )
+    .unwrap();
+
+    assert_returned_through_juno(&deps, &res, &env, funds);
+}
+
+#[test]
+fn test_three_hop_message_failure() {
+    let mut deps = setup();
+    let env = mock_env();
+    let funds = coin(100, "ibc/uandr");
+    REFUND_DATA
+        .save(
+            deps.as_mut().storage,
+            &RefundData {
+                original_sender: "origin".to_string(),
+                funds: funds.clone(),
+                channel: DESTINATION_ICS20_CHANNEL.to_string(),
+                recovery_addr: None,
+                previous_hops: three_hop_route(),
+            },
+        )
+        .unwrap();
+
+    let res = on_reply_refund_ibc_transfer_with_msg(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: ReplyId::IBCTransferWithMsg.repr(),
+            result: SubMsgResult::Err("error".to_string()),
+            payload: Binary::default(),
+            gas_used: 0,
+        },
+    )
+    .unwrap();
+
+    assert_returned_through_juno(&deps, &res, &env, funds);
+    assert!(!REFUND_DATA.exists(deps.as_ref().storage));
+}
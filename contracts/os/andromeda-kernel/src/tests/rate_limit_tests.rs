@@ -0,0 +1,223 @@
+use crate::{
+    contract::{execute, query},
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::{messages::AMPMsg, AndrAddr},
+    error::ContractError,
+    os::kernel::{
+        ExecuteMsg, QueryMsg, RateLimit, RateLimitCounter, RateLimitScope, RateLimitStatusResponse,
+    },
+    testing::mock_querier::{WasmMockQuerier, RECEIVER},
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    Addr, Binary, Coin, Env, OwnedDeps, Response,
+};
+
+fn set_rate_limit(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    sender: &Addr,
+    scope: RateLimitScope,
+    limit: Option<RateLimit>,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(sender, &[]),
+        ExecuteMsg::SetRateLimit { scope, limit },
+    )
+}
+
+fn send(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    sender: &Addr,
+    funds: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(sender, &funds),
+        ExecuteMsg::Send {
+            message: AMPMsg::new(RECEIVER, Binary::default(), Some(funds)),
+        },
+    )
+}
+
+fn status(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    origin: &Addr,
+) -> RateLimitStatusResponse {
+    from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RateLimitStatus {
+                origin: Some(origin.to_string()),
+                destination: Some(AndrAddr::from_string(RECEIVER)),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+fn status_messages(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    origin: &Addr,
+) -> u64 {
+    status(deps, env, origin).origin.unwrap().counter.messages
+}
+
+#[test]
+fn test_origin_rate_limit() {
+    let (mut deps, owner) = setup_kernel();
+    let sender = deps.api.addr_make("sender");
+    let other = deps.api.addr_make("other");
+    set_rate_limit(
+        &mut deps,
+        &owner,
+        RateLimitScope::Origins {},
+        Some(RateLimit {
+            window: 10,
+            max_messages: Some(2),
+            max_funds: vec![],
+        }),
+    )
+    .unwrap();
+
+    let mut env = mock_env();
+    env.block.height = 100;
+    send(&mut deps, &env, &sender, vec![coin(10, "uandr")]).unwrap();
+    send(&mut deps, &env, &sender, vec![coin(10, "uandr")]).unwrap();
+    let err = send(&mut deps, &env, &sender, vec![coin(10, "uandr")]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RateLimitExceeded {
+            scope: format!("origin {sender}")
+        }
+    );
+
+    // Each origin is counted separately
+    send(&mut deps, &env, &other, vec![coin(10, "uandr")]).unwrap();
+
+    let status = status(&deps, &env, &sender).origin.unwrap();
+    assert_eq!(
+        status.counter,
+        RateLimitCounter {
+            window_start: 100,
+            messages: 2,
+            funds: vec![coin(20, "uandr")],
+        }
+    );
+
+    // Counters are reset in the next window
+    env.block.height = 110;
+    assert_eq!(status_messages(&deps, &env, &sender), 0);
+    send(&mut deps, &env, &sender, vec![coin(10, "uandr")]).unwrap();
+
+    // Origin specific limits override the default limit
+    set_rate_limit(
+        &mut deps,
+        &owner,
+        RateLimitScope::Origin {
+            address: sender.to_string(),
+        },
+        Some(RateLimit {
+            window: 10,
+            max_messages: Some(5),
+            max_funds: vec![],
+        }),
+    )
+    .unwrap();
+    send(&mut deps, &env, &sender, vec![coin(10, "uandr")]).unwrap();
+    send(&mut deps, &env, &sender, vec![coin(10, "uandr")]).unwrap();
+    assert_eq!(status_messages(&deps, &env, &sender), 3);
+}
+
+#[test]
+fn test_destination_funds_rate_limit() {
+    let (mut deps, owner) = setup_kernel();
+    let sender = deps.api.addr_make("sender");
+    let other = deps.api.addr_make("other");
+    set_rate_limit(
+        &mut deps,
+        &owner,
+        RateLimitScope::Destination {
+            address: AndrAddr::from_string(RECEIVER),
+        },
+        Some(RateLimit {
+            window: 5,
+            max_messages: None,
+            max_funds: vec![coin(100, "uandr")],
+        }),
+    )
+    .unwrap();
+
+    let env = mock_env();
+    send(&mut deps, &env, &sender, vec![coin(60, "uandr")]).unwrap();
+    // Denoms without a limit are not restricted
+    send(&mut deps, &env, &sender, vec![coin(1000, "uusd")]).unwrap();
+
+    // The destination limit is shared by all origins
+    let err = send(&mut deps, &env, &other, vec![coin(60, "uandr")]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RateLimitExceeded {
+            scope: format!("destination {RECEIVER}")
+        }
+    );
+    send(&mut deps, &env, &other, vec![coin(40, "uandr")]).unwrap();
+
+    let status = status(&deps, &env, &sender);
+    assert!(status.origin.is_none());
+    assert_eq!(
+        status.destination.unwrap().counter.funds,
+        vec![coin(100, "uandr"), coin(1000, "uusd")]
+    );
+
+    // Removing the limit lifts the restriction
+    set_rate_limit(
+        &mut deps,
+        &owner,
+        RateLimitScope::Destination {
+            address: AndrAddr::from_string(RECEIVER),
+        },
+        None,
+    )
+    .unwrap();
+    send(&mut deps, &env, &other, vec![coin(60, "uandr")]).unwrap();
+}
+
+#[test]
+fn test_set_rate_limit_unauthorized() {
+    let (mut deps, owner) = setup_kernel();
+    let sender = deps.api.addr_make("sender");
+    let limit = RateLimit {
+        window: 10,
+        max_messages: Some(1),
+        max_funds: vec![],
+    };
+
+    let err = set_rate_limit(
+        &mut deps,
+        &sender,
+        RateLimitScope::Origins {},
+        Some(limit.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = set_rate_limit(
+        &mut deps,
+        &owner,
+        RateLimitScope::Origins {},
+        Some(RateLimit { window: 0, ..limit }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidZeroAmount {});
+}
@@ -0,0 +1,442 @@
+use crate::{
+    contract::{execute, query, reply, sudo},
+    ibc::{ibc_callback_memo, IBCLifecycleComplete, SudoMsg, PACKET_LIFETIME},
+    recovery::{migrate_legacy_recoveries, record_recovery},
+    state::{
+        OutgoingPacket, CHAIN_TO_CHANNEL, CHANNEL_TO_CHAIN, IBC_FUND_RECOVERY, OUTGOING_IBC_PACKETS,
+    },
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::AndrAddr,
+    error::ContractError,
+    os::kernel::{
+        ChannelInfo, ExecuteMsg, QueryMsg, RecoveriesResponse, RecoveryReason,
+        RecoveryRecordsResponse,
+    },
+    testing::mock_querier::WasmMockQuerier,
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    Addr, BankMsg, CosmosMsg, Event, IbcMsg, OwnedDeps, Reply, StdError, SubMsgResponse,
+    SubMsgResult,
+};
+use rstest::rstest;
+
+const CHANNEL: &str = "channel-juno-ics20";
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let (mut deps, _) = setup_kernel();
+    CHANNEL_TO_CHAIN
+        .save(deps.as_mut().storage, CHANNEL, &"juno".to_string())
+        .unwrap();
+    CHAIN_TO_CHANNEL
+        .save(
+            deps.as_mut().storage,
+            "juno",
+            &ChannelInfo {
+                kernel_address: "juno_kernel".to_string(),
+                ics20_channel_id: Some(CHANNEL.to_string()),
+                direct_channel_id: Some("channel-juno".to_string()),
+                supported_modules: vec![],
+            },
+        )
+        .unwrap();
+    deps
+}
+
+fn recoveries(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    addr: &Addr,
+) -> RecoveriesResponse {
+    from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Recoveries { addr: addr.clone() },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_failed_ack_records_origin() {
+    let mut deps = setup();
+    let user = deps.api.addr_make("user");
+    OUTGOING_IBC_PACKETS
+        .save(
+            deps.as_mut().storage,
+            (&CHANNEL.to_string(), 7),
+            &OutgoingPacket {
+                recovery_addr: user.clone(),
+                amount: coin(100, "uandr"),
+            },
+        )
+        .unwrap();
+    OUTGOING_IBC_PACKETS
+        .save(
+            deps.as_mut().storage,
+            (&CHANNEL.to_string(), 8),
+            &OutgoingPacket {
+                recovery_addr: user.clone(),
+                amount: coin(50, "uandr"),
+            },
+        )
+        .unwrap();
+
+    sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCAck {
+            channel: CHANNEL.to_string(),
+            sequence: 7,
+            ack: String::new(),
+            success: false,
+        }),
+    )
+    .unwrap();
+    sudo(
+        deps.as_mut(),
+        mock_env(),
+        SudoMsg::IBCLifecycleComplete(IBCLifecycleComplete::IBCTimeout {
+            channel: CHANNEL.to_string(),
+            sequence: 8,
+        }),
+    )
+    .unwrap();
+
+    let res = recoveries(&deps, &user);
+    assert_eq!(res.recoveries, vec![coin(150, "uandr")]);
+    assert_eq!(res.records.len(), 2);
+
+    let record = &res.records[0];
+    assert_eq!(record.id, 1);
+    assert_eq!(record.owner, user);
+    assert_eq!(record.amount, coin(100, "uandr"));
+    assert_eq!(record.reason, RecoveryReason::TransferFailed);
+    assert_eq!(record.chain, Some("juno".to_string()));
+    assert_eq!(record.channel, Some(CHANNEL.to_string()));
+    assert_eq!(record.sequence, Some(7));
+    assert_eq!(record.timestamp, mock_env().block.time);
+    assert_eq!(res.records[1].reason, RecoveryReason::TransferTimeout);
+    assert_eq!(res.records[1].sequence, Some(8));
+}
+
+#[test]
+fn test_recover_by_denom() {
+    let mut deps = setup();
+    let user = deps.api.addr_make("user");
+    let env = mock_env();
+    for amount in [coin(100, "uandr"), coin(20, "uusd"), coin(5, "uandr")] {
+        record_recovery(
+            deps.as_mut().storage,
+            env.block.time,
+            &user,
+            amount,
+            RecoveryReason::TransferFailed,
+            Some(CHANNEL.to_string()),
+            None,
+        )
+        .unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::Recover {
+            denom: Some("uandr".to_string()),
+            recipient: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: user.to_string(),
+            amount: vec![coin(105, "uandr")],
+        })
+    );
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "recovery_ids" && attr.value == "1,3"));
+
+    let res = recoveries(&deps, &user);
+    assert_eq!(res.recoveries, vec![coin(20, "uusd")]);
+
+    // Nothing is left to recover for the denom
+    let err = execute(
+        deps.as_mut(),
+        env,
+        message_info(&user, &[]),
+        ExecuteMsg::Recover {
+            denom: Some("uandr".to_string()),
+            recipient: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("No recoveries found"))
+    );
+}
+
+#[test]
+fn test_recover_to_recipient() {
+    let mut deps = setup();
+    let user = deps.api.addr_make("user");
+    let other = deps.api.addr_make("other");
+    let env = mock_env();
+    for _ in 0..2 {
+        record_recovery(
+            deps.as_mut().storage,
+            env.block.time,
+            &user,
+            coin(100, "uandr"),
+            RecoveryReason::ExecutionFailed,
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::Recover {
+            denom: None,
+            recipient: Some(AndrAddr::from_string(other.to_string())),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: other.to_string(),
+            amount: vec![coin(200, "uandr")],
+        })
+    );
+
+    record_recovery(
+        deps.as_mut().storage,
+        env.block.time,
+        &user,
+        coin(100, "uandr"),
+        RecoveryReason::ExecutionFailed,
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Unknown chains cannot receive recoveries
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::Recover {
+            denom: None,
+            recipient: Some(AndrAddr::from_string("ibc://osmosis/osmo1user")),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPacket {
+            error: Some("Channel not found for chain osmosis".to_string())
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::Recover {
+            denom: None,
+            recipient: Some(AndrAddr::from_string("ibc://juno/juno1user")),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: CHANNEL.to_string(),
+            to_address: "juno1user".to_string(),
+            amount: coin(100, "uandr"),
+            timeout: env.block.time.plus_seconds(PACKET_LIFETIME).into(),
+            memo: Some(ibc_callback_memo(&env)),
+        })
+    );
+    assert!(recoveries(&deps, &user).records.is_empty());
+}
+
+#[rstest]
+#[case::failed_ack(IBCLifecycleComplete::IBCAck {
+    channel: CHANNEL.to_string(),
+    sequence: 3,
+    ack: String::new(),
+    success: false,
+}, RecoveryReason::TransferFailed)]
+#[case::timeout(IBCLifecycleComplete::IBCTimeout {
+    channel: CHANNEL.to_string(),
+    sequence: 3,
+}, RecoveryReason::TransferTimeout)]
+#[allow(deprecated)]
+fn test_failed_recovery_transfer(
+    #[case] lifecycle: IBCLifecycleComplete,
+    #[case] reason: RecoveryReason,
+) {
+    let mut deps = setup();
+    let user = deps.api.addr_make("user");
+    let env = mock_env();
+    record_recovery(
+        deps.as_mut().storage,
+        env.block.time,
+        &user,
+        coin(100, "uandr"),
+        RecoveryReason::ExecutionFailed,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::Recover {
+            denom: None,
+            recipient: Some(AndrAddr::from_string("ibc://juno/juno1user")),
+        },
+    )
+    .unwrap();
+    assert!(recoveries(&deps, &user).records.is_empty());
+
+    // The transfer's packet is stored once it has been sent
+    let sub_msg = &res.messages[0];
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: sub_msg.id,
+            payload: sub_msg.payload.clone(),
+            gas_used: 0,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("send_packet").add_attribute("packet_sequence", "3")],
+                data: None,
+                msg_responses: vec![],
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        OUTGOING_IBC_PACKETS
+            .load(deps.as_ref().storage, (&CHANNEL.to_string(), 3))
+            .unwrap(),
+        OutgoingPacket {
+            recovery_addr: user.clone(),
+            amount: coin(100, "uandr"),
+        }
+    );
+
+    sudo(deps.as_mut(), env, SudoMsg::IBCLifecycleComplete(lifecycle)).unwrap();
+    let res = recoveries(&deps, &user);
+    assert_eq!(res.recoveries, vec![coin(100, "uandr")]);
+    assert_eq!(res.records[0].reason, reason);
+    assert_eq!(res.records[0].sequence, Some(3));
+    assert!(!OUTGOING_IBC_PACKETS.has(deps.as_ref().storage, (&CHANNEL.to_string(), 3)));
+}
+
+#[test]
+fn test_all_recoveries() {
+    let mut deps = setup();
+    let env = mock_env();
+    let users = [deps.api.addr_make("user1"), deps.api.addr_make("user2")];
+    for (i, user) in users.iter().cycle().take(6).enumerate() {
+        let denom = if i % 2 == 0 { "uandr" } else { "uusd" };
+        let channel = if i < 3 {
+            Some(CHANNEL.to_string())
+        } else {
+            None
+        };
+        record_recovery(
+            deps.as_mut().storage,
+            env.block.time,
+            user,
+            coin(10, denom),
+            RecoveryReason::TransferFailed,
+            channel,
+            None,
+        )
+        .unwrap();
+    }
+
+    let all_recoveries =
+        |denom: Option<&str>, chain: Option<&str>, start_after: Option<u64>, limit: Option<u32>| {
+            let res: RecoveryRecordsResponse = from_json(
+                query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::AllRecoveries {
+                        denom: denom.map(str::to_string),
+                        chain: chain.map(str::to_string),
+                        start_after,
+                        limit,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.records
+                .into_iter()
+                .map(|record| record.id)
+                .collect::<Vec<u64>>()
+        };
+
+    assert_eq!(
+        all_recoveries(None, None, None, None),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+    assert_eq!(all_recoveries(None, None, Some(2), Some(3)), vec![3, 4, 5]);
+    assert_eq!(
+        all_recoveries(Some("uusd"), None, None, None),
+        vec![2, 4, 6]
+    );
+    assert_eq!(
+        all_recoveries(None, Some("juno"), None, None),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        all_recoveries(Some("uandr"), Some("juno"), None, Some(1)),
+        vec![1]
+    );
+}
+
+#[test]
+fn test_migrate_legacy_recoveries() {
+    let mut deps = setup();
+    let user = deps.api.addr_make("user");
+    IBC_FUND_RECOVERY
+        .save(
+            deps.as_mut().storage,
+            &user,
+            &vec![coin(10, "uandr"), coin(5, "uandr")],
+        )
+        .unwrap();
+
+    migrate_legacy_recoveries(deps.as_mut().storage, mock_env().block.time).unwrap();
+
+    assert!(IBC_FUND_RECOVERY
+        .may_load(deps.as_ref().storage, &user)
+        .unwrap()
+        .is_none());
+    let res = recoveries(&deps, &user);
+    assert_eq!(res.recoveries, vec![coin(15, "uandr")]);
+    assert!(res
+        .records
+        .iter()
+        .all(|record| record.reason == RecoveryReason::Legacy && record.chain.is_none()));
+}
@@ -0,0 +1,206 @@
+use crate::{
+    contract::{execute, query},
+    execute::wrap_scheduled_sub_msg,
+    state::SCHEDULE_QUEUE,
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::messages::AMPMsg,
+    common::{expiration::Expiry, reply::ReplyId, Milliseconds},
+    error::ContractError,
+    os::kernel::{ExecuteMsg, QueryMsg, ScheduledMessagesResponse},
+    testing::mock_querier::{WasmMockQuerier, RECEIVER},
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Env, OwnedDeps, ReplyOn, SubMsg,
+};
+
+fn setup() -> (OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Env) {
+    let (deps, _) = setup_kernel();
+    (deps, mock_env())
+}
+
+fn schedule(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    sender: &Addr,
+    delay: u64,
+) -> Result<cosmwasm_std::Response, ContractError> {
+    let funds = vec![coin(100, "uandr")];
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(sender, &funds),
+        ExecuteMsg::ScheduleMessage {
+            message: AMPMsg::new(RECEIVER, Binary::default(), Some(funds.clone())),
+            expiry: Expiry::FromNow(Milliseconds(delay)),
+        },
+    )
+}
+
+#[test]
+fn test_schedule_and_process_messages() {
+    let (mut deps, mut env) = setup();
+    let sender = deps.api.addr_make("sender");
+
+    schedule(&mut deps, &env, &sender, 2000).unwrap();
+    schedule(&mut deps, &env, &sender, 1000).unwrap();
+
+    let res: ScheduledMessagesResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ScheduledMessages {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0].id, 0);
+    assert_eq!(res.messages[1].sender, sender);
+
+    // Nothing is due yet
+    let anyone = deps.api.addr_make("anyone");
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&anyone, &[]),
+        ExecuteMsg::ProcessScheduledMessages { limit: None },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    // Only the earliest message is released when the limit is one
+    env.block.time = env.block.time.plus_seconds(5);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&anyone, &[]),
+        ExecuteMsg::ProcessScheduledMessages { limit: Some(1) },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, ReplyId::ScheduledAMPMsg.repr());
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Error);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: RECEIVER.to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "scheduled:1" && attr.value == "released"));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&anyone, &[]),
+        ExecuteMsg::ProcessScheduledMessages { limit: None },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert!(SCHEDULE_QUEUE.is_empty(deps.as_ref().storage));
+}
+
+#[test]
+fn test_schedule_message_invalid() {
+    let (mut deps, env) = setup();
+    let sender = deps.api.addr_make("sender");
+
+    // Insufficient funds
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::ScheduleMessage {
+            message: AMPMsg::new(RECEIVER, Binary::default(), Some(vec![coin(100, "uandr")])),
+            expiry: Expiry::FromNow(Milliseconds(1000)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {});
+
+    // IBC messages with funds
+    let funds = vec![coin(100, "uandr")];
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &funds),
+        ExecuteMsg::ScheduleMessage {
+            message: AMPMsg::new(
+                "ibc://osmosis/home/user",
+                to_json_binary(&true).unwrap(),
+                Some(funds.clone()),
+            ),
+            expiry: Expiry::FromNow(Milliseconds(1000)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPacket {
+            error: Some("Scheduled IBC messages cannot carry funds".to_string())
+        }
+    );
+}
+
+#[test]
+fn test_cancel_scheduled_message() {
+    let (mut deps, env) = setup();
+    let sender = deps.api.addr_make("sender");
+    schedule(&mut deps, &env, &sender, 1000).unwrap();
+
+    let other = deps.api.addr_make("other");
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&other, &[]),
+        ExecuteMsg::CancelScheduledMessage { id: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::CancelScheduledMessage { id: 0 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    assert!(SCHEDULE_QUEUE.is_empty(deps.as_ref().storage));
+}
+
+#[test]
+fn test_scheduled_sub_msg_keeps_own_reply() {
+    let payload = to_json_binary(&"payload").unwrap();
+    let transfer = SubMsg::reply_always(
+        BankMsg::Send {
+            to_address: RECEIVER.to_string(),
+            amount: vec![coin(100, "uandr")],
+        },
+        ReplyId::IBCTransfer.repr(),
+    );
+    let wrapped = wrap_scheduled_sub_msg(transfer.clone(), &payload);
+    assert_eq!(wrapped, transfer);
+
+    let send = SubMsg::reply_always(transfer.msg.clone(), ReplyId::AMPMsg.repr());
+    let wrapped = wrap_scheduled_sub_msg(send, &payload);
+    assert_eq!(wrapped.id, ReplyId::ScheduledAMPMsg.repr());
+    assert_eq!(wrapped.reply_on, ReplyOn::Error);
+    assert_eq!(wrapped.payload, payload);
+}
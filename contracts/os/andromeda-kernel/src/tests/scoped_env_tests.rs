@@ -0,0 +1,402 @@
+use crate::{
+    contract::{execute, query},
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::{AndrAddr, VFS_KEY},
+    error::ContractError,
+    os::kernel::{EnvResponse, EnvScope, EnvValue, ExecuteMsg, QueryMsg, ScopedEnvResponse},
+    testing::mock_querier::{WasmMockQuerier, MOCK_VFS_CONTRACT},
+};
+use cosmwasm_std::{
+    from_json,
+    testing::{message_info, mock_env, MockApi, MockStorage},
+    Addr, Binary, CosmosMsg, OwnedDeps, Response, WasmMsg,
+};
+use serde_cw_value::Value;
+
+fn setup() -> (OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Addr) {
+    let (mut deps, owner) = setup_kernel();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        ExecuteMsg::UpsertKeyAddress {
+            key: VFS_KEY.to_string(),
+            value: MOCK_VFS_CONTRACT.to_string(),
+        },
+    )
+    .unwrap();
+    (deps, owner)
+}
+
+fn set_scoped_env(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    sender: &Addr,
+    scope: EnvScope,
+    variable: &str,
+    value: EnvValue,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(sender, &[]),
+        ExecuteMsg::SetScopedEnv {
+            scope,
+            variable: variable.to_string(),
+            value,
+        },
+    )
+}
+
+fn get_scoped_env(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    scope: EnvScope,
+    variable: &str,
+) -> Option<EnvValue> {
+    let res: ScopedEnvResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetScopedEnv {
+                scope,
+                variable: variable.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    res.value
+}
+
+#[test]
+fn test_global_scoped_env() {
+    let (mut deps, owner) = setup();
+
+    set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::Global {},
+        "amount",
+        EnvValue::Number(-10),
+    )
+    .unwrap();
+
+    assert_eq!(
+        get_scoped_env(&deps, EnvScope::Global {}, "AMOUNT"),
+        Some(EnvValue::Number(-10))
+    );
+    let res: EnvResponse = from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetEnv {
+                variable: "AMOUNT".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.value, Some("-10".to_string()));
+
+    // Variables set through SetEnv are readable as untyped strings
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        ExecuteMsg::SetEnv {
+            variable: "CHAIN".to_string(),
+            value: "andromeda".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        get_scoped_env(&deps, EnvScope::Global {}, "CHAIN"),
+        Some(EnvValue::String("andromeda".to_string()))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        ExecuteMsg::UnsetScopedEnv {
+            scope: EnvScope::Global {},
+            variable: "AMOUNT".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(get_scoped_env(&deps, EnvScope::Global {}, "AMOUNT"), None);
+}
+
+#[test]
+fn test_scoped_env_authorization() {
+    let (mut deps, owner) = setup();
+    let attacker = deps.api.addr_make("attacker");
+    let app = deps.api.addr_make("app");
+
+    let err = set_scoped_env(
+        &mut deps,
+        &attacker,
+        EnvScope::Global {},
+        "AMOUNT",
+        EnvValue::Number(1),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = set_scoped_env(
+        &mut deps,
+        &attacker,
+        EnvScope::App {
+            address: AndrAddr::from_string(app.to_string()),
+        },
+        "AMOUNT",
+        EnvValue::Number(1),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::User {
+            username: "owner".to_string(),
+        },
+        "AMOUNT",
+        EnvValue::Number(1),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The mock querier reports "owner" as the owner of every app and of the "owner" username
+    let app_owner = Addr::unchecked("owner");
+    set_scoped_env(
+        &mut deps,
+        &app_owner,
+        EnvScope::App {
+            address: AndrAddr::from_string(app.to_string()),
+        },
+        "AMOUNT",
+        EnvValue::Number(1),
+    )
+    .unwrap();
+    set_scoped_env(
+        &mut deps,
+        &app_owner,
+        EnvScope::User {
+            username: "owner".to_string(),
+        },
+        "AMOUNT",
+        EnvValue::Number(2),
+    )
+    .unwrap();
+
+    assert_eq!(
+        get_scoped_env(
+            &deps,
+            EnvScope::App {
+                address: AndrAddr::from_string(app.to_string()),
+            },
+            "AMOUNT"
+        ),
+        Some(EnvValue::Number(1))
+    );
+    assert_eq!(
+        get_scoped_env(
+            &deps,
+            EnvScope::User {
+                username: "owner".to_string(),
+            },
+            "AMOUNT"
+        ),
+        Some(EnvValue::Number(2))
+    );
+    assert_eq!(get_scoped_env(&deps, EnvScope::Global {}, "AMOUNT"), None);
+}
+
+#[test]
+fn test_scoped_env_invalid_value() {
+    let (mut deps, owner) = setup();
+
+    let err = set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::Global {},
+        "CONFIG",
+        EnvValue::Json("{not json".to_string()),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::InvalidEnvironmentVariable { .. }
+    ));
+
+    let err = set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::Global {},
+        "LABEL",
+        EnvValue::String(String::new()),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::InvalidEnvironmentVariable { .. }
+    ));
+}
+
+#[test]
+fn test_create_resolves_scoped_env() {
+    let (mut deps, owner) = setup();
+    let app_owner = Addr::unchecked("owner");
+
+    set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::Global {},
+        "AMOUNT",
+        EnvValue::Number(100),
+    )
+    .unwrap();
+    set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::Global {},
+        "CHAIN",
+        EnvValue::String("global".to_string()),
+    )
+    .unwrap();
+    // The creating app overrides the global value
+    set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::App {
+            address: AndrAddr::from_string(owner.to_string()),
+        },
+        "CHAIN",
+        EnvValue::String("app".to_string()),
+    )
+    .unwrap();
+    // The mock VFS resolves every address to the "owner" username
+    set_scoped_env(
+        &mut deps,
+        &app_owner,
+        EnvScope::User {
+            username: "owner".to_string(),
+        },
+        "CONFIG",
+        EnvValue::Json(r#"{"enabled":true}"#.to_string()),
+    )
+    .unwrap();
+
+    let msg = Binary::from(
+        br#"{"amount":"${AMOUNT}","label":"ado-${CHAIN}","config":"${CONFIG}"}"#.as_slice(),
+    );
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        ExecuteMsg::Create {
+            ado_type: "ado_type".to_string(),
+            msg,
+            owner: None,
+            chain: None,
+            resolve_env: true,
+        },
+    )
+    .unwrap();
+
+    let CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. }) = &res.messages[0].msg else {
+        panic!("Expected instantiate message");
+    };
+    let resolved: Value = from_json(msg).unwrap();
+    let expected: Value =
+        from_json(br#"{"amount":100,"label":"ado-app","config":{"enabled":true}}"#).unwrap();
+    assert_eq!(resolved, expected);
+
+    let msg = Binary::from(br#"{"amount":"${MISSING}"}"#.as_slice());
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        ExecuteMsg::Create {
+            ado_type: "ado_type".to_string(),
+            msg,
+            owner: None,
+            chain: None,
+            resolve_env: true,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::EnvironmentVariableNotFound { .. }
+    ));
+
+    // Messages without references are passed through untouched
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&owner, &[]),
+        ExecuteMsg::Create {
+            ado_type: "ado_type".to_string(),
+            msg: Binary::default(),
+            owner: None,
+            chain: None,
+            resolve_env: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn test_create_literal_env_references() {
+    let (mut deps, owner) = setup();
+    set_scoped_env(
+        &mut deps,
+        &owner,
+        EnvScope::Global {},
+        "AMOUNT",
+        EnvValue::Number(100),
+    )
+    .unwrap();
+
+    let create = |deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                  msg: &[u8],
+                  resolve_env: bool| {
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::Create {
+                ado_type: "ado_type".to_string(),
+                msg: Binary::from(msg),
+                owner: None,
+                chain: None,
+                resolve_env,
+            },
+        )
+        .unwrap();
+        let CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. }) = &res.messages[0].msg else {
+            panic!("Expected instantiate message");
+        };
+        from_json::<Value>(msg).unwrap()
+    };
+
+    // References are only resolved when requested
+    let msg = br#"{"amount":"${AMOUNT}","missing":"${MISSING}"}"#;
+    assert_eq!(
+        create(&mut deps, msg, false),
+        from_json::<Value>(msg).unwrap()
+    );
+
+    // Content that is not a valid variable name is kept as is
+    let msg =
+        br#"{"template":"${foo bar}","empty":"${}","open":"${AMOUNT","amount":"${ ${AMOUNT}"}"#;
+    let expected: Value = from_json(
+        br#"{"template":"${foo bar}","empty":"${}","open":"${AMOUNT","amount":"${ 100"}"#,
+    )
+    .unwrap();
+    assert_eq!(create(&mut deps, msg, true), expected);
+}
@@ -43,6 +43,7 @@ fn test_handle_local() {
         gas_limit: Some(1000000),
         direct: false,
         ibc_config: None,
+        condition: None,
     };
 
     let test_cases = vec![
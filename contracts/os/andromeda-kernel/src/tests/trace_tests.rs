@@ -0,0 +1,300 @@
+use crate::{
+    contract::{execute, query, reply},
+    ibc::ibc_packet_receive,
+    reply::AMPBundlePayload,
+    tests::setup_kernel,
+};
+use andromeda_std::{
+    amp::messages::{AMPMsg, AMPMsgConfig, AMPPkt},
+    common::reply::ReplyId,
+    error::ContractError,
+    os::kernel::{
+        ExecuteMsg, IbcExecuteMsg, PacketTrace, PacketTraceStatus, PacketTracesResponse, QueryMsg,
+    },
+    testing::mock_querier::{WasmMockQuerier, MOCK_APP_CONTRACT, RECEIVER},
+};
+use cosmwasm_std::{
+    coin, from_json,
+    testing::{message_info, mock_env, mock_ibc_packet_recv, MockApi, MockStorage},
+    to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Env, OwnedDeps, Reply, ReplyOn, Response,
+    SubMsgResponse, SubMsgResult,
+};
+
+fn setup(retention: Option<u64>) -> (OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Env) {
+    let (mut deps, owner) = setup_kernel();
+    let env = mock_env();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        ExecuteMsg::SetTraceRetention { retention },
+    )
+    .unwrap();
+    (deps, env)
+}
+
+fn receive_packet(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    origin: &str,
+) {
+    let funds = vec![coin(100, "uandr")];
+    let packet = AMPPkt::new(
+        origin,
+        env.contract.address.clone(),
+        vec![AMPMsg::new(
+            RECEIVER,
+            Binary::default(),
+            Some(funds.clone()),
+        )],
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &funds),
+        ExecuteMsg::AMPReceive(packet),
+    )
+    .unwrap();
+}
+
+fn query_trace(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    id: &str,
+) -> Option<PacketTrace> {
+    from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::PacketTrace { id: id.to_string() },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_set_trace_retention_unauthorized() {
+    let (mut deps, env) = setup(None);
+    let user = deps.api.addr_make("user");
+    let err = execute(
+        deps.as_mut(),
+        env,
+        message_info(&user, &[]),
+        ExecuteMsg::SetTraceRetention {
+            retention: Some(10),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_packet_traces_disabled() {
+    let (mut deps, env) = setup(None);
+    receive_packet(&mut deps, &env, "origin");
+    assert!(query_trace(&deps, "cosmos-testnet-14002.12345.0").is_none());
+}
+
+#[test]
+fn test_packet_traces_retention() {
+    let (mut deps, env) = setup(Some(2));
+    receive_packet(&mut deps, &env, "origin");
+    receive_packet(&mut deps, &env, "other");
+    receive_packet(&mut deps, &env, "origin");
+
+    // The oldest trace is pruned
+    assert!(query_trace(&deps, "cosmos-testnet-14002.12345.0").is_none());
+    let trace = query_trace(&deps, "cosmos-testnet-14002.12345.2").unwrap();
+    assert_eq!(trace.sequence, 2);
+    assert_eq!(trace.origin, "origin");
+    assert_eq!(trace.entries.len(), 1);
+    assert_eq!(trace.entries[0].recipient.to_string(), RECEIVER);
+    assert_eq!(trace.entries[0].funds, vec![coin(100, "uandr")]);
+    assert_eq!(trace.entries[0].status, PacketTraceStatus::Dispatched);
+
+    let res: PacketTracesResponse = from_json(
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PacketTraces {
+                origin: "origin".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.traces, vec![trace]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_packet_trace_atomic_bundle() {
+    let (mut deps, env) = setup(Some(10));
+    let packet = AMPPkt::new(
+        "origin",
+        env.contract.address.clone(),
+        vec![
+            AMPMsg::new(RECEIVER, Binary::default(), Some(vec![coin(100, "uandr")])),
+            AMPMsg::new(MOCK_APP_CONTRACT, to_json_binary(&true).unwrap(), None),
+        ],
+    )
+    .as_atomic();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &[coin(100, "uandr")]),
+        ExecuteMsg::AMPReceive(packet),
+    )
+    .unwrap();
+
+    let id = "cosmos-testnet-14002.12345.0";
+    let payload = AMPBundlePayload {
+        id: id.to_string(),
+        sender: env.contract.address.to_string(),
+        funds: vec![coin(100, "uandr")],
+        recipients: vec![RECEIVER.to_string(), MOCK_APP_CONTRACT.to_string()],
+    };
+    reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: ReplyId::AMPBundle.repr(),
+            result: SubMsgResult::Err("error".to_string()),
+            payload: to_json_binary(&payload).unwrap(),
+            gas_used: 0,
+        },
+    )
+    .unwrap();
+
+    let trace = query_trace(&deps, id).unwrap();
+    assert_eq!(trace.entries.len(), 2);
+    for entry in trace.entries {
+        assert_eq!(entry.status, PacketTraceStatus::Error);
+        assert_eq!(entry.error, Some("error".to_string()));
+    }
+}
+
+fn send_message(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    sender: &Addr,
+    exit_at_error: bool,
+) -> Response {
+    let funds = vec![coin(100, "uandr")];
+    let message =
+        AMPMsg::new(RECEIVER, Binary::default(), Some(funds.clone())).with_config(AMPMsgConfig {
+            exit_at_error,
+            ..AMPMsgConfig::default()
+        });
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(sender, &funds),
+        ExecuteMsg::Send { message },
+    )
+    .unwrap()
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_packet_trace_send() {
+    let (mut deps, env) = setup(Some(10));
+    let sender = deps.api.addr_make("sender");
+    let res = send_message(&mut deps, &env, &sender, true);
+
+    let id = "cosmos-testnet-14002.12345.0";
+    let trace = query_trace(&deps, id).unwrap();
+    assert_eq!(trace.origin, sender.to_string());
+    assert_eq!(trace.entries.len(), 1);
+    assert_eq!(trace.entries[0].recipient.to_string(), RECEIVER);
+    assert_eq!(trace.entries[0].status, PacketTraceStatus::Dispatched);
+
+    reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: ReplyId::AMPMsg.repr(),
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+            payload: res.messages[0].payload.clone(),
+            gas_used: 0,
+        },
+    )
+    .unwrap();
+    let trace = query_trace(&deps, id).unwrap();
+    assert_eq!(trace.entries[0].status, PacketTraceStatus::Success);
+}
+
+#[test]
+fn test_packet_trace_failed_message() {
+    let (mut deps, env) = setup(Some(10));
+    let sender = deps.api.addr_make("sender");
+    let res = send_message(&mut deps, &env, &sender, false);
+    let failure = Reply {
+        id: ReplyId::AMPMsg.repr(),
+        result: SubMsgResult::Err("error".to_string()),
+        payload: res.messages[0].payload.clone(),
+        gas_used: 0,
+    };
+
+    // The failure is recorded and the funds are refunded rather than reverting the transaction
+    let res = reply(deps.as_mut(), env.clone(), failure).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![coin(100, "uandr")],
+        })
+    );
+    let trace = query_trace(&deps, "cosmos-testnet-14002.12345.0").unwrap();
+    assert_eq!(trace.entries[0].status, PacketTraceStatus::Error);
+    assert_eq!(trace.entries[0].error, Some("error".to_string()));
+
+    // Messages that exit at an error still revert the transaction
+    let res = send_message(&mut deps, &env, &sender, true);
+    let failure = Reply {
+        id: ReplyId::AMPMsg.repr(),
+        result: SubMsgResult::Err("error".to_string()),
+        payload: res.messages[0].payload.clone(),
+        gas_used: 0,
+    };
+    assert!(reply(deps.as_mut(), env, failure).is_err());
+}
+
+#[test]
+fn test_untraced_message_reply() {
+    let (mut deps, env) = setup(None);
+    let sender = deps.api.addr_make("sender");
+
+    // Messages are dispatched unchanged when tracing is disabled
+    let res = send_message(&mut deps, &env, &sender, false);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Error);
+    assert!(res.messages[0].payload.is_empty());
+}
+
+#[test]
+fn test_packet_trace_failed_ibc_packet() {
+    let (mut deps, env) = setup(Some(10));
+    let address = deps.api.addr_make("user");
+    let msg = mock_ibc_packet_recv(
+        "channel-unknown",
+        &IbcExecuteMsg::RegisterUsername {
+            username: "user".to_string(),
+            address: address.to_string(),
+        },
+    )
+    .unwrap();
+    let id = format!("channel-unknown.{}", msg.packet.sequence);
+    ibc_packet_receive(deps.as_mut(), env, msg).unwrap();
+
+    let trace = query_trace(&deps, &id).unwrap();
+    assert_eq!(trace.origin, address.to_string());
+    assert_eq!(trace.entries.len(), 1);
+    assert_eq!(trace.entries[0].status, PacketTraceStatus::Error);
+    assert!(trace.entries[0].error.is_some());
+}
@@ -0,0 +1,323 @@
+use crate::reply::AMPMsgPayload;
+use crate::state::{
+    NEXT_TRACE_SEQUENCE, ORIGIN_TRACES, PACKET_TRACES, TRACE_RETENTION, TRACE_SEQUENCES,
+};
+use andromeda_std::{
+    amp::{
+        messages::{AMPMsg, CrossChainHop},
+        AndrAddr,
+    },
+    common::{reply::ReplyId, Milliseconds},
+    error::ContractError,
+    os::kernel::{IbcExecuteMsg, PacketTrace, PacketTraceEntry, PacketTraceStatus},
+};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Coin, Empty, Env, IbcPacket, Order, ReplyOn, Storage, SubMsg,
+};
+
+/// The maximum amount of traces pruned when a new trace is stored, bounds the gas cost of lowering the retention
+const MAX_PRUNE: usize = 5;
+
+/// Builds a trace entry at the current block time
+fn trace_entry(
+    env: &Env,
+    recipient: AndrAddr,
+    funds: Vec<Coin>,
+    previous_hops: &[CrossChainHop],
+    status: PacketTraceStatus,
+    error: Option<String>,
+) -> PacketTraceEntry {
+    PacketTraceEntry {
+        recipient,
+        funds,
+        previous_hops: previous_hops.to_vec(),
+        status,
+        error,
+        timestamp: Milliseconds::from_nanos(env.block.time.nanos()),
+    }
+}
+
+/// Builds dispatched trace entries for the given messages
+pub fn dispatched_entries(
+    env: &Env,
+    previous_hops: &[CrossChainHop],
+    messages: &[AMPMsg],
+) -> Vec<PacketTraceEntry> {
+    messages
+        .iter()
+        .map(|message| {
+            trace_entry(
+                env,
+                message.recipient.clone(),
+                message.funds.clone(),
+                previous_hops,
+                PacketTraceStatus::Dispatched,
+                None,
+            )
+        })
+        .collect()
+}
+
+/// Appends the given entries to the trace of the packet with the given ID, creating the trace if required.
+///
+/// Does nothing if tracing is disabled.
+pub fn record_trace(
+    storage: &mut dyn Storage,
+    id: &str,
+    origin: &str,
+    entries: Vec<PacketTraceEntry>,
+) -> Result<(), ContractError> {
+    let Some(retention) = TRACE_RETENTION.may_load(storage)? else {
+        return Ok(());
+    };
+
+    let trace = match PACKET_TRACES.may_load(storage, id)? {
+        Some(mut trace) => {
+            trace.entries.extend(entries);
+            trace
+        }
+        None => {
+            let sequence = NEXT_TRACE_SEQUENCE.may_load(storage)?.unwrap_or_default();
+            NEXT_TRACE_SEQUENCE.save(storage, &(sequence + 1))?;
+            TRACE_SEQUENCES.save(storage, sequence, &id.to_string())?;
+            ORIGIN_TRACES.save(storage, (origin, sequence), &Empty {})?;
+            prune_traces(storage, retention, sequence + 1)?;
+
+            PacketTrace {
+                id: id.to_string(),
+                sequence,
+                origin: origin.to_string(),
+                entries,
+            }
+        }
+    };
+    PACKET_TRACES.save(storage, id, &trace)?;
+
+    Ok(())
+}
+
+/// Dispatches the sub messages generated for a message with a reply recording their result.
+///
+/// Only the kernel's own AMP message sub messages are wrapped, messages handling their own replies are left untouched.
+/// Sub messages are returned as they are if the packet is not traced, so tracing does not change how failures are handled.
+/// Failures of traced messages that should not exit at an error are recorded and refunded to the sender.
+pub fn traced_sub_msgs(
+    storage: &dyn Storage,
+    id: &str,
+    sender: &Addr,
+    message: &AMPMsg,
+    sub_msgs: Vec<SubMsg>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    // The message's entry is the last one recorded for the packet
+    let entry = PACKET_TRACES
+        .may_load(storage, id)?
+        .and_then(|trace| trace.entries.len().checked_sub(1))
+        .map(|entry| entry as u64);
+    let Some(entry) = entry else {
+        return Ok(sub_msgs);
+    };
+
+    let payload = to_json_binary(&AMPMsgPayload {
+        id: id.to_string(),
+        entry: Some(entry),
+        sender: sender.to_string(),
+        funds: message.funds.clone(),
+        exit_at_error: message.config.exit_at_error,
+    })?;
+    Ok(sub_msgs
+        .into_iter()
+        .map(|sub_msg| {
+            if sub_msg.id != ReplyId::AMPMsg.repr() {
+                return sub_msg;
+            }
+            SubMsg {
+                reply_on: ReplyOn::Always,
+                payload: payload.clone(),
+                ..sub_msg
+            }
+        })
+        .collect())
+}
+
+/// The trace ID of a received IBC packet without an AMP packet ID
+pub fn received_packet_id(packet: &IbcPacket) -> String {
+    format!("{}.{}", packet.dest.channel_id, packet.sequence)
+}
+
+/// The ID, origin and entry under which a received IBC packet is traced.
+///
+/// Packets without an AMP packet ID are traced by their channel and sequence.
+pub fn received_packet_trace(
+    env: &Env,
+    packet: &IbcPacket,
+    msg: &IbcExecuteMsg,
+    status: PacketTraceStatus,
+    error: Option<String>,
+) -> (String, String, PacketTraceEntry) {
+    let fallback_id = received_packet_id(packet);
+    // Kernel level messages originate from the counterparty kernel
+    let counterparty_kernel = packet
+        .src
+        .port_id
+        .strip_prefix("wasm.")
+        .unwrap_or(&packet.src.port_id)
+        .to_string();
+    let (id, origin, recipient, funds, previous_hops) = match msg {
+        IbcExecuteMsg::SendMessage { amp_packet } => (
+            amp_packet.ctx.get_id().unwrap_or(fallback_id),
+            amp_packet.ctx.get_origin(),
+            amp_packet
+                .messages
+                .first()
+                .map(|message| message.recipient.clone())
+                .unwrap_or_else(|| AndrAddr::from_string(&counterparty_kernel)),
+            amp_packet
+                .messages
+                .first()
+                .map(|message| message.funds.clone())
+                .unwrap_or_default(),
+            amp_packet.ctx.previous_hops.clone(),
+        ),
+        IbcExecuteMsg::SendMessageWithFunds {
+            recipient,
+            funds,
+            original_sender,
+            previous_hops,
+            ..
+        } => (
+            fallback_id,
+            original_sender.clone(),
+            recipient.clone(),
+            vec![funds.clone()],
+            previous_hops.clone(),
+        ),
+        IbcExecuteMsg::CreateADO { owner, .. } => (
+            fallback_id,
+            owner.to_string(),
+            owner.clone(),
+            vec![],
+            vec![],
+        ),
+        IbcExecuteMsg::RegisterUsername { address, .. } => (
+            fallback_id,
+            address.clone(),
+            AndrAddr::from_string(address),
+            vec![],
+            vec![],
+        ),
+        IbcExecuteMsg::ResolvePath { path } => (
+            fallback_id,
+            counterparty_kernel,
+            AndrAddr::from_string(path),
+            vec![],
+            vec![],
+        ),
+    };
+    let entry = trace_entry(env, recipient, funds, &previous_hops, status, error);
+    (id, origin, entry)
+}
+
+/// Records a received IBC packet whose handling does not dispatch a traced message
+pub fn trace_received_ibc_packet(
+    storage: &mut dyn Storage,
+    env: &Env,
+    packet: &IbcPacket,
+) -> Result<(), ContractError> {
+    let msg = from_json::<IbcExecuteMsg>(&packet.data)?;
+    // Messages are traced as they are sent
+    if matches!(
+        msg,
+        IbcExecuteMsg::SendMessage { .. } | IbcExecuteMsg::SendMessageWithFunds { .. }
+    ) {
+        return Ok(());
+    }
+    let (id, origin, entry) =
+        received_packet_trace(env, packet, &msg, PacketTraceStatus::Success, None);
+    record_trace(storage, &id, &origin, vec![entry])
+}
+
+/// Records the failure of a received IBC packet
+pub fn trace_failed_ibc_packet(
+    storage: &mut dyn Storage,
+    env: &Env,
+    packet: &IbcPacket,
+    error: String,
+) -> Result<(), ContractError> {
+    let msg = from_json::<IbcExecuteMsg>(&packet.data)?;
+    let (id, origin, entry) =
+        received_packet_trace(env, packet, &msg, PacketTraceStatus::Error, Some(error));
+    record_trace(storage, &id, &origin, vec![entry])
+}
+
+/// Updates the status of a single entry of the trace of the packet with the given ID.
+///
+/// Does nothing if the trace or entry does not exist.
+pub fn update_trace_entry(
+    storage: &mut dyn Storage,
+    id: &str,
+    entry: u64,
+    status: PacketTraceStatus,
+    error: Option<String>,
+) -> Result<(), ContractError> {
+    let Some(mut trace) = PACKET_TRACES.may_load(storage, id)? else {
+        return Ok(());
+    };
+    let Some(trace_entry) = trace.entries.get_mut(entry as usize) else {
+        return Ok(());
+    };
+    trace_entry.status = status;
+    trace_entry.error = error;
+    PACKET_TRACES.save(storage, id, &trace)?;
+
+    Ok(())
+}
+
+/// Updates the status of the last `count` entries of the trace of the packet with the given ID.
+///
+/// Does nothing if the trace does not exist.
+pub fn update_trace_status(
+    storage: &mut dyn Storage,
+    id: &str,
+    count: usize,
+    status: PacketTraceStatus,
+    error: Option<String>,
+) -> Result<(), ContractError> {
+    let Some(mut trace) = PACKET_TRACES.may_load(storage, id)? else {
+        return Ok(());
+    };
+
+    let start = trace.entries.len().saturating_sub(count);
+    for entry in trace.entries.iter_mut().skip(start) {
+        entry.status = status.clone();
+        entry.error = error.clone();
+    }
+    PACKET_TRACES.save(storage, id, &trace)?;
+
+    Ok(())
+}
+
+/// Removes the oldest traces until at most `retention` traces remain
+fn prune_traces(
+    storage: &mut dyn Storage,
+    retention: u64,
+    next_sequence: u64,
+) -> Result<(), ContractError> {
+    let oldest: Vec<(u64, String)> = TRACE_SEQUENCES
+        .range(storage, None, None, Order::Ascending)
+        .take(MAX_PRUNE)
+        .collect::<Result<_, _>>()?;
+
+    for (sequence, id) in oldest {
+        // Sequences are only ever removed from the front so the remaining traces are contiguous
+        if next_sequence - sequence <= retention {
+            break;
+        }
+        if let Some(trace) = PACKET_TRACES.may_load(storage, &id)? {
+            ORIGIN_TRACES.remove(storage, (trace.origin.as_str(), sequence));
+        }
+        PACKET_TRACES.remove(storage, &id);
+        TRACE_SEQUENCES.remove(storage, sequence);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use andromeda_std::{
+    ado_base::permissioning::Permission, amp::AndrAddr, error::ContractError,
+    os::vfs::DirectoryAccess,
+};
+use cosmwasm_std::{ensure, Addr, Deps, DepsMut, Empty, Env};
+use cw_storage_plus::Map;
+
+use crate::state::resolve_pathname;
+
+/// Permissions granted to actors on a directory, keyed by directory address, access and actor
+pub const DIRECTORY_PERMISSIONS: Map<(&Addr, &str, &str), Permission> =
+    Map::new("directory_permissions");
+/// Directories whose paths can only be resolved by their owner and actors with resolve access
+/// This restricts routing through the directory only, its paths remain publicly readable from storage
+pub const RESTRICTED_DIRECTORIES: Map<&Addr, Empty> = Map::new("restricted_directories");
+
+/// Checks whether the actor has the given access to a directory.
+/// A directory is owned by its own address, which always has full access.
+pub fn has_access(
+    deps: Deps,
+    env: &Env,
+    directory: &Addr,
+    access: &DirectoryAccess,
+    actor: &Addr,
+) -> Result<bool, ContractError> {
+    if actor == directory {
+        return Ok(true);
+    }
+    let restricted = RESTRICTED_DIRECTORIES.has(deps.storage, directory);
+    // Write access must always be granted, resolve access only for restricted directories
+    let strict = *access == DirectoryAccess::Write || restricted;
+    let permission = DIRECTORY_PERMISSIONS
+        .may_load(deps.storage, (directory, access.as_str(), actor.as_str()))?;
+    match permission {
+        Some(mut permission) => Ok(permission
+            .get_permission(deps, actor.as_str())?
+            .is_permissioned(env, strict)),
+        None => Ok(!strict),
+    }
+}
+
+/// Ensures the actor can write to the directory, consuming a use of a local limited or quota permission
+pub fn ensure_write_access(
+    deps: DepsMut,
+    env: &Env,
+    directory: &Addr,
+    actor: &Addr,
+) -> Result<(), ContractError> {
+    ensure!(
+        has_access(
+            deps.as_ref(),
+            env,
+            directory,
+            &DirectoryAccess::Write,
+            actor
+        )?,
+        ContractError::Unauthorized {}
+    );
+    let key = (directory, DirectoryAccess::Write.as_str(), actor.as_str());
+    if let Some(Permission::Local(mut local_permission)) =
+        DIRECTORY_PERMISSIONS.may_load(deps.storage, key)?
+    {
+        if local_permission.is_use_limited() {
+            local_permission.consume_use(env)?;
+            DIRECTORY_PERMISSIONS.save(deps.storage, key, &Permission::Local(local_permission))?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether the actor can resolve the paths within a directory, restricted directories cannot be resolved without an actor
+fn can_resolve(
+    deps: Deps,
+    env: &Env,
+    directory: &Addr,
+    actor: Option<&Addr>,
+) -> Result<bool, ContractError> {
+    match actor {
+        Some(actor) => has_access(deps, env, directory, &DirectoryAccess::Resolve, actor),
+        None => Ok(!RESTRICTED_DIRECTORIES.has(deps.storage, directory)),
+    }
+}
+
+/// Ensures the actor can resolve every directory along the given path
+pub fn ensure_resolve_access(
+    deps: Deps,
+    env: &Env,
+    path: AndrAddr,
+    actor: Option<&Addr>,
+) -> Result<(), ContractError> {
+    let pathname = path.to_lowercase();
+    if !pathname.is_vfs_path() {
+        return Ok(());
+    }
+    let path_str = pathname.as_str();
+    let components: Vec<&str> = path_str.split('/').filter(|c| !c.is_empty()).collect();
+    // `/home` and `/lib` are not directories themselves, the first directory is the user or library
+    let (prefix, root_len) = if path_str.starts_with('/') {
+        ("/", 2)
+    } else {
+        ("", 1)
+    };
+    for end in root_len..components.len() {
+        let directory_path = format!("{prefix}{}", components[..end].join("/"));
+        let directory = resolve_pathname(
+            deps.storage,
+            deps.api,
+            &env.block,
+            AndrAddr::from_string(directory_path),
+            &mut vec![],
+        )?;
+        ensure!(
+            can_resolve(deps, env, &directory, actor)?,
+            ContractError::Unauthorized {}
+        );
+    }
+    Ok(())
+}
+
+/// Ensures the actor can list the paths within the directory at the given path, returning the directory's address
+pub fn ensure_list_access(
+    deps: Deps,
+    env: &Env,
+    path: AndrAddr,
+    actor: Option<&Addr>,
+) -> Result<Addr, ContractError> {
+    ensure_resolve_access(deps, env, path.clone(), actor)?;
+    let directory = resolve_pathname(deps.storage, deps.api, &env.block, path, &mut vec![])?;
+    ensure!(
+        can_resolve(deps, env, &directory, actor)?,
+        ContractError::Unauthorized {}
+    );
+    Ok(directory)
+}
@@ -71,6 +71,20 @@ pub fn execute(
         ExecuteMsg::RegisterUser { username, address } => {
             execute::register_user(execute_env, username, address)
         }
+        ExecuteMsg::RemovePath {
+            name,
+            parent_address,
+        } => execute::remove_path(execute_env, name, parent_address),
+        ExecuteMsg::RenamePath {
+            name,
+            new_name,
+            parent_address,
+        } => execute::rename_path(execute_env, name, new_name, parent_address),
+        ExecuteMsg::MovePath {
+            name,
+            parent_address,
+            new_parent_address,
+        } => execute::move_path(execute_env, name, parent_address, new_parent_address),
         ExecuteMsg::AddChild {
             name,
             parent_address,
@@ -82,6 +96,37 @@ pub fn execute(
         ExecuteMsg::RegisterUserCrossChain { chain, address } => {
             execute::register_user_cross_chain(execute_env, chain, address)
         }
+        ExecuteMsg::TransferUsername { recipient } => {
+            execute::transfer_username(execute_env, recipient)
+        }
+        ExecuteMsg::ReleaseUsername {} => execute::release_username(execute_env),
+        ExecuteMsg::RenewUsername {} => execute::renew_username(execute_env),
+        ExecuteMsg::SetUsernameConfig { config } => {
+            execute::set_username_config(execute_env, config)
+        }
+        ExecuteMsg::SetDirectoryPermission {
+            directory,
+            actors,
+            access,
+            permission,
+        } => execute::set_directory_permission(execute_env, directory, actors, access, permission),
+        ExecuteMsg::RemoveDirectoryPermission {
+            directory,
+            actors,
+            access,
+        } => execute::remove_directory_permission(execute_env, directory, actors, access),
+        ExecuteMsg::SetDirectoryRestricted {
+            directory,
+            restricted,
+        } => execute::set_directory_restricted(execute_env, directory, restricted),
+        ExecuteMsg::SetPrimaryPath { path } => execute::set_primary_path(execute_env, path),
+        ExecuteMsg::RemovePrimaryPath {} => execute::remove_primary_path(execute_env),
+        ExecuteMsg::RefreshRemotePath { path } => execute::refresh_remote_path(execute_env, path),
+        ExecuteMsg::CacheRemotePath {
+            chain,
+            path,
+            address,
+        } => execute::cache_remote_path(execute_env, chain, path, address),
         // Base message
         ExecuteMsg::Ownership(ownership_message) => ADOContract::default().execute_ownership(
             execute_env.deps,
@@ -98,19 +143,46 @@ pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, Co
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
-        QueryMsg::ResolvePath { path } => encode_binary(&query::resolve_path(deps, path)?),
+        QueryMsg::ResolvePath { path } => encode_binary(&query::resolve_path(deps, env, path)?),
         QueryMsg::SubDir {
             path,
             min,
             max,
             limit,
-        } => encode_binary(&query::subdir(deps, path, min, max, limit)?),
+        } => encode_binary(&query::subdir(deps, env, path, min, max, limit)?),
         QueryMsg::Paths { addr } => encode_binary(&query::paths(deps, addr)?),
-        QueryMsg::GetUsername { address } => encode_binary(&query::get_username(deps, address)?),
+        QueryMsg::ReverseResolve { addr } => {
+            encode_binary(&query::reverse_resolve(deps, env, addr)?)
+        }
+        QueryMsg::Tree {
+            path,
+            depth,
+            start_after,
+            limit,
+        } => encode_binary(&query::tree(deps, env, path, depth, start_after, limit)?),
+        QueryMsg::GetUsername { address } => {
+            encode_binary(&query::get_username(deps, env, address)?)
+        }
+        QueryMsg::UsernameInfo { username } => {
+            encode_binary(&query::username_info(deps, env, username)?)
+        }
+        QueryMsg::UsernameConfig {} => encode_binary(&query::username_config(deps)?),
         QueryMsg::GetLibrary { address } => encode_binary(&query::get_library_name(deps, address)?),
-        QueryMsg::ResolveSymlink { path } => encode_binary(&query::get_symlink(deps, path)?),
+        QueryMsg::ResolveSymlink { path } => encode_binary(&query::get_symlink(deps, env, path)?),
+        QueryMsg::ResolvePathCrossChain { path, max_age } => {
+            encode_binary(&query::resolve_path_cross_chain(deps, env, path, max_age)?)
+        }
+        QueryMsg::ResolvePathFor { path, actor } => {
+            encode_binary(&query::resolve_path_for(deps, env, path, actor)?)
+        }
+        QueryMsg::DirectoryPermissions { directory } => {
+            encode_binary(&query::directory_permissions(deps, env, directory)?)
+        }
+        QueryMsg::EffectivePermissions { directory, actor } => {
+            encode_binary(&query::effective_permissions(deps, env, directory, actor)?)
+        }
         // Base queries
         QueryMsg::Version {} => encode_binary(&ADOContract::default().query_version(deps)?),
         QueryMsg::Type {} => encode_binary(&ADOContract::default().query_type(deps)?),
@@ -1,19 +1,28 @@
-use andromeda_std::ado_contract::ADOContract;
+use andromeda_std::ado_base::permissioning::Permission;
+use andromeda_std::ado_contract::{permissioning::verify_local_permission, ADOContract};
 use andromeda_std::amp::AndrAddr;
+use andromeda_std::common::Milliseconds;
 use andromeda_std::error::ContractError;
 use andromeda_std::os::aos_querier::AOSQuerier;
 use andromeda_std::os::kernel::InternalMsg;
 use andromeda_std::os::{
     kernel::ExecuteMsg as KernelExecuteMsg,
-    vfs::{validate_component_name, validate_username},
+    vfs::{
+        validate_component_name, validate_username, DirectoryAccess, PathInfo, RemotePathInfo,
+        UsernameConfig, UsernameStatus,
+    },
 };
 use cosmwasm_std::{
-    attr, ensure, to_json_binary, Addr, DepsMut, Env, MessageInfo, Response, SubMsg, WasmMsg,
+    attr, ensure, to_json_binary, Addr, DepsMut, Empty, Env, MessageInfo, Order, Response,
+    StdResult, SubMsg, WasmMsg,
 };
 
+use crate::acl::{ensure_write_access, DIRECTORY_PERMISSIONS, RESTRICTED_DIRECTORIES};
 use crate::state::{
-    add_path_symlink, add_pathname, paths, resolve_pathname, ADDRESS_LIBRARY, ADDRESS_USERNAME,
-    LIBRARIES, USERS,
+    add_path_symlink, add_pathname, get_remote_target, is_username_expired, move_pathname, paths,
+    remove_pathname, remove_username, rename_pathname, resolve_pathname, username_status,
+    ADDRESS_LIBRARY, ADDRESS_USERNAME, LIBRARIES, PRIMARY_PATHS, REMOTE_PATHS, USERNAME_CONFIG,
+    USERNAME_EXPIRATIONS, USERS,
 };
 
 pub struct ExecuteEnv<'a> {
@@ -23,27 +32,13 @@ pub struct ExecuteEnv<'a> {
 }
 
 pub fn add_path(
-    env: ExecuteEnv,
+    mut env: ExecuteEnv,
     name: String,
     address: Addr,
     parent_address: Option<AndrAddr>,
 ) -> Result<Response, ContractError> {
     let name = name.to_lowercase();
-    let kernel_address = ADOContract::default().get_kernel_address(env.deps.storage)?;
-    ensure!(
-        parent_address.is_none()
-            || env.info.sender == kernel_address
-            || ADOContract::default()
-                .is_contract_owner(env.deps.storage, env.info.sender.as_str())?,
-        ContractError::Unauthorized {}
-    );
-    let parent_andr_addr = parent_address.unwrap_or(AndrAddr::from_string(env.info.sender));
-    let parent_addr = resolve_pathname(
-        env.deps.storage,
-        env.deps.api,
-        parent_andr_addr,
-        &mut vec![],
-    )?;
+    let parent_addr = resolve_parent(&mut env, parent_address)?;
     validate_component_name(name.clone())?;
     add_pathname(
         env.deps.storage,
@@ -60,41 +55,142 @@ pub fn add_path(
 }
 
 pub fn add_symlink(
-    env: ExecuteEnv,
+    mut env: ExecuteEnv,
     name: String,
     symlink: AndrAddr,
     parent_address: Option<AndrAddr>,
 ) -> Result<Response, ContractError> {
     let name = name.to_lowercase();
     let symlink = symlink.to_lowercase();
-    let kernel_address = ADOContract::default().get_kernel_address(env.deps.storage)?;
-    ensure!(
-        parent_address.is_none()
-            || env.info.sender == kernel_address
-            || ADOContract::default()
-                .is_contract_owner(env.deps.storage, env.info.sender.as_str())?,
-        ContractError::Unauthorized {}
-    );
-    let parent_andr_addr = parent_address.unwrap_or(AndrAddr::from_string(env.info.sender));
+    let parent_addr = resolve_parent(&mut env, parent_address)?;
+    validate_component_name(name.clone())?;
+    add_path_symlink(
+        env.deps.storage,
+        env.deps.api,
+        &env.env.block,
+        parent_addr.clone(),
+        name.clone(),
+        symlink.clone(),
+    )?;
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "add_symlink"),
+        attr("symlink", symlink),
+        attr("name", name),
+        attr("parent", parent_addr),
+    ]))
+}
+
+/// Resolves the parent of a path being modified, defaulting to the sender.
+/// The sender must have write access to the parent unless they are the Kernel or VFS owner.
+fn resolve_parent(
+    env: &mut ExecuteEnv,
+    parent_address: Option<AndrAddr>,
+) -> Result<Addr, ContractError> {
+    let parent_andr_addr = parent_address.unwrap_or(AndrAddr::from_string(env.info.sender.clone()));
     let parent_addr = resolve_pathname(
         env.deps.storage,
         env.deps.api,
+        &env.env.block,
         parent_andr_addr,
         &mut vec![],
     )?;
-    validate_component_name(name.clone())?;
-    add_path_symlink(
+    ensure_directory_write(env, &parent_addr)?;
+    Ok(parent_addr)
+}
+
+/// Whether the sender is the Kernel or VFS owner, who can write to any directory
+fn is_privileged_sender(env: &ExecuteEnv) -> Result<bool, ContractError> {
+    let kernel_address = ADOContract::default().get_kernel_address(env.deps.storage)?;
+    Ok(env.info.sender == kernel_address
+        || ADOContract::default().is_contract_owner(env.deps.storage, env.info.sender.as_str())?)
+}
+
+/// Ensures the sender can write to the directory, the Kernel and VFS owner can write to any directory
+fn ensure_directory_write(env: &mut ExecuteEnv, directory: &Addr) -> Result<(), ContractError> {
+    if is_privileged_sender(env)? {
+        return Ok(());
+    }
+    ensure_write_access(env.deps.branch(), &env.env, directory, &env.info.sender)
+}
+
+pub fn remove_path(
+    mut env: ExecuteEnv,
+    name: String,
+    parent_address: Option<AndrAddr>,
+) -> Result<Response, ContractError> {
+    let name = name.to_lowercase();
+    let parent_addr = resolve_parent(&mut env, parent_address)?;
+    // Only the paths within directories the sender can write to are removed along with the path
+    let actor = match is_privileged_sender(&env)? {
+        true => None,
+        false => Some(env.info.sender.clone()),
+    };
+    let removed = remove_pathname(
+        env.deps.branch(),
+        &env.env,
+        parent_addr.clone(),
+        name.clone(),
+        actor.as_ref(),
+    )?;
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "remove_path"),
+        attr("name", name),
+        attr("parent", parent_addr),
+        attr("removed_paths", removed.to_string()),
+    ]))
+}
+
+pub fn rename_path(
+    mut env: ExecuteEnv,
+    name: String,
+    new_name: String,
+    parent_address: Option<AndrAddr>,
+) -> Result<Response, ContractError> {
+    let name = name.to_lowercase();
+    let new_name = new_name.to_lowercase();
+    let parent_addr = resolve_parent(&mut env, parent_address)?;
+    validate_component_name(new_name.clone())?;
+    rename_pathname(
+        env.deps.storage,
+        parent_addr.clone(),
+        name.clone(),
+        new_name.clone(),
+    )?;
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "rename_path"),
+        attr("name", name),
+        attr("new_name", new_name),
+        attr("parent", parent_addr),
+    ]))
+}
+
+pub fn move_path(
+    mut env: ExecuteEnv,
+    name: String,
+    parent_address: Option<AndrAddr>,
+    new_parent_address: AndrAddr,
+) -> Result<Response, ContractError> {
+    let name = name.to_lowercase();
+    let parent_addr = resolve_parent(&mut env, parent_address)?;
+    let new_parent_addr = resolve_pathname(
         env.deps.storage,
         env.deps.api,
+        &env.env.block,
+        new_parent_address,
+        &mut vec![],
+    )?;
+    ensure_directory_write(&mut env, &new_parent_addr)?;
+    move_pathname(
+        env.deps.storage,
         parent_addr.clone(),
         name.clone(),
-        symlink.clone(),
+        new_parent_addr.clone(),
     )?;
     Ok(Response::default().add_attributes(vec![
-        attr("action", "add_symlink"),
-        attr("symlink", symlink),
+        attr("action", "move_path"),
         attr("name", name),
         attr("parent", parent_addr),
+        attr("new_parent", new_parent_addr),
     ]))
 }
 
@@ -103,7 +199,7 @@ pub fn add_child(
     name: String,
     parent_address: AndrAddr,
 ) -> Result<Response, ContractError> {
-    let ExecuteEnv { deps, info, .. } = env;
+    let ExecuteEnv { deps, info, env } = env;
     let name = name.to_lowercase();
 
     let sender_code_id_res = deps.querier.query_wasm_contract_info(info.sender.clone());
@@ -122,7 +218,13 @@ pub fn add_child(
     );
 
     validate_component_name(name.clone())?;
-    let parent_address = resolve_pathname(deps.storage, deps.api, parent_address, &mut vec![])?;
+    let parent_address = resolve_pathname(
+        deps.storage,
+        deps.api,
+        &env.block,
+        parent_address,
+        &mut vec![],
+    )?;
     let existing = paths()
         .load(deps.storage, (parent_address.clone(), name.clone()))
         .ok();
@@ -186,18 +288,20 @@ pub fn register_user(
         ContractError::Unauthorized {}
     );
     let sender = address.unwrap_or(env.info.sender.clone());
-    let current_user_address = USERS.may_load(env.deps.storage, username.as_str())?;
+    let status = username_status(env.deps.storage, &env.env.block, username.as_str())?;
     ensure!(
-        current_user_address.is_none(),
+        status == UsernameStatus::Available,
         ContractError::InvalidUsername {
             error: Some("Username already taken".to_string())
         }
     );
+    // Usernames past their grace period are claimable by others
+    remove_username(env.deps.storage, username.as_str())?;
 
     //Remove username registration from previous username
     let current_username = ADDRESS_USERNAME.may_load(env.deps.storage, sender.as_ref())?;
     if let Some(current_username) = current_username {
-        USERS.remove(env.deps.storage, current_username.as_str());
+        remove_username(env.deps.storage, current_username.as_str())?;
     }
 
     // If the username is a valid address, it should be equal to info.sender
@@ -223,10 +327,347 @@ pub fn register_user(
     //Update current address' username
     ADDRESS_USERNAME.save(env.deps.storage, sender.as_ref(), &username)?;
 
-    Ok(Response::default().add_attributes(vec![
+    let mut res = Response::default().add_attributes(vec![
         attr("action", "register_username"),
         attr("addr", sender),
+        attr("username", username.clone()),
+    ]);
+    let config = USERNAME_CONFIG
+        .may_load(env.deps.storage)?
+        .unwrap_or_default();
+    if let Some(registration_period) = config.registration_period {
+        let expiration = Milliseconds::from_nanos(env.env.block.time.nanos())
+            .plus_milliseconds(registration_period);
+        USERNAME_EXPIRATIONS.save(env.deps.storage, username.as_str(), &expiration)?;
+        res = res.add_attribute("expiration", expiration.to_string());
+    }
+    Ok(res)
+}
+
+/// Loads the sender's username, ensuring it has not expired
+fn load_active_username(env: &ExecuteEnv) -> Result<String, ContractError> {
+    let username = ADDRESS_USERNAME
+        .may_load(env.deps.storage, env.info.sender.as_str())?
+        .ok_or(ContractError::InvalidUsername {
+            error: Some("Sender does not have a username".to_string()),
+        })?;
+    ensure!(
+        !is_username_expired(env.deps.storage, &env.env.block, username.as_str())?,
+        ContractError::InvalidUsername {
+            error: Some("Username has expired".to_string())
+        }
+    );
+    Ok(username)
+}
+
+pub fn transfer_username(env: ExecuteEnv, recipient: String) -> Result<Response, ContractError> {
+    let username = load_active_username(&env)?;
+    let ExecuteEnv { deps, info, .. } = env;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    ensure!(
+        !ADDRESS_USERNAME.has(deps.storage, recipient.as_str()),
+        ContractError::InvalidUsername {
+            error: Some("Recipient already has a username".to_string())
+        }
+    );
+
+    // Paths of the home directory are keyed by the owner's address and are moved to the recipient
+    let home_paths = paths()
+        .idx
+        .parent
+        .prefix(info.sender.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<((Addr, String), PathInfo)>>>()?;
+    for ((_, name), path) in home_paths {
+        ensure!(
+            !paths().has(deps.storage, (recipient.clone(), name.clone())),
+            ContractError::InvalidPathname {
+                error: Some(format!("Recipient already has a path named {name}"))
+            }
+        );
+        paths().remove(deps.storage, (info.sender.clone(), name.clone()))?;
+        paths().save(
+            deps.storage,
+            (recipient.clone(), name),
+            &PathInfo {
+                parent_address: recipient.clone(),
+                ..path
+            },
+        )?;
+    }
+
+    // The access settings of the home directory are keyed by its address as well
+    let permissions = DIRECTORY_PERMISSIONS
+        .sub_prefix(&info.sender)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<((String, String), Permission)>>>()?;
+    for ((access, actor), permission) in permissions {
+        DIRECTORY_PERMISSIONS.remove(deps.storage, (&info.sender, &access, &actor));
+        DIRECTORY_PERMISSIONS.save(deps.storage, (&recipient, &access, &actor), &permission)?;
+    }
+    if RESTRICTED_DIRECTORIES.has(deps.storage, &info.sender) {
+        RESTRICTED_DIRECTORIES.remove(deps.storage, &info.sender);
+        RESTRICTED_DIRECTORIES.save(deps.storage, &recipient, &Empty {})?;
+    }
+    if let Some(primary_path) = PRIMARY_PATHS.may_load(deps.storage, &info.sender)? {
+        PRIMARY_PATHS.remove(deps.storage, &info.sender);
+        PRIMARY_PATHS.save(deps.storage, &recipient, &primary_path)?;
+    }
+
+    USERS.save(deps.storage, username.as_str(), &recipient)?;
+    ADDRESS_USERNAME.remove(deps.storage, info.sender.as_str());
+    ADDRESS_USERNAME.save(deps.storage, recipient.as_str(), &username)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "transfer_username"),
         attr("username", username),
+        attr("from", info.sender),
+        attr("to", recipient),
+    ]))
+}
+
+pub fn release_username(env: ExecuteEnv) -> Result<Response, ContractError> {
+    let username = ADDRESS_USERNAME
+        .may_load(env.deps.storage, env.info.sender.as_str())?
+        .ok_or(ContractError::InvalidUsername {
+            error: Some("Sender does not have a username".to_string()),
+        })?;
+    remove_username(env.deps.storage, username.as_str())?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "release_username"),
+        attr("addr", env.info.sender),
+        attr("username", username),
+    ]))
+}
+
+pub fn renew_username(env: ExecuteEnv) -> Result<Response, ContractError> {
+    let username = ADDRESS_USERNAME
+        .may_load(env.deps.storage, env.info.sender.as_str())?
+        .ok_or(ContractError::InvalidUsername {
+            error: Some("Sender does not have a username".to_string()),
+        })?;
+    ensure!(
+        username_status(env.deps.storage, &env.env.block, username.as_str())?
+            != UsernameStatus::Available,
+        ContractError::InvalidUsername {
+            error: Some("Username grace period has ended".to_string())
+        }
+    );
+    let registration_period = USERNAME_CONFIG
+        .may_load(env.deps.storage)?
+        .unwrap_or_default()
+        .registration_period;
+    let (Some(registration_period), Some(expiration)) = (
+        registration_period,
+        USERNAME_EXPIRATIONS.may_load(env.deps.storage, username.as_str())?,
+    ) else {
+        return Err(ContractError::InvalidUsername {
+            error: Some("Username does not expire".to_string()),
+        });
+    };
+
+    // Renewals during the grace period start from the current time
+    let now = Milliseconds::from_nanos(env.env.block.time.nanos());
+    let expiration = if expiration.is_expired(&env.env.block) {
+        now
+    } else {
+        expiration
+    }
+    .plus_milliseconds(registration_period);
+    USERNAME_EXPIRATIONS.save(env.deps.storage, username.as_str(), &expiration)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "renew_username"),
+        attr("username", username),
+        attr("expiration", expiration.to_string()),
+    ]))
+}
+
+pub fn set_username_config(
+    env: ExecuteEnv,
+    config: UsernameConfig,
+) -> Result<Response, ContractError> {
+    ensure!(
+        ADOContract::default().is_contract_owner(env.deps.storage, env.info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+    ensure!(
+        config
+            .registration_period
+            .iter()
+            .all(|period| !period.is_zero()),
+        ContractError::InvalidZeroAmount {}
+    );
+    USERNAME_CONFIG.save(env.deps.storage, &config)?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_username_config"),
+        attr(
+            "registration_period",
+            config
+                .registration_period
+                .map_or("none".to_string(), |period| period.to_string()),
+        ),
+        attr("grace_period", config.grace_period.to_string()),
+    ]))
+}
+
+/// Resolves a directory whose permissions the sender may manage, only the directory itself, Kernel and VFS owner may do so
+fn resolve_managed_directory(env: &ExecuteEnv, directory: AndrAddr) -> Result<Addr, ContractError> {
+    let directory = resolve_pathname(
+        env.deps.storage,
+        env.deps.api,
+        &env.env.block,
+        directory,
+        &mut vec![],
+    )?;
+    let kernel_address = ADOContract::default().get_kernel_address(env.deps.storage)?;
+    ensure!(
+        env.info.sender == directory
+            || env.info.sender == kernel_address
+            || ADOContract::default()
+                .is_contract_owner(env.deps.storage, env.info.sender.as_str())?,
+        ContractError::Unauthorized {}
+    );
+    Ok(directory)
+}
+
+fn resolve_actors(env: &ExecuteEnv, actors: Vec<AndrAddr>) -> Result<Vec<Addr>, ContractError> {
+    ensure!(!actors.is_empty(), ContractError::NoActorsProvided {});
+    actors
+        .into_iter()
+        .map(|actor| {
+            resolve_pathname(
+                env.deps.storage,
+                env.deps.api,
+                &env.env.block,
+                actor,
+                &mut vec![],
+            )
+        })
+        .collect()
+}
+
+pub fn set_directory_permission(
+    env: ExecuteEnv,
+    directory: AndrAddr,
+    actors: Vec<AndrAddr>,
+    access: DirectoryAccess,
+    permission: Permission,
+) -> Result<Response, ContractError> {
+    let directory = resolve_managed_directory(&env, directory)?;
+    let actors = resolve_actors(&env, actors)?;
+    let permission = match permission {
+        Permission::Local(local_permission) => {
+            Permission::Local(verify_local_permission(&env.env, local_permission)?)
+        }
+        Permission::Contract(andr_addr) => Permission::Contract(andr_addr),
+    };
+    for actor in actors.iter() {
+        DIRECTORY_PERMISSIONS.save(
+            env.deps.storage,
+            (&directory, access.as_str(), actor.as_str()),
+            &permission,
+        )?;
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_directory_permission"),
+        attr("directory", directory.as_str()),
+        attr("access", access.as_str()),
+        attr(
+            "actors",
+            actors
+                .iter()
+                .map(|actor| actor.as_str())
+                .collect::<Vec<&str>>()
+                .join(","),
+        ),
+        attr("permission", permission.to_string()),
+    ]))
+}
+
+pub fn remove_directory_permission(
+    env: ExecuteEnv,
+    directory: AndrAddr,
+    actors: Vec<AndrAddr>,
+    access: DirectoryAccess,
+) -> Result<Response, ContractError> {
+    let directory = resolve_managed_directory(&env, directory)?;
+    let actors = resolve_actors(&env, actors)?;
+    for actor in actors.iter() {
+        DIRECTORY_PERMISSIONS.remove(
+            env.deps.storage,
+            (&directory, access.as_str(), actor.as_str()),
+        );
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "remove_directory_permission"),
+        attr("directory", directory.as_str()),
+        attr("access", access.as_str()),
+        attr(
+            "actors",
+            actors
+                .iter()
+                .map(|actor| actor.as_str())
+                .collect::<Vec<&str>>()
+                .join(","),
+        ),
+    ]))
+}
+
+pub fn set_directory_restricted(
+    env: ExecuteEnv,
+    directory: AndrAddr,
+    restricted: bool,
+) -> Result<Response, ContractError> {
+    let directory = resolve_managed_directory(&env, directory)?;
+    if restricted {
+        RESTRICTED_DIRECTORIES.save(env.deps.storage, &directory, &Empty {})?;
+    } else {
+        RESTRICTED_DIRECTORIES.remove(env.deps.storage, &directory);
+    }
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_directory_restricted"),
+        attr("directory", directory),
+        attr("restricted", restricted.to_string()),
+    ]))
+}
+
+pub fn set_primary_path(env: ExecuteEnv, path: AndrAddr) -> Result<Response, ContractError> {
+    let path = path.to_lowercase();
+    ensure!(
+        path.is_vfs_path(),
+        ContractError::InvalidPathname {
+            error: Some("Primary path must be a VFS path".to_string())
+        }
+    );
+    let address = resolve_pathname(
+        env.deps.storage,
+        env.deps.api,
+        &env.env.block,
+        path.clone(),
+        &mut vec![],
+    )?;
+    ensure!(address == env.info.sender, ContractError::Unauthorized {});
+    PRIMARY_PATHS.save(env.deps.storage, &address, &path.to_string())?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "set_primary_path"),
+        attr("addr", address),
+        attr("path", path.to_string()),
+    ]))
+}
+
+pub fn remove_primary_path(env: ExecuteEnv) -> Result<Response, ContractError> {
+    PRIMARY_PATHS.remove(env.deps.storage, &env.info.sender);
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "remove_primary_path"),
+        attr("addr", env.info.sender),
     ]))
 }
 
@@ -285,3 +726,55 @@ pub fn register_user_cross_chain(
         ])
         .add_submessage(sub_msg))
 }
+
+pub fn refresh_remote_path(env: ExecuteEnv, path: AndrAddr) -> Result<Response, ContractError> {
+    let (chain, remote_path) =
+        get_remote_target(env.deps.storage, env.deps.api, &env.env.block, path)?;
+    let kernel = ADOContract::default().get_kernel_address(env.deps.storage)?;
+    let msg = KernelExecuteMsg::Internal(InternalMsg::ResolvePathCrossChain {
+        path: remote_path.clone(),
+        chain: chain.clone(),
+    });
+    let sub_msg = SubMsg::reply_on_error(
+        WasmMsg::Execute {
+            contract_addr: kernel.to_string(),
+            msg: to_json_binary(&msg)?,
+            funds: vec![],
+        },
+        1,
+    );
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            attr("action", "refresh_remote_path"),
+            attr("path", remote_path),
+            attr("chain", chain),
+        ])
+        .add_submessage(sub_msg))
+}
+
+pub fn cache_remote_path(
+    env: ExecuteEnv,
+    chain: String,
+    path: String,
+    address: String,
+) -> Result<Response, ContractError> {
+    let kernel = ADOContract::default().get_kernel_address(env.deps.storage)?;
+    ensure!(env.info.sender == kernel, ContractError::Unauthorized {});
+    let cached_at = Milliseconds::from_nanos(env.env.block.time.nanos());
+    REMOTE_PATHS.save(
+        env.deps.storage,
+        (chain.as_str(), path.as_str()),
+        &RemotePathInfo {
+            address: address.clone(),
+            cached_at,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "cache_remote_path"),
+        attr("chain", chain),
+        attr("path", path),
+        attr("address", address),
+    ]))
+}
@@ -2,6 +2,7 @@ pub mod contract;
 #[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
 pub mod mock;
 
+mod acl;
 mod state;
 
 #[cfg(test)]
@@ -1,24 +1,39 @@
-use andromeda_std::os::vfs::{validate_path_name, SubDirBound, SubDirResponse};
-use andromeda_std::{amp::AndrAddr, error::ContractError};
-use cosmwasm_std::{Addr, Deps};
+use andromeda_std::ado_base::permissioning::{PermissionInfo, PermissionsResponse};
+use andromeda_std::os::vfs::{
+    validate_path_name, CrossChainPathResponse, DirectoryAccess, EffectivePermissionsResponse,
+    SubDirBound, SubDirResponse, TreeResponse, UsernameConfig, UsernameInfoResponse,
+    UsernameStatus,
+};
+use andromeda_std::{amp::AndrAddr, common::Milliseconds, error::ContractError};
+use cosmwasm_std::{Addr, Deps, Env, Order};
 
+use crate::acl::{
+    ensure_list_access, ensure_resolve_access, has_access, DIRECTORY_PERMISSIONS,
+    RESTRICTED_DIRECTORIES,
+};
 use crate::state::{
-    get_paths, get_subdir, resolve_pathname, resolve_symlink, ADDRESS_LIBRARY, ADDRESS_USERNAME,
+    get_paths, get_remote_target, get_subdir, get_tree, is_username_expired, resolve_pathname,
+    resolve_symlink, username_status, ADDRESS_LIBRARY, ADDRESS_USERNAME, PRIMARY_PATHS,
+    REMOTE_PATHS, USERNAME_CONFIG, USERNAME_EXPIRATIONS, USERS,
 };
 
-pub fn resolve_path(deps: Deps, path: AndrAddr) -> Result<Addr, ContractError> {
+/// Resolves a path, paths through restricted directories can only be resolved with `ResolvePathFor`
+pub fn resolve_path(deps: Deps, env: Env, path: AndrAddr) -> Result<Addr, ContractError> {
     validate_path_name(deps.api, path.to_string())?;
-    resolve_pathname(deps.storage, deps.api, path, &mut vec![])
+    ensure_resolve_access(deps, &env, path.clone(), None)?;
+    resolve_pathname(deps.storage, deps.api, &env.block, path, &mut vec![])
 }
 pub fn subdir(
     deps: Deps,
+    env: Env,
     path: AndrAddr,
     min: Option<SubDirBound>,
     max: Option<SubDirBound>,
     limit: Option<u32>,
 ) -> Result<SubDirResponse, ContractError> {
     validate_path_name(deps.api, path.to_string())?;
-    let subdirs = get_subdir(deps.storage, deps.api, path, min, max, limit)?;
+    ensure_list_access(deps, &env, path.clone(), None)?;
+    let subdirs = get_subdir(deps.storage, deps.api, &env.block, path, min, max, limit)?;
     Ok(SubDirResponse { subdirs })
 }
 
@@ -26,15 +41,87 @@ pub fn paths(deps: Deps, addr: Addr) -> Result<Vec<String>, ContractError> {
     get_paths(deps.storage, addr)
 }
 
-pub fn get_symlink(deps: Deps, addr: AndrAddr) -> Result<AndrAddr, ContractError> {
-    resolve_symlink(deps.storage, deps.api, addr)
+pub fn reverse_resolve(deps: Deps, env: Env, addr: Addr) -> Result<Option<String>, ContractError> {
+    let Some(path) = PRIMARY_PATHS.may_load(deps.storage, &addr)? else {
+        return Ok(None);
+    };
+    // The primary path may have since been removed or now point elsewhere
+    let resolved = resolve_pathname(
+        deps.storage,
+        deps.api,
+        &env.block,
+        AndrAddr::from_string(path.clone()),
+        &mut vec![],
+    );
+    let accessible =
+        ensure_resolve_access(deps, &env, AndrAddr::from_string(path.clone()), None).is_ok();
+    Ok(match resolved {
+        Ok(resolved) if resolved == addr && accessible => Some(path),
+        _ => None,
+    })
 }
 
-pub fn get_username(deps: Deps, addr: Addr) -> Result<String, ContractError> {
-    let username = ADDRESS_USERNAME
-        .may_load(deps.storage, addr.to_string().as_str())?
-        .unwrap_or(addr.to_string());
-    Ok(username)
+pub fn tree(
+    deps: Deps,
+    env: Env,
+    path: AndrAddr,
+    depth: Option<u32>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<TreeResponse, ContractError> {
+    validate_path_name(deps.api, path.to_string())?;
+    ensure_list_access(deps, &env, path.clone(), None)?;
+    let nodes = get_tree(
+        deps.storage,
+        deps.api,
+        &env.block,
+        path,
+        depth,
+        start_after,
+        limit,
+    )?;
+    Ok(TreeResponse { nodes })
+}
+
+pub fn get_symlink(deps: Deps, env: Env, addr: AndrAddr) -> Result<AndrAddr, ContractError> {
+    resolve_symlink(deps.storage, deps.api, &env.block, addr)
+}
+
+pub fn get_username(deps: Deps, env: Env, addr: Addr) -> Result<String, ContractError> {
+    let username = ADDRESS_USERNAME.may_load(deps.storage, addr.to_string().as_str())?;
+    // Expired usernames are not resolved
+    match username {
+        Some(username) if !is_username_expired(deps.storage, &env.block, username.as_str())? => {
+            Ok(username)
+        }
+        _ => Ok(addr.to_string()),
+    }
+}
+
+pub fn username_info(
+    deps: Deps,
+    env: Env,
+    username: String,
+) -> Result<UsernameInfoResponse, ContractError> {
+    let username = username.to_lowercase();
+    let status = username_status(deps.storage, &env.block, username.as_str())?;
+    let (owner, expiration) = match status {
+        UsernameStatus::Available => (None, None),
+        _ => (
+            USERS.may_load(deps.storage, username.as_str())?,
+            USERNAME_EXPIRATIONS.may_load(deps.storage, username.as_str())?,
+        ),
+    };
+    Ok(UsernameInfoResponse {
+        username,
+        owner,
+        expiration,
+        status,
+    })
+}
+
+pub fn username_config(deps: Deps) -> Result<UsernameConfig, ContractError> {
+    Ok(USERNAME_CONFIG.may_load(deps.storage)?.unwrap_or_default())
 }
 
 pub fn get_library_name(deps: Deps, addr: Addr) -> Result<String, ContractError> {
@@ -43,3 +130,79 @@ pub fn get_library_name(deps: Deps, addr: Addr) -> Result<String, ContractError>
         .unwrap_or(addr.to_string());
     Ok(lib_name)
 }
+
+pub fn resolve_path_for(
+    deps: Deps,
+    env: Env,
+    path: AndrAddr,
+    actor: AndrAddr,
+) -> Result<Addr, ContractError> {
+    validate_path_name(deps.api, path.to_string())?;
+    let actor = resolve_pathname(deps.storage, deps.api, &env.block, actor, &mut vec![])?;
+    ensure_resolve_access(deps, &env, path.clone(), Some(&actor))?;
+    resolve_pathname(deps.storage, deps.api, &env.block, path, &mut vec![])
+}
+
+pub fn directory_permissions(
+    deps: Deps,
+    env: Env,
+    directory: AndrAddr,
+) -> Result<PermissionsResponse, ContractError> {
+    let directory = resolve_pathname(deps.storage, deps.api, &env.block, directory, &mut vec![])?;
+    let permissions = DIRECTORY_PERMISSIONS
+        .sub_prefix(&directory)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|((action, actor), permission)| PermissionInfo {
+                permission,
+                action,
+                actor,
+            })
+        })
+        .collect::<Result<Vec<PermissionInfo>, _>>()?;
+    Ok(PermissionsResponse { permissions })
+}
+
+pub fn effective_permissions(
+    deps: Deps,
+    env: Env,
+    directory: AndrAddr,
+    actor: AndrAddr,
+) -> Result<EffectivePermissionsResponse, ContractError> {
+    let directory = resolve_pathname(deps.storage, deps.api, &env.block, directory, &mut vec![])?;
+    let actor = resolve_pathname(deps.storage, deps.api, &env.block, actor, &mut vec![])?;
+    Ok(EffectivePermissionsResponse {
+        is_owner: actor == directory,
+        restricted: RESTRICTED_DIRECTORIES.has(deps.storage, &directory),
+        can_write: has_access(deps, &env, &directory, &DirectoryAccess::Write, &actor)?,
+        can_resolve: has_access(deps, &env, &directory, &DirectoryAccess::Resolve, &actor)?,
+        directory,
+    })
+}
+
+/// Remote addresses cached longer than a day ago are considered stale by default
+const DEFAULT_REMOTE_PATH_MAX_AGE: Milliseconds = Milliseconds(86_400_000);
+
+pub fn resolve_path_cross_chain(
+    deps: Deps,
+    env: Env,
+    path: AndrAddr,
+    max_age: Option<Milliseconds>,
+) -> Result<CrossChainPathResponse, ContractError> {
+    let (chain, path) = get_remote_target(deps.storage, deps.api, &env.block, path)?;
+    let info = REMOTE_PATHS
+        .may_load(deps.storage, (chain.as_str(), path.as_str()))?
+        .ok_or(ContractError::InvalidPathname {
+            error: Some(format!("{path} on {chain} has not been resolved")),
+        })?;
+    let now = Milliseconds::from_nanos(env.block.time.nanos());
+    let age = now.minus_milliseconds(info.cached_at);
+    Ok(CrossChainPathResponse {
+        stale: age > max_age.unwrap_or(DEFAULT_REMOTE_PATH_MAX_AGE),
+        chain,
+        path,
+        address: info.address,
+        cached_at: info.cached_at,
+        age,
+    })
+}
@@ -1,10 +1,15 @@
+use crate::acl::{has_access, RESTRICTED_DIRECTORIES};
 use andromeda_std::{
     amp::AndrAddr,
+    common::Milliseconds,
     error::ContractError,
-    os::vfs::{validate_path_name, PathInfo, SubDirBound},
+    os::vfs::{
+        validate_path_name, DirectoryAccess, PathInfo, RemotePathInfo, SubDirBound, TreeNode,
+        UsernameConfig, UsernameStatus,
+    },
 };
-use cosmwasm_std::{ensure, Addr, Api, Storage};
-use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Map, MultiIndex};
+use cosmwasm_std::{ensure, Addr, Api, BlockInfo, DepsMut, Env, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 pub struct PathIndices<'a> {
     /// PK: parent_address + component_name
@@ -41,6 +46,69 @@ pub const USERS: Map<&str, Addr> = Map::new("users");
 pub const LIBRARIES: Map<&str, Addr> = Map::new("libraries");
 pub const ADDRESS_USERNAME: Map<&str, String> = Map::new("address_username");
 pub const ADDRESS_LIBRARY: Map<&str, String> = Map::new("address_library");
+/// The expiration of usernames registered while a registration period was configured
+pub const USERNAME_EXPIRATIONS: Map<&str, Milliseconds> = Map::new("username_expirations");
+pub const USERNAME_CONFIG: Item<UsernameConfig> = Item::new("username_config");
+/// The primary path of an address, used for reverse resolution
+pub const PRIMARY_PATHS: Map<&Addr, String> = Map::new("primary_paths");
+/// Addresses of remote paths resolved by their chain's Kernel, keyed by chain and path
+pub const REMOTE_PATHS: Map<(&str, &str), RemotePathInfo> = Map::new("remote_paths");
+
+/// Gets the registration status of a username.
+///
+/// Usernames without an expiration never expire, expired usernames are kept for their owner during the grace period.
+pub fn username_status(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    username: &str,
+) -> Result<UsernameStatus, ContractError> {
+    if !USERS.has(storage, username) {
+        return Ok(UsernameStatus::Available);
+    }
+    let Some(expiration) = USERNAME_EXPIRATIONS.may_load(storage, username)? else {
+        return Ok(UsernameStatus::Active);
+    };
+    if !expiration.is_expired(block) {
+        return Ok(UsernameStatus::Active);
+    }
+    let grace_period = USERNAME_CONFIG
+        .may_load(storage)?
+        .unwrap_or_default()
+        .grace_period;
+    if !expiration.plus_milliseconds(grace_period).is_expired(block) {
+        Ok(UsernameStatus::Grace)
+    } else {
+        Ok(UsernameStatus::Available)
+    }
+}
+
+/// Whether the username's registration has expired, usernames in their grace period are expired
+pub fn is_username_expired(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    username: &str,
+) -> Result<bool, ContractError> {
+    Ok(USERNAME_EXPIRATIONS
+        .may_load(storage, username)?
+        .is_some_and(|expiration| expiration.is_expired(block)))
+}
+
+/// Removes a username registration, the previous owner's home directory is kept under their address
+pub fn remove_username(storage: &mut dyn Storage, username: &str) -> Result<(), ContractError> {
+    if let Some(owner) = USERS.may_load(storage, username)? {
+        // The owner may have registered a new username since
+        if ADDRESS_USERNAME
+            .may_load(storage, owner.as_str())?
+            .as_deref()
+            == Some(username)
+        {
+            ADDRESS_USERNAME.remove(storage, owner.as_str());
+        }
+    }
+    USERS.remove(storage, username);
+    USERNAME_EXPIRATIONS.remove(storage, username);
+    Ok(())
+}
 
 /**
    Splits a pathname into its components.
@@ -59,12 +127,14 @@ pub fn split_pathname(path: String) -> Vec<String> {
 
     * **storage**: CosmWasm storage struct
     * **api**: CosmWasm API struct
+    * **block**: The current block, used to check username expiration
     * **path**: The full path to be resolved
     * **resolved_paths**: A vector of resolved paths to prevent looping or paths that are too long
 */
 pub fn resolve_pathname(
     storage: &dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     pathname: AndrAddr,
     resolved_paths: &mut Vec<(Addr, String)>,
 ) -> Result<Addr, ContractError> {
@@ -79,8 +149,8 @@ pub fn resolve_pathname(
 
     if pathname.is_vfs_path() {
         match pathname.get_root_dir() {
-            "home" => resolve_home_path(storage, api, pathname, resolved_paths),
-            "lib" => resolve_lib_path(storage, api, pathname, resolved_paths),
+            "home" => resolve_home_path(storage, api, block, pathname, resolved_paths),
+            "lib" => resolve_lib_path(storage, api, block, pathname, resolved_paths),
             &_ => Err(ContractError::InvalidAddress {}),
         }
     } else {
@@ -93,12 +163,14 @@ pub fn resolve_pathname(
 
     * **storage**: CosmWasm storage struct
     * **api**: CosmWasm API struct
+    * **block**: The current block, used to check username expiration
     * **path**: The full path to be resolved
     * **resolved_paths**: A vector of resolved paths to prevent looping or paths that are too long
 */
 fn resolve_home_path(
     storage: &dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     path: AndrAddr,
     resolved_paths: &mut Vec<(Addr, String)>,
 ) -> Result<Addr, ContractError> {
@@ -111,13 +183,29 @@ fn resolve_home_path(
         .unwrap_or(&parts[amount_to_skip]);
     let user_address = match api.addr_validate(username_or_address) {
         Ok(addr) => addr,
-        Err(_e) => USERS.load(storage, username_or_address)?,
+        Err(_e) => {
+            let user_address = USERS.load(storage, username_or_address)?;
+            ensure!(
+                !is_username_expired(storage, block, username_or_address)?,
+                ContractError::InvalidUsername {
+                    error: Some("Username has expired".to_string())
+                }
+            );
+            user_address
+        }
     };
 
     let mut remaining_parts = parts.to_vec();
 
     remaining_parts.drain(0..amount_to_skip + 1);
-    resolve_path(storage, api, remaining_parts, user_address, resolved_paths)
+    resolve_path(
+        storage,
+        api,
+        block,
+        remaining_parts,
+        user_address,
+        resolved_paths,
+    )
 }
 
 /**
@@ -125,12 +213,14 @@ fn resolve_home_path(
 
     * **storage**: CosmWasm storage struct
     * **api**: CosmWasm API struct
+    * **block**: The current block, used to check username expiration
     * **path**: The full path to be resolved
     * **resolved_paths**: A vector of resolved paths to prevent looping or paths that are too long
 */
 fn resolve_lib_path(
     storage: &dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     path: AndrAddr,
     resolved_paths: &mut Vec<(Addr, String)>,
 ) -> Result<Addr, ContractError> {
@@ -144,7 +234,14 @@ fn resolve_lib_path(
     };
     let mut remaining_parts = parts.to_vec();
     remaining_parts.drain(0..2);
-    resolve_path(storage, api, remaining_parts, lib_address, resolved_paths)
+    resolve_path(
+        storage,
+        api,
+        block,
+        remaining_parts,
+        lib_address,
+        resolved_paths,
+    )
 }
 
 const MAX_DEPTH: u8 = 50;
@@ -156,6 +253,7 @@ const MAX_DEPTH: u8 = 50;
 
     * **storage**: CosmWasm storage struct
     * **api**: CosmWasm API struct
+    * **block**: The current block, used to check username expiration
     * **parts**: The remaining parts of the path to resolve
     * **parent_address**: The address of the parent lib/user
     * **resolved_paths**: A vector of resolved paths to prevent looping or paths that are too long
@@ -163,6 +261,7 @@ const MAX_DEPTH: u8 = 50;
 fn resolve_path(
     storage: &dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     parts: Vec<String>,
     parent_address: Addr,
     resolved_paths: &mut Vec<(Addr, String)>,
@@ -190,7 +289,7 @@ fn resolve_path(
         let info = paths().load(storage, (address.clone(), part.clone()))?;
         resolved_paths.push((address, part.clone()));
         address = match info.symlink {
-            Some(symlink) => resolve_pathname(storage, api, symlink, resolved_paths)?,
+            Some(symlink) => resolve_pathname(storage, api, block, symlink, resolved_paths)?,
             None => info.address,
         };
     }
@@ -204,12 +303,13 @@ const DEFAULT_LIMIT: u32 = 50u32;
 pub fn get_subdir(
     storage: &dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     pathname: AndrAddr,
     min: Option<SubDirBound>,
     max: Option<SubDirBound>,
     limit: Option<u32>,
 ) -> Result<Vec<PathInfo>, ContractError> {
-    let address = resolve_pathname(storage, api, pathname, &mut vec![])?;
+    let address = resolve_pathname(storage, api, block, pathname, &mut vec![])?;
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
 
     let subdirs = paths()
@@ -229,6 +329,170 @@ pub fn get_subdir(
     Ok(subdirs)
 }
 
+const MAX_TREE_DEPTH: u32 = 10u32;
+const DEFAULT_TREE_DEPTH: u32 = 3u32;
+
+/// A path left to visit by `get_tree` along with its depth and the addresses above it
+type PendingNode = (String, u32, Vec<Addr>, PathInfo);
+
+/// Queues the children of a directory after the given name to be visited, the first child is queued last
+#[allow(clippy::too_many_arguments)]
+fn push_children(
+    storage: &dyn Storage,
+    pending: &mut Vec<PendingNode>,
+    directory: &Addr,
+    prefix: &str,
+    depth: u32,
+    ancestors: &[Addr],
+    after: Option<&str>,
+    limit: usize,
+) -> StdResult<()> {
+    let children = paths()
+        .idx
+        .parent
+        .prefix(directory.clone())
+        .range(
+            storage,
+            after.map(|name| Bound::exclusive((directory.clone(), name.to_string()))),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, path)| path))
+        .collect::<StdResult<Vec<PathInfo>>>()?;
+    pending.extend(children.into_iter().rev().map(|info| {
+        (
+            format!("{prefix}{}", info.name),
+            depth,
+            ancestors.to_vec(),
+            info,
+        )
+    }));
+    Ok(())
+}
+
+/// Symlinks are not followed, neither are restricted directories or paths pointing to one of their ancestors
+fn is_descendable(
+    storage: &dyn Storage,
+    info: &PathInfo,
+    depth: u32,
+    max_depth: u32,
+    ancestors: &[Addr],
+) -> bool {
+    depth < max_depth
+        && info.symlink.is_none()
+        && !ancestors.contains(&info.address)
+        && !RESTRICTED_DIRECTORIES.has(storage, &info.address)
+}
+
+/**
+   Lists the paths beneath a given path depth first, with the children of each path ordered by name.
+
+    * **storage**: CosmWasm storage struct
+    * **api**: CosmWasm API struct
+    * **block**: The current block, used to check username expiration
+    * **pathname**: The path to list the paths beneath
+    * **depth**: How many levels beneath the path to list
+    * **start_after**: The relative path of the last node of the previous page
+    * **limit**: The maximum number of nodes to return
+*/
+pub fn get_tree(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    block: &BlockInfo,
+    pathname: AndrAddr,
+    depth: Option<u32>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<TreeNode>, ContractError> {
+    let address = resolve_pathname(storage, api, block, pathname, &mut vec![])?;
+    let max_depth = depth.unwrap_or(DEFAULT_TREE_DEPTH).min(MAX_TREE_DEPTH);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let mut pending: Vec<PendingNode> = vec![];
+    match start_after {
+        None => push_children(
+            storage,
+            &mut pending,
+            &address,
+            "",
+            1,
+            &[address.clone()],
+            None,
+            limit,
+        )?,
+        // The traversal resumes from the cursor, only the siblings after each path along it are left to visit
+        Some(start_after) => {
+            let invalid_cursor = || ContractError::InvalidPathname {
+                error: Some(format!("{start_after} is not a node of the tree")),
+            };
+            let names = start_after.split('/').collect::<Vec<&str>>();
+            let mut directory = address.clone();
+            let mut ancestors = vec![address];
+            let mut prefix = String::new();
+            for (idx, name) in names.iter().enumerate() {
+                let depth = idx as u32 + 1;
+                ensure!(depth <= max_depth, invalid_cursor());
+                let info = paths()
+                    .may_load(storage, (directory.clone(), name.to_string()))?
+                    .ok_or_else(invalid_cursor)?;
+                push_children(
+                    storage,
+                    &mut pending,
+                    &directory,
+                    &prefix,
+                    depth,
+                    &ancestors,
+                    Some(name),
+                    limit,
+                )?;
+                let descendable = is_descendable(storage, &info, depth, max_depth, &ancestors);
+                prefix = format!("{prefix}{name}/");
+                ancestors.push(info.address.clone());
+                if idx + 1 < names.len() {
+                    ensure!(descendable, invalid_cursor());
+                } else if descendable {
+                    push_children(
+                        storage,
+                        &mut pending,
+                        &info.address,
+                        &prefix,
+                        depth + 1,
+                        &ancestors,
+                        None,
+                        limit,
+                    )?;
+                }
+                directory = info.address;
+            }
+        }
+    }
+
+    let mut nodes: Vec<TreeNode> = vec![];
+    while let Some((path, depth, ancestors, info)) = pending.pop() {
+        if nodes.len() >= limit {
+            break;
+        }
+        if is_descendable(storage, &info, depth, max_depth, &ancestors) {
+            let mut ancestors = ancestors;
+            ancestors.push(info.address.clone());
+            push_children(
+                storage,
+                &mut pending,
+                &info.address,
+                &format!("{path}/"),
+                depth + 1,
+                &ancestors,
+                None,
+                limit,
+            )?;
+        }
+        nodes.push(TreeNode { path, depth, info });
+    }
+
+    Ok(nodes)
+}
+
 pub fn get_paths(storage: &dyn Storage, addr: Addr) -> Result<Vec<String>, ContractError> {
     let mut resolved_paths: Vec<String> = vec![];
     let parent_dirs: Vec<PathInfo> = paths()
@@ -245,7 +509,11 @@ pub fn get_paths(storage: &dyn Storage, addr: Addr) -> Result<Vec<String>, Contr
             .unwrap_or(addr.to_string());
         resolved_paths.push(username_or_address)
     }
-    for parent_dir in parent_dirs {
+    // Paths within restricted directories are not listed
+    for parent_dir in parent_dirs
+        .into_iter()
+        .filter(|dir| !RESTRICTED_DIRECTORIES.has(storage, &dir.parent_address))
+    {
         let parent_paths = get_paths(storage, parent_dir.clone().parent_address)?;
         for parent_path in parent_paths {
             resolved_paths.push(parent_path + "/" + parent_dir.name.as_str());
@@ -274,9 +542,154 @@ pub fn add_pathname(
     Ok(())
 }
 
+/// Removes a path and every path beneath it.
+///
+/// Directories that are still referenced by another path or belong to a user or library are kept.
+/// The paths within a directory are only removed if the actor can write to it, otherwise it is only unlinked.
+/// Without an actor, as for the Kernel or VFS owner, every unreferenced directory is cleared.
+/// Returns the amount of removed paths.
+pub fn remove_pathname(
+    deps: DepsMut,
+    env: &Env,
+    parent_addr: Addr,
+    name: String,
+    actor: Option<&Addr>,
+) -> Result<u64, ContractError> {
+    let info = paths().load(deps.storage, (parent_addr.clone(), name.clone()))?;
+    paths().remove(deps.storage, (parent_addr, name))?;
+    let mut removed = 1;
+
+    // Symlinks do not have children of their own
+    let mut pending = match info.symlink {
+        Some(_) => vec![],
+        None => vec![info.address],
+    };
+    let mut visited: Vec<Addr> = vec![];
+    while let Some(address) = pending.pop() {
+        if visited.contains(&address) || is_referenced(deps.storage, &address)? {
+            continue;
+        }
+        visited.push(address.clone());
+        if let Some(actor) = actor {
+            if !has_access(deps.as_ref(), env, &address, &DirectoryAccess::Write, actor)? {
+                continue;
+            }
+        }
+        let children = paths()
+            .idx
+            .parent
+            .prefix(address)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<((Addr, String), PathInfo)>>>()?;
+        for (key, child) in children {
+            paths().remove(deps.storage, key)?;
+            removed += 1;
+            if child.symlink.is_none() {
+                pending.push(child.address);
+            }
+        }
+    }
+    Ok(removed)
+}
+
+fn is_referenced(storage: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    Ok(ADDRESS_USERNAME.has(storage, address.as_str())
+        || ADDRESS_LIBRARY.has(storage, address.as_str())
+        || paths()
+            .idx
+            .address
+            .prefix(address.clone())
+            .keys(storage, None, None, Order::Ascending)
+            .next()
+            .is_some())
+}
+
+pub fn rename_pathname(
+    storage: &mut dyn Storage,
+    parent_addr: Addr,
+    name: String,
+    new_name: String,
+) -> Result<(), ContractError> {
+    let info = paths().load(storage, (parent_addr.clone(), name.clone()))?;
+    ensure!(
+        !paths().has(storage, (parent_addr.clone(), new_name.clone())),
+        ContractError::InvalidPathname {
+            error: Some(format!("Path {new_name} already exists"))
+        }
+    );
+    paths().remove(storage, (parent_addr.clone(), name))?;
+    paths().save(
+        storage,
+        (parent_addr, new_name.clone()),
+        &PathInfo {
+            name: new_name,
+            ..info
+        },
+    )?;
+    Ok(())
+}
+
+/// Moves a path to a new parent, the paths beneath it are keyed by its address and move along with it
+pub fn move_pathname(
+    storage: &mut dyn Storage,
+    parent_addr: Addr,
+    name: String,
+    new_parent_addr: Addr,
+) -> Result<(), ContractError> {
+    let info = paths().load(storage, (parent_addr.clone(), name.clone()))?;
+    ensure!(
+        !paths().has(storage, (new_parent_addr.clone(), name.clone())),
+        ContractError::InvalidPathname {
+            error: Some(format!("Path {name} already exists"))
+        }
+    );
+    // Moving a directory beneath itself would create a looping reference
+    ensure!(
+        info.symlink.is_some() || !is_ancestor(storage, &info.address, &new_parent_addr)?,
+        ContractError::InvalidPathname {
+            error: Some("Cannot move a path beneath itself".to_string())
+        }
+    );
+    paths().remove(storage, (parent_addr, name.clone()))?;
+    paths().save(
+        storage,
+        (new_parent_addr.clone(), name),
+        &PathInfo {
+            parent_address: new_parent_addr,
+            ..info
+        },
+    )?;
+    Ok(())
+}
+
+/// Whether `ancestor` is the given address or one of the directories containing it
+fn is_ancestor(storage: &dyn Storage, ancestor: &Addr, address: &Addr) -> StdResult<bool> {
+    let mut pending = vec![address.clone()];
+    let mut visited: Vec<Addr> = vec![];
+    while let Some(address) = pending.pop() {
+        if &address == ancestor {
+            return Ok(true);
+        }
+        if visited.contains(&address) {
+            continue;
+        }
+        visited.push(address.clone());
+        for item in paths()
+            .idx
+            .address
+            .prefix(address)
+            .range(storage, None, None, Order::Ascending)
+        {
+            pending.push(item?.1.parent_address);
+        }
+    }
+    Ok(false)
+}
+
 pub fn add_path_symlink(
     storage: &mut dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     parent_addr: Addr,
     name: String,
     symlink: AndrAddr,
@@ -294,7 +707,7 @@ pub fn add_path_symlink(
     if symlink.get_protocol().is_none() {
         // Ensure that the symlink resolves to a valid address
         let pathname = AndrAddr::from_string(format!("~{}/{}", parent_addr, name));
-        resolve_pathname(storage, api, pathname, &mut vec![])?;
+        resolve_pathname(storage, api, block, pathname, &mut vec![])?;
     }
 
     Ok(())
@@ -303,6 +716,7 @@ pub fn add_path_symlink(
 pub fn resolve_symlink(
     storage: &dyn Storage,
     api: &dyn Api,
+    block: &BlockInfo,
     path: AndrAddr,
 ) -> Result<AndrAddr, ContractError> {
     let mut parts = split_pathname(path.to_string());
@@ -317,7 +731,7 @@ pub fn resolve_symlink(
     } else {
         AndrAddr::from_string(format!("/{reconstructed_addr}"))
     };
-    let addr = resolve_pathname(storage, api, remaining_path, &mut vec![])?;
+    let addr = resolve_pathname(storage, api, block, remaining_path, &mut vec![])?;
     let info = paths().load(storage, (addr, final_part))?;
     match info.symlink {
         Some(symlink) => Ok(symlink),
@@ -325,9 +739,37 @@ pub fn resolve_symlink(
     }
 }
 
+/// Gets the chain and path on that chain of a remote path, following a local symlink to a remote path
+pub fn get_remote_target(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    block: &BlockInfo,
+    path: AndrAddr,
+) -> Result<(String, String), ContractError> {
+    let target = resolve_symlink(storage, api, block, path.to_lowercase())?;
+    let (Some("ibc"), Some(chain)) = (target.get_protocol(), target.get_chain()) else {
+        return Err(ContractError::InvalidPathname {
+            error: Some(format!("{target} is not a remote path")),
+        });
+    };
+    let remote_path = AndrAddr::from_string(target.get_raw_path());
+    ensure!(
+        remote_path.get_protocol().is_none(),
+        ContractError::InvalidPathname {
+            error: Some(
+                "Remote paths routed through multiple chains are not supported".to_string()
+            )
+        }
+    );
+    Ok((chain.to_string(), remote_path.to_string()))
+}
+
 #[cfg(test)]
 mod test {
-    use cosmwasm_std::{testing::mock_dependencies, DepsMut};
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        DepsMut,
+    };
 
     use super::*;
 
@@ -349,6 +791,7 @@ mod test {
         let res = resolve_pathname(
             &mock_dependencies().storage,
             &mock_dependencies().api,
+            &mock_env().block,
             path,
             &mut vec![],
         )
@@ -379,6 +822,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{username}")),
             &mut vec![],
         )
@@ -388,6 +832,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("~{username}")),
             &mut vec![],
         )
@@ -397,6 +842,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("~/{username}")),
             &mut vec![],
         );
@@ -418,6 +864,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{username}/{first_directory}")),
             &mut vec![],
         )
@@ -426,6 +873,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("~{username}/{first_directory}")),
             &mut vec![],
         )
@@ -451,6 +899,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!(
                 "/home/{username}/{first_directory}/{second_directory}"
             )),
@@ -475,6 +924,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!(
                 "/home/{username}/{first_directory}/{second_directory}/{file}"
             )),
@@ -504,6 +954,7 @@ mod test {
         let res = resolve_lib_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/lib/{lib_name}")),
             &mut vec![],
         )
@@ -526,6 +977,7 @@ mod test {
         let res = resolve_lib_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/lib/{lib_name}/{first_directory}")),
             &mut vec![],
         )
@@ -551,6 +1003,7 @@ mod test {
         let res = resolve_lib_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!(
                 "/lib/{lib_name}/{first_directory}/{second_directory}"
             )),
@@ -575,6 +1028,7 @@ mod test {
         let res = resolve_lib_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!(
                 "/lib/{lib_name}/{first_directory}/{second_directory}/{file}"
             )),
@@ -613,6 +1067,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{username}/{first_directory}")),
             &mut vec![],
         )
@@ -626,6 +1081,7 @@ mod test {
         add_path_symlink(
             storage,
             api,
+            &mock_env().block,
             symlink_parent.clone(),
             symlink_name.to_string(),
             symlink.clone(),
@@ -635,6 +1091,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{symlink_parent}/{symlink_name}")),
             &mut vec![],
         )
@@ -644,6 +1101,7 @@ mod test {
         let res = resolve_symlink(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{symlink_parent}/{symlink_name}")),
         )
         .unwrap();
@@ -653,6 +1111,7 @@ mod test {
         let res = resolve_symlink(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{username}/{first_directory}")),
         )
         .unwrap();
@@ -665,6 +1124,7 @@ mod test {
         let res = resolve_symlink(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("ibc://chain/home/{symlink_parent}/{symlink}")),
         )
         .unwrap();
@@ -678,6 +1138,7 @@ mod test {
         let res = resolve_symlink(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(someaddress.to_string()),
         )
         .unwrap();
@@ -688,6 +1149,7 @@ mod test {
         let res = resolve_symlink(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(format!("/home/{someuser}")),
         )
         .unwrap();
@@ -711,6 +1173,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(path),
             &mut vec![],
         );
@@ -732,6 +1195,7 @@ mod test {
         let res = resolve_lib_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(path),
             &mut vec![],
         );
@@ -764,6 +1228,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(path.clone()),
             &mut vec![],
         );
@@ -787,6 +1252,7 @@ mod test {
         let res = resolve_home_path(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(new_path),
             &mut vec![],
         );
@@ -825,6 +1291,7 @@ mod test {
         let res = add_path_symlink(
             storage,
             api,
+            &mock_env().block,
             first_directory_address,
             username.to_string(),
             AndrAddr::from_string(format!("/home/{username}/{first_directory}/{username}")),
@@ -1,13 +1,23 @@
 use crate::{
+    acl::{DIRECTORY_PERMISSIONS, RESTRICTED_DIRECTORIES},
     contract::{execute, instantiate, query},
-    state::{add_pathname, resolve_pathname, ADDRESS_LIBRARY, ADDRESS_USERNAME, USERS},
+    state::{
+        add_pathname, paths, resolve_pathname, ADDRESS_LIBRARY, ADDRESS_USERNAME, PRIMARY_PATHS,
+        USERS,
+    },
 };
 
 use andromeda_std::{
+    ado_base::permissioning::{LocalPermission, Permission, PermissionInfo, PermissionsResponse},
     amp::AndrAddr,
+    common::{schedule::Schedule, Milliseconds},
     os::{
         kernel::{ExecuteMsg as KernelExecuteMsg, InternalMsg},
-        vfs::{ExecuteMsg, InstantiateMsg, PathInfo, SubDirResponse},
+        vfs::{
+            CrossChainPathResponse, DirectoryAccess, EffectivePermissionsResponse, ExecuteMsg,
+            InstantiateMsg, PathInfo, SubDirResponse, TreeResponse, UsernameConfig,
+            UsernameInfoResponse, UsernameStatus,
+        },
     },
     testing::mock_querier::{
         mock_dependencies_custom, MOCK_APP_CONTRACT, MOCK_FAKE_KERNEL_CONTRACT,
@@ -18,7 +28,7 @@ use andromeda_std::{error::ContractError, os::vfs::QueryMsg};
 use cosmwasm_std::{
     from_json,
     testing::{message_info, mock_dependencies, mock_env},
-    to_json_binary, Addr, CosmosMsg, DepsMut, Env, MessageInfo, WasmMsg,
+    to_json_binary, Addr, CosmosMsg, DepsMut, Env, MessageInfo, OwnedDeps, WasmMsg,
 };
 
 fn instantiate_contract(deps: DepsMut, env: Env, info: MessageInfo) {
@@ -363,6 +373,7 @@ fn test_add_path() {
     let resolved_addr = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -385,6 +396,7 @@ fn test_add_path() {
     let resolved_addr = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -434,6 +446,7 @@ fn test_add_symlink() {
     let resolved_addr = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -455,6 +468,7 @@ fn test_add_symlink() {
     let resolved_addr = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -476,6 +490,7 @@ fn test_add_symlink() {
     let err = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -502,6 +517,7 @@ fn test_add_symlink() {
     let resolved_addr = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -525,6 +541,7 @@ fn test_add_symlink() {
     let err = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -563,6 +580,7 @@ fn test_add_child() {
     let resolved_addr = resolve_pathname(
         deps.as_ref().storage,
         deps.as_ref().api,
+        &mock_env().block,
         AndrAddr::from_string(path),
         &mut vec![],
     )
@@ -748,6 +766,7 @@ fn test_get_subdir() {
         let resolved_addr = resolve_pathname(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(path_name.clone()),
             &mut vec![],
         );
@@ -847,6 +866,7 @@ fn test_get_paths() {
         let resolved_addr = resolve_pathname(
             deps.as_ref().storage,
             deps.as_ref().api,
+            &mock_env().block,
             AndrAddr::from_string(path_name.clone()),
             &mut vec![],
         );
@@ -861,3 +881,1117 @@ fn test_get_paths() {
     let val: Vec<String> = from_json(res).unwrap();
     assert_eq!(val.len(), 2);
 }
+
+#[test]
+fn test_transfer_username() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let sender = deps.api.addr_make("sender");
+    let recipient = deps.api.addr_make("recipient");
+    let component = deps.api.addr_make("component");
+    let info = message_info(&sender, &[]);
+    instantiate_contract(deps.as_mut(), env.clone(), info.clone());
+
+    let msg = ExecuteMsg::RegisterUser {
+        username: "u1".to_string(),
+        address: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::AddPath {
+        name: "dir".to_string(),
+        address: component.clone(),
+        parent_address: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let home = AndrAddr::from_string(sender.to_string());
+    let msgs = vec![
+        ExecuteMsg::SetDirectoryRestricted {
+            directory: home.clone(),
+            restricted: true,
+        },
+        ExecuteMsg::SetDirectoryPermission {
+            directory: home,
+            actors: vec![AndrAddr::from_string(component.to_string())],
+            access: DirectoryAccess::Resolve,
+            permission: Permission::Local(LocalPermission::whitelisted(
+                Schedule::new(None, None),
+                None,
+                None,
+            )),
+        },
+        ExecuteMsg::SetPrimaryPath {
+            path: AndrAddr::from_string("/home/u1"),
+        },
+    ];
+    for msg in msgs {
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::TransferUsername {
+        recipient: recipient.to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    assert_eq!(USERS.load(deps.as_ref().storage, "u1").unwrap(), recipient);
+    assert!(!ADDRESS_USERNAME.has(deps.as_ref().storage, sender.as_str()));
+    assert_eq!(
+        ADDRESS_USERNAME
+            .load(deps.as_ref().storage, recipient.as_str())
+            .unwrap(),
+        "u1"
+    );
+    // The home directory moves with the username
+    let query_msg = QueryMsg::ResolvePathFor {
+        path: AndrAddr::from_string("~u1/dir"),
+        actor: AndrAddr::from_string(recipient.to_string()),
+    };
+    let res: Addr = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(res, component);
+    // So do its access settings and primary path
+    let storage = deps.as_ref().storage;
+    assert!(!RESTRICTED_DIRECTORIES.has(storage, &sender));
+    assert!(RESTRICTED_DIRECTORIES.has(storage, &recipient));
+    let access = DirectoryAccess::Resolve;
+    assert!(!DIRECTORY_PERMISSIONS.has(storage, (&sender, access.as_str(), component.as_str())));
+    assert!(DIRECTORY_PERMISSIONS.has(storage, (&recipient, access.as_str(), component.as_str())));
+    assert!(!PRIMARY_PATHS.has(storage, &sender));
+    assert_eq!(
+        PRIMARY_PATHS.load(storage, &recipient).unwrap(),
+        "/home/u1".to_string()
+    );
+
+    // The sender no longer has a username to transfer
+    let msg = ExecuteMsg::TransferUsername {
+        recipient: recipient.to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidUsername {
+            error: Some("Sender does not have a username".to_string())
+        }
+    );
+
+    // Recipients cannot hold two usernames
+    let info = message_info(&sender, &[]);
+    let msg = ExecuteMsg::RegisterUser {
+        username: "u2".to_string(),
+        address: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::TransferUsername {
+        recipient: recipient.to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidUsername {
+            error: Some("Recipient already has a username".to_string())
+        }
+    );
+
+    // The recipient must be a valid address
+    let msg = ExecuteMsg::TransferUsername {
+        recipient: "Invalid Address".to_string(),
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_release_username() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let sender = deps.api.addr_make("sender");
+    let other = deps.api.addr_make("other");
+    let info = message_info(&sender, &[]);
+    instantiate_contract(deps.as_mut(), env.clone(), info.clone());
+
+    let msg = ExecuteMsg::RegisterUser {
+        username: "u1".to_string(),
+        address: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ReleaseUsername {},
+    )
+    .unwrap();
+
+    assert!(!USERS.has(deps.as_ref().storage, "u1"));
+    assert!(!ADDRESS_USERNAME.has(deps.as_ref().storage, sender.as_str()));
+
+    // Released usernames can be claimed immediately
+    execute(deps.as_mut(), env, message_info(&other, &[]), msg).unwrap();
+    assert_eq!(USERS.load(deps.as_ref().storage, "u1").unwrap(), other);
+}
+
+#[test]
+fn test_username_expiry() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let mut env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    let other = deps.api.addr_make("other");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+
+    let config = UsernameConfig {
+        registration_period: Some(Milliseconds::from_seconds(100)),
+        grace_period: Milliseconds::from_seconds(50),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::SetUsernameConfig {
+            config: config.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        ExecuteMsg::SetUsernameConfig { config },
+    )
+    .unwrap();
+
+    let register_msg = ExecuteMsg::RegisterUser {
+        username: "u1".to_string(),
+        address: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        register_msg.clone(),
+    )
+    .unwrap();
+    let registered_at = Milliseconds::from_nanos(env.block.time.nanos());
+
+    let username_info = |deps: &OwnedDeps<_, _, _>, env: &Env| -> UsernameInfoResponse {
+        let query_msg = QueryMsg::UsernameInfo {
+            username: "u1".to_string(),
+        };
+        from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap()
+    };
+    let get_username = |deps: &OwnedDeps<_, _, _>, env: &Env| -> String {
+        let query_msg = QueryMsg::GetUsername {
+            address: sender.clone(),
+        };
+        from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap()
+    };
+
+    let info = username_info(&deps, &env);
+    assert_eq!(info.status, UsernameStatus::Active);
+    assert_eq!(info.owner, Some(sender.clone()));
+    assert_eq!(info.expiration, Some(registered_at.plus_seconds(100)));
+    assert_eq!(get_username(&deps, &env), "u1");
+
+    // Expired usernames are reserved for their owner but no longer resolve
+    env.block.time = env.block.time.plus_seconds(100);
+    assert_eq!(username_info(&deps, &env).status, UsernameStatus::Grace);
+    assert_eq!(get_username(&deps, &env), sender.to_string());
+    let query_msg = QueryMsg::ResolvePath {
+        path: AndrAddr::from_string("~u1"),
+    };
+    let err = query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidUsername {
+            error: Some("Username has expired".to_string())
+        }
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&other, &[]),
+        register_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidUsername {
+            error: Some("Username already taken".to_string())
+        }
+    );
+
+    // Renewing during the grace period extends the registration from the current time
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RenewUsername {},
+    )
+    .unwrap();
+    let info = username_info(&deps, &env);
+    assert_eq!(info.status, UsernameStatus::Active);
+    assert_eq!(info.expiration, Some(registered_at.plus_seconds(200)));
+
+    // Once the grace period ends anyone can claim the username
+    env.block.time = env.block.time.plus_seconds(150);
+    assert_eq!(username_info(&deps, &env).status, UsernameStatus::Available);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RenewUsername {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidUsername {
+            error: Some("Username grace period has ended".to_string())
+        }
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&other, &[]),
+        register_msg,
+    )
+    .unwrap();
+    assert_eq!(USERS.load(deps.as_ref().storage, "u1").unwrap(), other);
+    assert!(!ADDRESS_USERNAME.has(deps.as_ref().storage, sender.as_str()));
+}
+
+/// Adds `/home/<sender>/app` with the components `comp1`, `comp1/sub` and `comp2`
+fn add_app_tree(deps: DepsMut, sender: &Addr) -> Vec<Addr> {
+    let addresses = ["app", "comp1", "sub", "comp2"]
+        .map(|name| deps.api.addr_make(name))
+        .to_vec();
+    add_pathname(
+        deps.storage,
+        sender.clone(),
+        "app".to_string(),
+        addresses[0].clone(),
+    )
+    .unwrap();
+    add_pathname(
+        deps.storage,
+        addresses[0].clone(),
+        "comp1".to_string(),
+        addresses[1].clone(),
+    )
+    .unwrap();
+    add_pathname(
+        deps.storage,
+        addresses[1].clone(),
+        "sub".to_string(),
+        addresses[2].clone(),
+    )
+    .unwrap();
+    add_pathname(
+        deps.storage,
+        addresses[0].clone(),
+        "comp2".to_string(),
+        addresses[3].clone(),
+    )
+    .unwrap();
+    addresses
+}
+
+#[test]
+fn test_remove_path_to_foreign_directory() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let attacker = deps.api.addr_make("attacker");
+    let victim = deps.api.addr_make("victim");
+    let component = deps.api.addr_make("component");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    add_pathname(
+        deps.as_mut().storage,
+        victim.clone(),
+        "doc".to_string(),
+        component,
+    )
+    .unwrap();
+
+    // Linking to another directory and removing the link does not clear that directory
+    let info = message_info(&attacker, &[]);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::AddPath {
+            name: "x".to_string(),
+            address: victim.clone(),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env,
+        info,
+        ExecuteMsg::RemovePath {
+            name: "x".to_string(),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "removed_paths" && attr.value == "1"));
+
+    let storage = deps.as_ref().storage;
+    assert!(!paths().has(storage, (attacker, "x".to_string())));
+    assert!(paths().has(storage, (victim, "doc".to_string())));
+}
+
+#[test]
+fn test_remove_path() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    let addresses = add_app_tree(deps.as_mut(), &sender);
+    // A second reference to comp1 keeps its subtree alive
+    add_pathname(
+        deps.as_mut().storage,
+        sender.clone(),
+        "alias".to_string(),
+        addresses[1].clone(),
+    )
+    .unwrap();
+
+    // Only the Kernel or owner may remove paths outside of the sender's directory
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RemovePath {
+            name: "comp1".to_string(),
+            parent_address: Some(AndrAddr::from_string(format!("/home/{sender}/app"))),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The paths within the app are only removed by actors that can write to it
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&addresses[0], &[]),
+        ExecuteMsg::SetDirectoryPermission {
+            directory: AndrAddr::from_string(addresses[0].to_string()),
+            actors: vec![AndrAddr::from_string(sender.to_string())],
+            access: DirectoryAccess::Write,
+            permission: Permission::Local(LocalPermission::whitelisted(
+                Schedule::new(None, None),
+                None,
+                None,
+            )),
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RemovePath {
+            name: "app".to_string(),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "removed_paths" && attr.value == "3"));
+
+    let storage = deps.as_ref().storage;
+    assert!(!paths().has(storage, (sender.clone(), "app".to_string())));
+    assert!(!paths().has(storage, (addresses[0].clone(), "comp1".to_string())));
+    assert!(!paths().has(storage, (addresses[0].clone(), "comp2".to_string())));
+    assert!(paths().has(storage, (addresses[1].clone(), "sub".to_string())));
+
+    // The reverse index no longer lists the removed path
+    let query_msg = QueryMsg::Paths {
+        addr: addresses[1].clone(),
+    };
+    let res: Vec<String> =
+        from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(res, vec![format!("{sender}/alias")]);
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        message_info(&sender, &[]),
+        ExecuteMsg::RemovePath {
+            name: "app".to_string(),
+            parent_address: None,
+        },
+    );
+    assert!(err.is_err());
+}
+
+#[test]
+fn test_rename_path() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    let addresses = add_app_tree(deps.as_mut(), &sender);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RenamePath {
+            name: "app".to_string(),
+            new_name: "App2".to_string(),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+
+    let query_msg = QueryMsg::ResolvePath {
+        path: AndrAddr::from_string(format!("/home/{sender}/app2/comp1/sub")),
+    };
+    let res: Addr = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(res, addresses[2]);
+    assert!(!paths().has(deps.as_ref().storage, (sender.clone(), "app".to_string())));
+
+    // Paths cannot be renamed over existing paths
+    let err = execute(
+        deps.as_mut(),
+        env,
+        message_info(&owner, &[]),
+        ExecuteMsg::RenamePath {
+            name: "comp1".to_string(),
+            new_name: "comp2".to_string(),
+            parent_address: Some(AndrAddr::from_string(format!("/home/{sender}/app2"))),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPathname {
+            error: Some("Path comp2 already exists".to_string())
+        }
+    );
+}
+
+#[test]
+fn test_move_path() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    let other = deps.api.addr_make("other");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    let addresses = add_app_tree(deps.as_mut(), &sender);
+
+    // Paths can only be moved into another directory by the Kernel or owner
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::MovePath {
+            name: "app".to_string(),
+            parent_address: None,
+            new_parent_address: AndrAddr::from_string(other.to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        ExecuteMsg::MovePath {
+            name: "app".to_string(),
+            parent_address: Some(AndrAddr::from_string(sender.to_string())),
+            new_parent_address: AndrAddr::from_string(format!("/home/{sender}/app/comp1")),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPathname {
+            error: Some("Cannot move a path beneath itself".to_string())
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        ExecuteMsg::MovePath {
+            name: "comp1".to_string(),
+            parent_address: Some(AndrAddr::from_string(format!("/home/{sender}/app"))),
+            new_parent_address: AndrAddr::from_string(sender.to_string()),
+        },
+    )
+    .unwrap();
+
+    // The subtree moves along with the path
+    let query_msg = QueryMsg::ResolvePath {
+        path: AndrAddr::from_string(format!("/home/{sender}/comp1/sub")),
+    };
+    let res: Addr = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(res, addresses[2]);
+    let query_msg = QueryMsg::Paths {
+        addr: addresses[2].clone(),
+    };
+    let res: Vec<String> = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+    assert_eq!(res, vec![format!("{sender}/comp1/sub")]);
+}
+
+#[test]
+fn test_directory_write_permission() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let alice = deps.api.addr_make("alice");
+    let shared = deps.api.addr_make("shared");
+    let teammate = deps.api.addr_make("teammate");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&alice, &[]),
+        ExecuteMsg::AddPath {
+            name: "shared".to_string(),
+            address: shared.clone(),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+    let shared_path = AndrAddr::from_string(format!("/home/{alice}/shared"));
+    let api = deps.api;
+    let add_doc = |name: &str| ExecuteMsg::AddPath {
+        name: name.to_string(),
+        address: api.addr_make(name),
+        parent_address: Some(shared_path.clone()),
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&teammate, &[]),
+        add_doc("doc1"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Only the directory itself can manage its permissions
+    let set_permission = ExecuteMsg::SetDirectoryPermission {
+        directory: shared_path.clone(),
+        actors: vec![AndrAddr::from_string(teammate.to_string())],
+        access: DirectoryAccess::Write,
+        permission: Permission::Local(LocalPermission::limited(Schedule::new(None, None), 1)),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&alice, &[]),
+        set_permission.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    // Quotas without uses or with an empty window are rejected
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&shared, &[]),
+        ExecuteMsg::SetDirectoryPermission {
+            directory: shared_path.clone(),
+            actors: vec![AndrAddr::from_string(teammate.to_string())],
+            access: DirectoryAccess::Write,
+            permission: Permission::Local(LocalPermission::quota(
+                Schedule::new(None, None),
+                1,
+                Milliseconds::zero(),
+            )),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidPermission { .. }));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&shared, &[]),
+        set_permission,
+    )
+    .unwrap();
+
+    let query_msg = QueryMsg::EffectivePermissions {
+        directory: shared_path.clone(),
+        actor: AndrAddr::from_string(teammate.to_string()),
+    };
+    let res: EffectivePermissionsResponse =
+        from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        EffectivePermissionsResponse {
+            directory: shared.clone(),
+            is_owner: false,
+            restricted: false,
+            can_write: true,
+            can_resolve: true,
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&teammate, &[]),
+        add_doc("doc1"),
+    )
+    .unwrap();
+    let resolved = resolve_pathname(
+        deps.as_ref().storage,
+        deps.as_ref().api,
+        &env.block,
+        AndrAddr::from_string(format!("/home/{alice}/shared/doc1")),
+        &mut vec![],
+    )
+    .unwrap();
+    assert_eq!(resolved, deps.api.addr_make("doc1"));
+
+    // The limited permission has been used up
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&teammate, &[]),
+        add_doc("doc2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&shared, &[]),
+        ExecuteMsg::SetDirectoryPermission {
+            directory: shared_path.clone(),
+            actors: vec![AndrAddr::from_string(teammate.to_string())],
+            access: DirectoryAccess::Write,
+            permission: Permission::Local(LocalPermission::default()),
+        },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&teammate, &[]),
+        ExecuteMsg::RemovePath {
+            name: "doc1".to_string(),
+            parent_address: Some(shared_path.clone()),
+        },
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&shared, &[]),
+        ExecuteMsg::RemoveDirectoryPermission {
+            directory: shared_path.clone(),
+            actors: vec![AndrAddr::from_string(teammate.to_string())],
+            access: DirectoryAccess::Write,
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env,
+        message_info(&teammate, &[]),
+        add_doc("doc2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn test_directory_resolve_restriction() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let alice = deps.api.addr_make("alice");
+    let shared = deps.api.addr_make("shared");
+    let doc = deps.api.addr_make("doc");
+    let teammate = deps.api.addr_make("teammate");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    add_pathname(
+        deps.as_mut().storage,
+        alice.clone(),
+        "shared".to_string(),
+        shared.clone(),
+    )
+    .unwrap();
+    add_pathname(
+        deps.as_mut().storage,
+        shared.clone(),
+        "doc".to_string(),
+        doc.clone(),
+    )
+    .unwrap();
+    let shared_path = AndrAddr::from_string(format!("/home/{alice}/shared"));
+    let resolve_for = |actor: &Addr| QueryMsg::ResolvePathFor {
+        path: AndrAddr::from_string(format!("/home/{alice}/shared/doc")),
+        actor: AndrAddr::from_string(actor.to_string()),
+    };
+
+    // Directories are resolvable by anyone until restricted
+    let res: Addr =
+        from_json(query(deps.as_ref(), env.clone(), resolve_for(&teammate)).unwrap()).unwrap();
+    assert_eq!(res, doc);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&shared, &[]),
+        ExecuteMsg::SetDirectoryRestricted {
+            directory: shared_path.clone(),
+            restricted: true,
+        },
+    )
+    .unwrap();
+    let err = query(deps.as_ref(), env.clone(), resolve_for(&teammate)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res: Addr =
+        from_json(query(deps.as_ref(), env.clone(), resolve_for(&shared)).unwrap()).unwrap();
+    assert_eq!(res, doc);
+
+    // Restricted directories are hidden from queries without an actor
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::ResolvePath {
+            path: AndrAddr::from_string(format!("/home/{alice}/shared/doc")),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res: Addr = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ResolvePath {
+                path: shared_path.clone(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, shared);
+    for query_msg in [
+        QueryMsg::SubDir {
+            path: shared_path.clone(),
+            min: None,
+            max: None,
+            limit: None,
+        },
+        QueryMsg::Tree {
+            path: shared_path.clone(),
+            depth: None,
+            start_after: None,
+            limit: None,
+        },
+    ] {
+        let err = query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+    let res: Vec<String> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Paths { addr: doc.clone() },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(res.is_empty());
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&shared, &[]),
+        ExecuteMsg::SetDirectoryPermission {
+            directory: shared_path.clone(),
+            actors: vec![AndrAddr::from_string(teammate.to_string())],
+            access: DirectoryAccess::Resolve,
+            permission: Permission::Local(LocalPermission::default()),
+        },
+    )
+    .unwrap();
+    let res: Addr =
+        from_json(query(deps.as_ref(), env.clone(), resolve_for(&teammate)).unwrap()).unwrap();
+    assert_eq!(res, doc);
+
+    let query_msg = QueryMsg::DirectoryPermissions {
+        directory: shared_path,
+    };
+    let res: PermissionsResponse =
+        from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+    assert_eq!(
+        res.permissions,
+        vec![PermissionInfo {
+            permission: Permission::Local(LocalPermission::default()),
+            action: "resolve".to_string(),
+            actor: teammate.to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_reverse_resolve() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    let addresses = add_app_tree(deps.as_mut(), &sender);
+    let path = format!("/home/{sender}/app/comp1");
+    let reverse_resolve = QueryMsg::ReverseResolve {
+        addr: addresses[1].clone(),
+    };
+
+    let res: Option<String> =
+        from_json(query(deps.as_ref(), env.clone(), reverse_resolve.clone()).unwrap()).unwrap();
+    assert_eq!(res, None);
+
+    // The primary path must resolve to the sender
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::SetPrimaryPath {
+            path: AndrAddr::from_string(path.clone()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&addresses[1], &[]),
+        ExecuteMsg::SetPrimaryPath {
+            path: AndrAddr::from_string(path.clone()),
+        },
+    )
+    .unwrap();
+    let res: Option<String> =
+        from_json(query(deps.as_ref(), env.clone(), reverse_resolve.clone()).unwrap()).unwrap();
+    assert_eq!(res, Some(path));
+
+    // Primary paths that no longer resolve to the address are not returned
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RenamePath {
+            name: "app".to_string(),
+            new_name: "app2".to_string(),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+    let res: Option<String> =
+        from_json(query(deps.as_ref(), env, reverse_resolve).unwrap()).unwrap();
+    assert_eq!(res, None);
+}
+
+#[test]
+fn test_tree() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    add_app_tree(deps.as_mut(), &sender);
+    let tree = |depth: Option<u32>, start_after: Option<&str>, limit: Option<u32>| {
+        let query_msg = QueryMsg::Tree {
+            path: AndrAddr::from_string(format!("/home/{sender}")),
+            depth,
+            start_after: start_after.map(|path| path.to_string()),
+            limit,
+        };
+        let res: TreeResponse =
+            from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        res.nodes
+            .into_iter()
+            .map(|node| (node.path, node.depth))
+            .collect::<Vec<(String, u32)>>()
+    };
+
+    assert_eq!(
+        tree(None, None, None),
+        vec![
+            ("app".to_string(), 1),
+            ("app/comp1".to_string(), 2),
+            ("app/comp1/sub".to_string(), 3),
+            ("app/comp2".to_string(), 2),
+        ]
+    );
+    assert_eq!(
+        tree(Some(2), None, None),
+        vec![
+            ("app".to_string(), 1),
+            ("app/comp1".to_string(), 2),
+            ("app/comp2".to_string(), 2),
+        ]
+    );
+    assert_eq!(
+        tree(None, None, Some(2)),
+        vec![("app".to_string(), 1), ("app/comp1".to_string(), 2)]
+    );
+    assert_eq!(
+        tree(None, Some("app/comp1"), Some(2)),
+        vec![
+            ("app/comp1/sub".to_string(), 3),
+            ("app/comp2".to_string(), 2)
+        ]
+    );
+    assert_eq!(
+        tree(None, Some("app/comp1/sub"), None),
+        vec![("app/comp2".to_string(), 2)]
+    );
+    assert_eq!(tree(None, Some("app/comp2"), None), vec![]);
+
+    // Cursors must point to a node of the tree
+    for (depth, start_after) in [
+        (None, "app/missing"),
+        (None, "app/comp1/sub/missing"),
+        (Some(2), "app/comp1/sub"),
+    ] {
+        let query_msg = QueryMsg::Tree {
+            path: AndrAddr::from_string(format!("/home/{sender}")),
+            depth,
+            start_after: Some(start_after.to_string()),
+            limit: None,
+        };
+        let err = query(deps.as_ref(), env.clone(), query_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPathname {
+                error: Some(format!("{start_after} is not a node of the tree"))
+            }
+        );
+    }
+}
+
+#[test]
+fn test_resolve_path_cross_chain() {
+    let mut deps = mock_dependencies_custom(&[]);
+    let mut env = mock_env();
+    let owner = deps.api.addr_make("owner");
+    let sender = deps.api.addr_make("sender");
+    instantiate_contract(deps.as_mut(), env.clone(), message_info(&owner, &[]));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::AddSymlink {
+            name: "remote".to_string(),
+            symlink: AndrAddr::from_string("ibc://chain/home/user/app"),
+            parent_address: None,
+        },
+    )
+    .unwrap();
+    let symlink_path = AndrAddr::from_string(format!("/home/{sender}/remote"));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RefreshRemotePath {
+            path: symlink_path.clone(),
+        },
+    )
+    .unwrap();
+    let expected = KernelExecuteMsg::Internal(InternalMsg::ResolvePathCrossChain {
+        path: "/home/user/app".to_string(),
+        chain: "chain".to_string(),
+    });
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_KERNEL_CONTRACT.to_string(),
+            msg: to_json_binary(&expected).unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // Local paths cannot be resolved cross-chain
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        ExecuteMsg::RefreshRemotePath {
+            path: AndrAddr::from_string(format!("/home/{sender}")),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPathname {
+            error: Some(format!("/home/{sender} is not a remote path"))
+        }
+    );
+
+    let query_msg = QueryMsg::ResolvePathCrossChain {
+        path: symlink_path.clone(),
+        max_age: None,
+    };
+    assert!(query(deps.as_ref(), env.clone(), query_msg.clone()).is_err());
+
+    let cache_msg = ExecuteMsg::CacheRemotePath {
+        chain: "chain".to_string(),
+        path: "/home/user/app".to_string(),
+        address: "remote_app".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&sender, &[]),
+        cache_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&Addr::unchecked(MOCK_KERNEL_CONTRACT), &[]),
+        cache_msg,
+    )
+    .unwrap();
+    let cached_at = Milliseconds::from_nanos(env.block.time.nanos());
+
+    let res: CrossChainPathResponse =
+        from_json(query(deps.as_ref(), env.clone(), query_msg.clone()).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        CrossChainPathResponse {
+            chain: "chain".to_string(),
+            path: "/home/user/app".to_string(),
+            address: "remote_app".to_string(),
+            cached_at,
+            age: Milliseconds::zero(),
+            stale: false,
+        }
+    );
+
+    // The cached address goes stale after the maximum age
+    env.block.time = env.block.time.plus_seconds(60);
+    let query_msg = QueryMsg::ResolvePathCrossChain {
+        path: AndrAddr::from_string("ibc://chain/home/user/app"),
+        max_age: Some(Milliseconds::from_seconds(30)),
+    };
+    let res: CrossChainPathResponse =
+        from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+    assert_eq!(res.age, Milliseconds::from_seconds(60));
+    assert!(res.stale);
+}
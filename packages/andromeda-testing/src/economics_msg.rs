@@ -8,6 +8,7 @@ pub fn generate_economics_message(payee: &str, action: &str) -> SubMsg {
             msg: to_json_binary(&EconomicsExecuteMsg::PayFee {
                 payee: Addr::unchecked(payee),
                 action: action.to_string(),
+                funds: vec![],
             })
             .unwrap(),
             funds: vec![],
@@ -1,7 +1,10 @@
 use crate::{mock::MockApp, mock_ado, mock_contract::ExecuteResult, MockADO, MockContract};
 use andromeda_std::amp::AndrAddr;
+use andromeda_std::common::denom::Asset;
 use andromeda_std::os::ibc_registry::{
-    AllDenomInfoResponse, DenomInfoResponse, ExecuteMsg, IBCDenomInfo, InstantiateMsg, QueryMsg,
+    AllDenomInfoResponse, AssetMetadata, AssetMetadataBatchResponse, AssetMetadataResponse,
+    DenomChainsResponse, DenomInfoResponse, DenomTrace, ExecuteMsg, IBCDenomInfo, IBCDenomResponse,
+    InstantiateMsg, QueryMsg,
 };
 use cosmwasm_std::{Addr, Coin};
 use cw_multi_test::Executor;
@@ -51,6 +54,37 @@ impl MockIbcRegistry {
         }
     }
 
+    pub fn execute_record_denom_traces(
+        &self,
+        app: &mut MockApp,
+        sender: Addr,
+        traces: Vec<DenomTrace>,
+    ) -> ExecuteResult {
+        let msg = ExecuteMsg::RecordDenomTraces { traces };
+        app.execute_contract(sender, self.addr().clone(), &msg, &[])
+    }
+
+    pub fn execute_set_asset_metadata(
+        &self,
+        app: &mut MockApp,
+        sender: Addr,
+        asset: Asset,
+        metadata: AssetMetadata,
+    ) -> ExecuteResult {
+        let msg = ExecuteMsg::SetAssetMetadata { asset, metadata };
+        app.execute_contract(sender, self.addr().clone(), &msg, &[])
+    }
+
+    pub fn execute_remove_asset_metadata(
+        &self,
+        app: &mut MockApp,
+        sender: Addr,
+        asset: Asset,
+    ) -> ExecuteResult {
+        let msg = ExecuteMsg::RemoveAssetMetadata { asset };
+        app.execute_contract(sender, self.addr().clone(), &msg, &[])
+    }
+
     pub fn query_denom_info(&self, app: &mut MockApp, denom: String) -> DenomInfoResponse {
         let msg = QueryMsg::DenomInfo { denom };
         let res: DenomInfoResponse = self.query(app, msg);
@@ -67,6 +101,39 @@ impl MockIbcRegistry {
         let res: AllDenomInfoResponse = self.query(app, msg);
         res
     }
+
+    pub fn query_ibc_denom(
+        &self,
+        app: &mut MockApp,
+        base_denom: String,
+        path: String,
+    ) -> IBCDenomResponse {
+        let msg = QueryMsg::IBCDenom { base_denom, path };
+        let res: IBCDenomResponse = self.query(app, msg);
+        res
+    }
+
+    pub fn query_asset_metadata(&self, app: &mut MockApp, asset: Asset) -> AssetMetadataResponse {
+        let msg = QueryMsg::AssetMetadata { asset };
+        let res: AssetMetadataResponse = self.query(app, msg);
+        res
+    }
+
+    pub fn query_asset_metadata_batch(
+        &self,
+        app: &mut MockApp,
+        assets: Vec<Asset>,
+    ) -> AssetMetadataBatchResponse {
+        let msg = QueryMsg::AssetMetadataBatch { assets };
+        let res: AssetMetadataBatchResponse = self.query(app, msg);
+        res
+    }
+
+    pub fn query_denom_chains(&self, app: &mut MockApp, base_denom: String) -> DenomChainsResponse {
+        let msg = QueryMsg::DenomChains { base_denom };
+        let res: DenomChainsResponse = self.query(app, msg);
+        res
+    }
 }
 
 pub fn mock_ibc_registry_instantiate_msg(
@@ -42,6 +42,16 @@ pub fn enum_implementation(_metadata: TokenStream, input: TokenStream) -> TokenS
                 PermissionedActionsWithExpiration {},
                 #[returns(andromeda_std::ado_base::permissioning::PermissionedActionExpirationResponse)]
                 PermissionedActionsExpiration { action: String },
+                #[returns(andromeda_std::ado_base::permissioning::RolesResponse)]
+                Roles { start_after: Option<String>, limit: Option<u32> },
+                #[returns(andromeda_std::ado_base::permissioning::RoleMembersResponse)]
+                RoleMembers { role: String, start_after: Option<String>, limit: Option<u32> },
+                #[returns(andromeda_std::ado_base::permissioning::ActorRolesResponse)]
+                ActorRoles { actor: andromeda_std::amp::AndrAddr },
+                #[returns(andromeda_std::ado_base::permissioning::DelegationChainResponse)]
+                DelegationChain { action: String, actor: andromeda_std::amp::AndrAddr },
+                #[returns(andromeda_std::ado_base::permissioning::PermissionQuotaResponse)]
+                PermissionQuota { action: String, actor: andromeda_std::amp::AndrAddr },
             }
         }
         .into(),
@@ -86,6 +86,23 @@ pub enum AndromedaQuery {
         start_after: Option<String>,
         order_by: Option<OrderBy>,
     },
+    #[returns(self::permissioning::RolesResponse)]
+    Roles {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(self::permissioning::RoleMembersResponse)]
+    RoleMembers {
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(self::permissioning::ActorRolesResponse)]
+    ActorRoles { actor: AndrAddr },
+    #[returns(self::permissioning::DelegationChainResponse)]
+    DelegationChain { action: String, actor: AndrAddr },
+    #[returns(self::permissioning::PermissionQuotaResponse)]
+    PermissionQuota { action: String, actor: AndrAddr },
 
     #[cfg(feature = "rates")]
     #[returns(Option<self::rates::Rate>)]
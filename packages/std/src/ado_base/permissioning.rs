@@ -28,6 +28,39 @@ pub enum PermissioningMessage {
     DisableActionPermissioning {
         action: String,
     },
+    /// Defines a role granting the given actions with the given permission.
+    /// Redefining a role resets the permission of its members.
+    SetRole {
+        role: String,
+        actions: Vec<String>,
+        permission: LocalPermission,
+    },
+    /// Removes a role and revokes it from all of its members
+    RemoveRole {
+        role: String,
+    },
+    AssignRole {
+        role: String,
+        actors: Vec<AndrAddr>,
+    },
+    RevokeRole {
+        role: String,
+        actors: Vec<AndrAddr>,
+    },
+    /// Delegates part of the sender's permission for an action to another address.
+    ///
+    /// The delegated permission must be narrower than the sender's, uses delegated from a limited permission are deducted from it.
+    /// Delegations are revoked when the delegator's permission changes.
+    DelegatePermission {
+        action: String,
+        delegate: AndrAddr,
+        permission: LocalPermission,
+    },
+    /// Revokes a delegation, restricted to the delegator or the owner
+    RevokeDelegation {
+        action: String,
+        delegate: AndrAddr,
+    },
 }
 
 #[cw_serde]
@@ -62,11 +95,58 @@ pub struct PermissionedActorsResponse {
     pub actors: Vec<String>,
 }
 
+/// A named set of actions granted to the role's members with the role's permission
+#[cw_serde]
+pub struct RoleInfo {
+    pub role: String,
+    pub actions: Vec<String>,
+    pub permission: LocalPermission,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub roles: Vec<RoleInfo>,
+}
+
+#[cw_serde]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ActorRolesResponse {
+    pub roles: Vec<String>,
+}
+
+#[cw_serde]
+pub struct PermissionQuotaResponse {
+    pub max_uses: u32,
+    pub remaining_uses: u32,
+    /// When the next use is refilled, `None` if no uses are within the window
+    pub resets_at: Option<Milliseconds>,
+}
+
+#[cw_serde]
+pub struct DelegationInfo {
+    pub action: String,
+    pub delegator: String,
+    pub delegate: String,
+    pub permission: LocalPermission,
+}
+
+#[cw_serde]
+pub struct DelegationChainResponse {
+    /// The delegations from the actor up to the delegator holding the permission directly, empty if the actor has no current delegation
+    pub chain: Vec<DelegationInfo>,
+}
+
 /// An enum to represent a user's permission for an action
 ///
 /// - **Blacklisted** - The user cannot perform the action until after the provided expiration
 /// - **Limited** - The user can perform the action while uses are remaining and before the provided expiration **for a permissioned action**
 /// - **Whitelisted** - The user can perform the action until the provided expiration **for a permissioned action**
+/// - **Quota** - The user can perform the action up to `max_uses` times within any `window` before the provided expiration **for a permissioned action**,
+///   each use is refilled once `window` has elapsed since it was made
 ///
 /// Expiration defaults to `Never` if not provided
 #[cw_serde]
@@ -86,6 +166,16 @@ pub enum LocalPermission {
         window: Option<Milliseconds>,
         last_used: Option<Milliseconds>,
     },
+    Quota {
+        #[serde(default)]
+        schedule: Schedule,
+        max_uses: u32,
+        window: Milliseconds,
+        /// The times of the most recent uses, oldest first, at most `max_uses` are kept.
+        /// Reset when the permission is set
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        recent_uses: Vec<Milliseconds>,
+    },
 }
 
 impl std::default::Default for LocalPermission {
@@ -119,6 +209,43 @@ impl LocalPermission {
         Self::Limited { schedule, uses }
     }
 
+    pub fn quota(schedule: Schedule, max_uses: u32, window: Milliseconds) -> Self {
+        Self::Quota {
+            schedule,
+            max_uses,
+            window,
+            recent_uses: vec![],
+        }
+    }
+
+    /// Whether uses of the permission are counted, in which case it must be saved after being consumed
+    pub fn is_use_limited(&self) -> bool {
+        matches!(self, Self::Limited { .. } | Self::Quota { .. })
+    }
+
+    /// Returns the uses of a quota remaining within the window ending now, along with the time the next use is refilled if any are in use
+    pub fn quota_remaining(&self, env: &Env) -> Option<(u32, Option<Milliseconds>)> {
+        let Self::Quota {
+            max_uses,
+            window,
+            recent_uses,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let now = Milliseconds::from_nanos(env.block.time.nanos());
+        let refills: Vec<Milliseconds> = recent_uses
+            .iter()
+            .map(|used_at| used_at.plus_milliseconds(*window))
+            .filter(|refill| *refill > now)
+            .collect();
+        Some((
+            max_uses.saturating_sub(refills.len() as u32),
+            refills.first().copied(),
+        ))
+    }
+
     pub fn is_permissioned(&self, env: &Env, strict: bool) -> bool {
         match self {
             Self::Blacklisted { schedule } => {
@@ -182,6 +309,25 @@ impl LocalPermission {
                 }
                 true
             }
+            Self::Quota { schedule, .. } => {
+                if let Some(start) = &schedule.start {
+                    if !start.get_time(&env.block).is_expired(&env.block) {
+                        return !strict;
+                    }
+                }
+                if let Some(expiration) = &schedule.end {
+                    if expiration.get_time(&env.block).is_expired(&env.block) {
+                        return !strict;
+                    }
+                }
+                if self
+                    .quota_remaining(env)
+                    .is_some_and(|(remaining, _)| remaining == 0)
+                {
+                    return !strict;
+                }
+                true
+            }
         }
     }
 
@@ -202,6 +348,11 @@ impl LocalPermission {
                 .clone()
                 .unwrap_or_default()
                 .get_time(&env.block),
+            Self::Quota { schedule, .. } => schedule
+                .end
+                .clone()
+                .unwrap_or_default()
+                .get_time(&env.block),
         }
     }
 
@@ -222,12 +373,31 @@ impl LocalPermission {
                 .clone()
                 .unwrap_or_default()
                 .get_time(&env.block),
+            Self::Quota { schedule, .. } => schedule
+                .start
+                .clone()
+                .unwrap_or_default()
+                .get_time(&env.block),
         }
     }
 
-    pub fn consume_use(&mut self) -> Result<(), ContractError> {
-        if let Self::Limited { uses, .. } = self {
-            *uses = uses.saturating_sub(1);
+    pub fn consume_use(&mut self, env: &Env) -> Result<(), ContractError> {
+        match self {
+            Self::Limited { uses, .. } => *uses = uses.saturating_sub(1),
+            Self::Quota {
+                max_uses,
+                window,
+                recent_uses,
+                ..
+            } => {
+                // Uses outside of the window no longer count towards the quota
+                let now = Milliseconds::from_nanos(env.block.time.nanos());
+                recent_uses.retain(|used_at| used_at.plus_milliseconds(*window) > now);
+                recent_uses.push(now);
+                let excess = recent_uses.len().saturating_sub(*max_uses as usize);
+                recent_uses.drain(..excess);
+            }
+            _ => {}
         }
 
         Ok(())
@@ -241,6 +411,7 @@ impl LocalPermission {
             Self::Blacklisted { schedule } => (schedule.start.clone(), schedule.end.clone()),
             Self::Limited { schedule, .. } => (schedule.start.clone(), schedule.end.clone()),
             Self::Whitelisted { schedule, .. } => (schedule.start.clone(), schedule.end.clone()),
+            Self::Quota { schedule, .. } => (schedule.start.clone(), schedule.end.clone()),
         };
 
         let start = start
@@ -349,6 +520,21 @@ impl fmt::Display for LocalPermission {
                 }
                 (None, None, None, None) => "whitelisted".to_string(),
             },
+            Self::Quota {
+                schedule,
+                max_uses,
+                window,
+                recent_uses,
+            } => {
+                let used = recent_uses.len();
+                let quota = format!("quota uses:{used}/{max_uses} window:{window}");
+                match (schedule.start.clone(), schedule.end.clone()) {
+                    (Some(s), Some(e)) => format!("{quota} starting from:{s} until:{e}"),
+                    (Some(s), None) => format!("{quota} starting from:{s}"),
+                    (None, Some(e)) => format!("{quota} until:{e}"),
+                    (None, None) => quota,
+                }
+            }
         };
         write!(f, "{self_as_string}")
     }
@@ -664,4 +850,61 @@ mod tests {
         let is_authorized = permission.is_permissioned(&env, true);
         assert_eq!(is_authorized, expected_authorized);
     }
+
+    #[test]
+    fn test_quota_permission() {
+        let mut env = mock_env();
+        let mut permission =
+            LocalPermission::quota(Schedule::new(None, None), 2, Milliseconds::from_seconds(60));
+        assert_eq!(permission.quota_remaining(&env), Some((2, None)));
+
+        permission.consume_use(&env).unwrap();
+        let reset = Milliseconds::from_nanos(env.block.time.nanos()).plus_seconds(60);
+        assert_eq!(permission.quota_remaining(&env), Some((1, Some(reset))));
+        permission.consume_use(&env).unwrap();
+        assert_eq!(permission.quota_remaining(&env), Some((0, Some(reset))));
+        assert!(!permission.is_permissioned(&env, true));
+
+        // Uses refill once the window has elapsed
+        env.block.time = env.block.time.plus_seconds(60);
+        assert_eq!(permission.quota_remaining(&env), Some((2, None)));
+        assert!(permission.is_permissioned(&env, true));
+        permission.consume_use(&env).unwrap();
+        assert_eq!(
+            permission.quota_remaining(&env),
+            Some((1, Some(reset.plus_seconds(60))))
+        );
+    }
+
+    #[test]
+    fn test_quota_permission_sliding_window() {
+        let mut env = mock_env();
+        let start = Milliseconds::from_nanos(env.block.time.nanos());
+        let mut permission =
+            LocalPermission::quota(Schedule::new(None, None), 2, Milliseconds::from_seconds(60));
+        permission.consume_use(&env).unwrap();
+        env.block.time = env.block.time.plus_seconds(30);
+        permission.consume_use(&env).unwrap();
+
+        // Both uses are within the window until the first is a full window old
+        env.block.time = env.block.time.plus_nanos(29_999_000_000);
+        assert_eq!(
+            permission.quota_remaining(&env),
+            Some((0, Some(start.plus_seconds(60))))
+        );
+        assert!(!permission.is_permissioned(&env, true));
+
+        // Only the first use is refilled at the end of its window
+        env.block.time = env.block.time.plus_nanos(1_000_000);
+        assert_eq!(
+            permission.quota_remaining(&env),
+            Some((1, Some(start.plus_seconds(90))))
+        );
+        permission.consume_use(&env).unwrap();
+        assert_eq!(
+            permission.quota_remaining(&env),
+            Some((0, Some(start.plus_seconds(90))))
+        );
+        assert!(!permission.is_permissioned(&env, true));
+    }
 }
@@ -228,6 +228,7 @@ impl LocalRate {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             };
@@ -10,13 +10,16 @@ use crate::{
     ado_base::{AndromedaMsg, InstantiateMsg},
     ado_contract::{permissioning, ADOContract},
     amp::{addresses::AndrAddr, messages::AMPPkt},
-    common::{context::ExecuteContext, reply::ReplyId},
+    common::{context::ExecuteContext, migration::ensure_migration_path, reply::ReplyId},
     error::{from_semver, ContractError},
-    os::{aos_querier::AOSQuerier, economics::ExecuteMsg as EconomicsExecuteMsg},
+    os::{
+        aos_querier::AOSQuerier, economics::ExecuteMsg as EconomicsExecuteMsg,
+        kernel::is_os_contract,
+    },
 };
 use cosmwasm_std::{
-    attr, ensure, from_json, to_json_binary, Addr, Api, ContractInfoResponse, CosmosMsg, Deps,
-    DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, Storage, SubMsg, WasmMsg,
+    attr, ensure, from_json, to_json_binary, Addr, Api, Coin, ContractInfoResponse, CosmosMsg,
+    Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, Storage, SubMsg, WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use semver::Version;
@@ -158,6 +161,11 @@ impl ADOContract {
             }
         );
 
+        // Upgrades cannot skip the migrations registered in the ADODB, OS contracts are not published through it
+        if storage_version < version && !is_os_contract(contract_name) {
+            ensure_migration_path(&deps.as_ref(), contract_name, contract_version)?;
+        }
+
         // Migrate from old permissioning format to new
         permissioning::migrate::migrate(deps.storage)?;
 
@@ -313,17 +321,24 @@ impl ADOContract {
     ///
     /// If any of the above cannot pay the fee the remainder is paid by the next in the list until no remainder remains.
     /// If there is still a remainder after all 3 payments then the fee cannot be paid and the message will error.
+    ///
+    /// The provided funds are used to calculate fees charging a percentage of the funds sent with the action.
     pub fn pay_fee(
         &self,
         storage: &dyn Storage,
         querier: &QuerierWrapper,
         action: String,
         payee: Addr,
+        funds: Vec<Coin>,
     ) -> Result<SubMsg, ContractError> {
         let kernel_address = self.get_kernel_address(storage)?;
         let economics_contract_address =
             AOSQuerier::kernel_address_getter(querier, &kernel_address, "economics")?;
-        let economics_msg = EconomicsExecuteMsg::PayFee { action, payee };
+        let economics_msg = EconomicsExecuteMsg::PayFee {
+            action,
+            payee,
+            funds,
+        };
         let msg = SubMsg::reply_on_error(
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: economics_contract_address.to_string(),
@@ -1,6 +1,8 @@
 use crate::ado_base::permissioning::{
-    LocalPermission, PermissionedActionExpirationResponse, PermissionedActionsResponse,
+    ActorRolesResponse, DelegationChainResponse, DelegationInfo, LocalPermission,
+    PermissionQuotaResponse, PermissionedActionExpirationResponse, PermissionedActionsResponse,
     PermissionedActionsWithExpirationResponse, PermissionedActorsResponse, PermissionsResponse,
+    RoleInfo, RoleMembersResponse, RolesResponse,
 };
 use crate::common::Milliseconds;
 use crate::os::aos_querier::AOSQuerier;
@@ -12,16 +14,17 @@ use crate::{
 };
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    ensure, to_json_binary, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult, Storage, SubMsg, WasmMsg,
+    ensure, to_json_binary, Addr, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdResult, Storage, SubMsg, WasmMsg,
 };
-use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, MultiIndex};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Map, MultiIndex};
 
 use super::ADOContract;
 
 const MAX_QUERY_LIMIT: u32 = 50;
 const DEFAULT_QUERY_LIMIT: u32 = 25;
 const WILDCARD: &str = "*";
+const MAX_DELEGATION_DEPTH: usize = 5;
 
 #[cw_serde]
 // Importing this enum from the address list contract would result in a circular dependency
@@ -63,6 +66,28 @@ pub fn permissions() -> IndexedMap<&'static str, PermissionInfo, PermissionsIndi
     IndexedMap::new("andr_permissions", indexes)
 }
 
+/// Roles defined for the ADO contract, keyed by role name
+const ROLES: Map<&str, RoleInfo> = Map::new("andr_roles");
+/// The permission of each member of a role, keyed by role and actor
+///
+/// Members keep their own copy of the role's permission so that uses and windows are tracked per actor
+const ROLE_MEMBERS: Map<(&str, &str), LocalPermission> = Map::new("andr_role_members");
+/// The roles assigned to each actor, keyed by actor and role
+const ACTOR_ROLES: Map<(&str, &str), Empty> = Map::new("andr_actor_roles");
+
+#[cw_serde]
+struct Delegation {
+    delegator: String,
+    permission: LocalPermission,
+    /// The version of the delegator's grant when the delegation was made
+    parent_version: u64,
+}
+
+/// Delegated permissions, keyed by action and delegate
+const DELEGATIONS: Map<(&str, &str), Delegation> = Map::new("andr_delegations");
+/// Incremented whenever the permission or delegation of an actor for an action changes, keyed by action and actor
+const GRANT_VERSIONS: Map<(&str, &str), u64> = Map::new("andr_grant_versions");
+
 impl ADOContract {
     pub fn execute_permissioning(
         &self,
@@ -84,14 +109,37 @@ impl ADOContract {
             PermissioningMessage::DisableActionPermissioning { action } => {
                 self.execute_disable_action_permission(ctx, action)
             }
+            PermissioningMessage::SetRole {
+                role,
+                actions,
+                permission,
+            } => self.execute_set_role(ctx, role, actions, permission),
+            PermissioningMessage::RemoveRole { role } => self.execute_remove_role(ctx, role),
+            PermissioningMessage::AssignRole { role, actors } => {
+                self.execute_assign_role(ctx, role, actors)
+            }
+            PermissioningMessage::RevokeRole { role, actors } => {
+                self.execute_revoke_role(ctx, role, actors)
+            }
+            PermissioningMessage::DelegatePermission {
+                action,
+                delegate,
+                permission,
+            } => self.execute_delegate_permission(ctx, action, delegate, permission),
+            PermissioningMessage::RevokeDelegation { action, delegate } => {
+                self.execute_revoke_delegation(ctx, action, delegate)
+            }
         }
     }
     /// Determines if the provided actor is authorised to perform the given action
     ///
+    /// The actor's roles are checked before any permission granted to the actor directly,
+    /// which is used if none of the roles authorise the actor.
+    ///
     /// Returns an error if the given action is not permissioned for the given actor
     pub fn is_permissioned(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         action: impl Into<String>,
         actor: impl Into<String>,
@@ -104,26 +152,26 @@ impl ADOContract {
             return Ok(None);
         }
 
+        let permissioned_action =
+            self.is_action_permissioned(deps.storage, &env, &action_string)?;
+        // Actors whose roles do not authorise them fall back to their own permissions
+        let role_denied = match check_role_permissions(
+            deps.branch(),
+            &env,
+            &action_string,
+            &actor_string,
+            permissioned_action,
+        )? {
+            Some(true) => return Ok(None),
+            Some(false) => true,
+            None => false,
+        };
+
         let permission = Self::get_permission(
             deps.as_ref().storage,
             action_string.clone(),
             actor_string.clone(),
         )?;
-        let permissioned_action = self
-            .permissioned_actions
-            .may_load(deps.storage, action_string.clone())?;
-
-        let permissioned_action = match permissioned_action {
-            Some(expiry) => match expiry {
-                // If the time is expired, it means that the permission is expired and that the action is not longer permissioned
-                Some(expiry) => !expiry.is_expired(&env.block),
-                // If no expiry is provided by the user, that means that the permission will never expire
-                None => true,
-            },
-            // If there's no entry at all for the permissioned action, that means that the action is not permissioned
-            None => false,
-        };
-
         match permission {
             Some(mut some_permission) => {
                 match some_permission {
@@ -133,6 +181,7 @@ impl ADOContract {
                         // Consume a use for a limited permission
                         consume_and_save_limited_permission(
                             deps,
+                            &env,
                             local_permission,
                             &action_string,
                             &actor_string,
@@ -149,10 +198,10 @@ impl ADOContract {
                         handle_local_permission(&env, &mut local_permission, permissioned_action)?;
 
                         // Limited section
-                        if let LocalPermission::Limited { .. } = local_permission {
+                        if local_permission.is_use_limited() {
                             // Only consume a use if the action is permissioned
                             if permissioned_action {
-                                local_permission.consume_use()?;
+                                local_permission.consume_use(&env)?;
                             }
                         }
                         // Construct Sub Msg to update the permission in the address list contract
@@ -163,6 +212,17 @@ impl ADOContract {
                 }
             }
             None => {
+                if let Some(authorised) = check_delegated_permission(
+                    deps.branch(),
+                    &env,
+                    &action_string,
+                    &actor_string,
+                    permissioned_action,
+                )? {
+                    ensure!(authorised, ContractError::Unauthorized {});
+                    return Ok(None);
+                }
+
                 let permission = Self::get_permission(
                     deps.as_ref().storage,
                     action_string.clone(),
@@ -176,6 +236,7 @@ impl ADOContract {
                             // Consume a use for a limited permission
                             consume_and_save_limited_permission(
                                 deps,
+                                &env,
                                 local_permission,
                                 &action_string,
                                 WILDCARD,
@@ -196,10 +257,10 @@ impl ADOContract {
                             )?;
 
                             // Limited section
-                            if let LocalPermission::Limited { .. } = local_permission {
+                            if local_permission.is_use_limited() {
                                 // Only consume a use if the action is permissioned
                                 if permissioned_action {
-                                    local_permission.consume_use()?;
+                                    local_permission.consume_use(&env)?;
                                 }
                             }
                             // Construct Sub Msg to update the permission in the address list contract
@@ -209,7 +270,10 @@ impl ADOContract {
                         }
                     }
                 } else {
-                    ensure!(!permissioned_action, ContractError::Unauthorized {});
+                    ensure!(
+                        !permissioned_action && !role_denied,
+                        ContractError::Unauthorized {}
+                    );
                     None
                 };
                 Ok(sub_msg)
@@ -221,10 +285,13 @@ impl ADOContract {
     ///
     /// **Ignores the `PERMISSIONED_ACTIONS` map**
     ///
+    /// The actor's roles are checked before any permission granted to the actor directly,
+    /// which is used if none of the roles authorise the actor.
+    ///
     /// Returns an error if the permission has expired or if no permission exists for a restricted ADO
     pub fn is_permissioned_strict(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         action: impl Into<String>,
         actor: impl Into<String>,
@@ -237,6 +304,13 @@ impl ADOContract {
             return Ok(None);
         }
 
+        // Actors whose roles do not authorise them fall back to their own permissions
+        if check_role_permissions(deps.branch(), &env, &action_string, &actor_string, true)?
+            == Some(true)
+        {
+            return Ok(None);
+        }
+
         let permission =
             Self::get_permission(deps.storage, action_string.clone(), actor_string.clone())?;
         match permission {
@@ -247,6 +321,7 @@ impl ADOContract {
 
                         consume_and_save_limited_permission(
                             deps,
+                            &env,
                             local_permission,
                             &action_string,
                             &actor_string,
@@ -261,9 +336,9 @@ impl ADOContract {
                         handle_local_permission(&env, &mut local_permission, true)?;
 
                         // Limited section
-                        if let LocalPermission::Limited { .. } = local_permission {
+                        if local_permission.is_use_limited() {
                             // Always consume a use due to strict setting
-                            local_permission.consume_use()?;
+                            local_permission.consume_use(&env)?;
                         }
                         // Construct Sub Msg to update the permission in the address list contract
                         let sub_msg =
@@ -273,6 +348,17 @@ impl ADOContract {
                 }
             }
             None => {
+                if let Some(authorised) = check_delegated_permission(
+                    deps.branch(),
+                    &env,
+                    &action_string,
+                    &actor_string,
+                    true,
+                )? {
+                    ensure!(authorised, ContractError::Unauthorized {});
+                    return Ok(None);
+                }
+
                 let permission = Self::get_permission(
                     deps.as_ref().storage,
                     action_string.clone(),
@@ -285,6 +371,7 @@ impl ADOContract {
 
                             consume_and_save_limited_permission(
                                 deps,
+                                &env,
                                 local_permission,
                                 &action_string,
                                 WILDCARD,
@@ -302,9 +389,9 @@ impl ADOContract {
                             handle_local_permission(&env, &mut local_permission, true)?;
 
                             // Limited section
-                            if let LocalPermission::Limited { .. } = local_permission {
+                            if local_permission.is_use_limited() {
                                 // Only consume a use if the action is permissioned
-                                local_permission.consume_use()?;
+                                local_permission.consume_use(&env)?;
                             }
                             // Construct Sub Msg to update the permission in the address list contract
                             let sub_msg =
@@ -320,6 +407,29 @@ impl ADOContract {
         }
     }
 
+    /// Returns whether the given action is currently permissioned
+    fn is_action_permissioned(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        action: &str,
+    ) -> Result<bool, ContractError> {
+        let permissioned_action = self
+            .permissioned_actions
+            .may_load(storage, action.to_string())?;
+
+        Ok(match permissioned_action {
+            Some(expiry) => match expiry {
+                // If the time is expired, it means that the permission is expired and that the action is not longer permissioned
+                Some(expiry) => !expiry.is_expired(&env.block),
+                // If no expiry is provided by the user, that means that the permission will never expire
+                None => true,
+            },
+            // If there's no entry at all for the permissioned action, that means that the action is not permissioned
+            None => false,
+        })
+    }
+
     /// Gets the permission for the given action and actor
     pub fn get_permission(
         store: &dyn Storage,
@@ -346,6 +456,7 @@ impl ADOContract {
         let action = action.into();
         let actor = actor.into();
         let key = action.clone() + &actor;
+        bump_grant_version(store, &action, &actor)?;
         permissions().save(
             store,
             &key,
@@ -366,14 +477,16 @@ impl ADOContract {
     ) -> Result<(), ContractError> {
         let action = action.into();
         let actor = actor.into();
+        bump_grant_version(store, &action, &actor)?;
         let key = action + &actor;
         permissions().remove(store, &key)?;
         Ok(())
     }
 
-    /// Removes the permission for the given action and actor
+    /// Removes all permissions along with any delegations
     pub fn clear_all_permissions(store: &mut dyn Storage) -> Result<(), ContractError> {
         permissions().clear(store);
+        DELEGATIONS.clear(store);
         Ok(())
     }
 
@@ -386,7 +499,7 @@ impl ADOContract {
         ctx: ExecuteContext,
         actors: Vec<AndrAddr>,
         action: impl Into<String>,
-        mut permission: Permission,
+        permission: Permission,
     ) -> Result<Response, ContractError> {
         ensure!(
             Self::is_contract_owner(self, ctx.deps.storage, ctx.info.sender.as_str())?,
@@ -406,32 +519,11 @@ impl ADOContract {
                 actor_addrs.push(actor_addr);
             }
         }
-        // Last used should always be set at None in the beginning
-        permission = match permission {
-            Permission::Local(LocalPermission::Whitelisted {
-                schedule, window, ..
-            }) => Permission::Local(LocalPermission::whitelisted(schedule, window, None)),
-            _ => permission,
-        };
-
-        let (start, end) = permission.validate_times(&ctx.env)?;
-        let verified_schedule = Schedule::new(Some(Expiry::AtTime(start)), end.map(Expiry::AtTime));
-
-        match permission {
-            Permission::Local(LocalPermission::Whitelisted {
-                window, last_used, ..
-            }) => Permission::Local(LocalPermission::whitelisted(
-                verified_schedule,
-                window,
-                last_used,
-            )),
-            Permission::Local(LocalPermission::Blacklisted { .. }) => {
-                Permission::Local(LocalPermission::blacklisted(verified_schedule))
-            }
-            Permission::Local(LocalPermission::Limited { uses, .. }) => {
-                Permission::Local(LocalPermission::limited(verified_schedule, uses))
+        let permission = match permission {
+            Permission::Local(local_permission) => {
+                Permission::Local(verify_local_permission(&ctx.env, local_permission)?)
             }
-            Permission::Contract(ref andr_addr) => Permission::Contract(andr_addr.clone()),
+            Permission::Contract(andr_addr) => Permission::Contract(andr_addr),
         };
 
         for actor_addr in actor_addrs.clone() {
@@ -559,6 +651,292 @@ impl ADOContract {
         ]))
     }
 
+    /// Execute handler for defining a role
+    pub fn execute_set_role(
+        &self,
+        ctx: ExecuteContext,
+        role: String,
+        actions: Vec<String>,
+        permission: LocalPermission,
+    ) -> Result<Response, ContractError> {
+        ensure!(
+            Self::is_contract_owner(self, ctx.deps.storage, ctx.info.sender.as_str())?,
+            ContractError::Unauthorized {}
+        );
+        ensure!(
+            !role.is_empty(),
+            ContractError::InvalidRole {
+                msg: "Role name cannot be empty".to_string()
+            }
+        );
+        ensure!(
+            !actions.is_empty(),
+            ContractError::InvalidRole {
+                msg: "A role must grant at least one action".to_string()
+            }
+        );
+        let permission = verify_local_permission(&ctx.env, permission)?;
+
+        // Members are reset to the new permission
+        let members = ROLE_MEMBERS
+            .prefix(role.as_str())
+            .keys(ctx.deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<String>>>()?;
+        for member in members {
+            ROLE_MEMBERS.save(
+                ctx.deps.storage,
+                (role.as_str(), member.as_str()),
+                &permission,
+            )?;
+        }
+        ROLES.save(
+            ctx.deps.storage,
+            role.as_str(),
+            &RoleInfo {
+                role: role.clone(),
+                actions: actions.clone(),
+                permission: permission.clone(),
+            },
+        )?;
+
+        Ok(Response::default().add_attributes(vec![
+            ("action", "set_role"),
+            ("role", role.as_str()),
+            ("actions", actions.join(", ").as_str()),
+            ("permission", permission.to_string().as_str()),
+        ]))
+    }
+
+    /// Execute handler for removing a role, the role is revoked from all of its members
+    pub fn execute_remove_role(
+        &self,
+        ctx: ExecuteContext,
+        role: String,
+    ) -> Result<Response, ContractError> {
+        ensure!(
+            Self::is_contract_owner(self, ctx.deps.storage, ctx.info.sender.as_str())?,
+            ContractError::Unauthorized {}
+        );
+        load_role(ctx.deps.storage, &role)?;
+
+        let members = ROLE_MEMBERS
+            .prefix(role.as_str())
+            .keys(ctx.deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<String>>>()?;
+        for member in members {
+            ROLE_MEMBERS.remove(ctx.deps.storage, (role.as_str(), member.as_str()));
+            ACTOR_ROLES.remove(ctx.deps.storage, (member.as_str(), role.as_str()));
+        }
+        ROLES.remove(ctx.deps.storage, role.as_str());
+
+        Ok(Response::default()
+            .add_attributes(vec![("action", "remove_role"), ("role", role.as_str())]))
+    }
+
+    /// Execute handler for assigning a role to actors
+    pub fn execute_assign_role(
+        &self,
+        ctx: ExecuteContext,
+        role: String,
+        actors: Vec<AndrAddr>,
+    ) -> Result<Response, ContractError> {
+        ensure!(
+            Self::is_contract_owner(self, ctx.deps.storage, ctx.info.sender.as_str())?,
+            ContractError::Unauthorized {}
+        );
+        ensure!(!actors.is_empty(), ContractError::NoActorsProvided {});
+        let role_info = load_role(ctx.deps.storage, &role)?;
+
+        let mut actor_addrs = Vec::new();
+        for actor in actors {
+            let actor_addr = actor.get_raw_address(&ctx.deps.as_ref())?;
+            ROLE_MEMBERS.save(
+                ctx.deps.storage,
+                (role.as_str(), actor_addr.as_str()),
+                &role_info.permission,
+            )?;
+            ACTOR_ROLES.save(
+                ctx.deps.storage,
+                (actor_addr.as_str(), role.as_str()),
+                &Empty {},
+            )?;
+            actor_addrs.push(actor_addr.to_string());
+        }
+
+        Ok(Response::default().add_attributes(vec![
+            ("action", "assign_role"),
+            ("role", role.as_str()),
+            ("actors", actor_addrs.join(", ").as_str()),
+        ]))
+    }
+
+    /// Execute handler for revoking a role from actors
+    pub fn execute_revoke_role(
+        &self,
+        ctx: ExecuteContext,
+        role: String,
+        actors: Vec<AndrAddr>,
+    ) -> Result<Response, ContractError> {
+        ensure!(
+            Self::is_contract_owner(self, ctx.deps.storage, ctx.info.sender.as_str())?,
+            ContractError::Unauthorized {}
+        );
+        ensure!(!actors.is_empty(), ContractError::NoActorsProvided {});
+
+        let mut actor_addrs = Vec::new();
+        for actor in actors {
+            let actor_addr = actor.get_raw_address(&ctx.deps.as_ref())?;
+            ROLE_MEMBERS.remove(ctx.deps.storage, (role.as_str(), actor_addr.as_str()));
+            ACTOR_ROLES.remove(ctx.deps.storage, (actor_addr.as_str(), role.as_str()));
+            actor_addrs.push(actor_addr.to_string());
+        }
+
+        Ok(Response::default().add_attributes(vec![
+            ("action", "revoke_role"),
+            ("role", role.as_str()),
+            ("actors", actor_addrs.join(", ").as_str()),
+        ]))
+    }
+
+    /// Execute handler for delegating part of the sender's permission for an action
+    pub fn execute_delegate_permission(
+        &self,
+        ctx: ExecuteContext,
+        action: String,
+        delegate: AndrAddr,
+        permission: LocalPermission,
+    ) -> Result<Response, ContractError> {
+        let delegator = ctx.info.sender.to_string();
+        let delegate = delegate.get_raw_address(&ctx.deps.as_ref())?.to_string();
+        ensure!(
+            delegate != delegator,
+            ContractError::InvalidPermissionDelegation {
+                msg: "Cannot delegate to self".to_string()
+            }
+        );
+        let permission = verify_local_permission(&ctx.env, permission)?;
+
+        // A delegate holds a single delegation per action, which only its delegator can replace
+        if let Some(existing) =
+            DELEGATIONS.may_load(ctx.deps.storage, (action.as_str(), delegate.as_str()))?
+        {
+            let is_current = !delegation_chain(ctx.deps.storage, &action, &delegate)?.is_empty();
+            ensure!(
+                !is_current || existing.delegator == delegator,
+                ContractError::InvalidPermissionDelegation {
+                    msg: format!(
+                        "{delegate} already holds a delegation of {action} from {}",
+                        existing.delegator
+                    )
+                }
+            );
+            refund_delegated_uses(ctx.deps.storage, &action, &existing)?;
+        }
+
+        // The delegator's own grant is either held directly or delegated to them
+        let direct_permission = match Self::get_permission(ctx.deps.storage, &action, &delegator)? {
+            Some(Permission::Local(local_permission)) => Some(local_permission),
+            Some(Permission::Contract(_)) => {
+                return Err(ContractError::InvalidPermissionDelegation {
+                    msg: "Permissions referencing a contract cannot be delegated".to_string(),
+                })
+            }
+            None => None,
+        };
+        let parent_delegation = match direct_permission {
+            Some(_) => None,
+            None => delegation_chain(ctx.deps.storage, &action, &delegator)?
+                .into_iter()
+                .next(),
+        };
+        let mut parent_permission = match (&direct_permission, &parent_delegation) {
+            (Some(local_permission), _) => local_permission.clone(),
+            (None, Some(delegation)) => delegation.permission.clone(),
+            (None, None) => return Err(ContractError::Unauthorized {}),
+        };
+        ensure_narrower_permission(&ctx.env, &parent_permission, &permission)?;
+
+        // Delegated uses are deducted from the delegator's uses
+        if let (
+            LocalPermission::Limited { uses, .. },
+            LocalPermission::Limited {
+                uses: delegated_uses,
+                ..
+            },
+        ) = (&mut parent_permission, &permission)
+        {
+            *uses -= delegated_uses;
+            match parent_delegation {
+                Some(delegation) => DELEGATIONS.save(
+                    ctx.deps.storage,
+                    (action.as_str(), delegator.as_str()),
+                    &Delegation {
+                        permission: parent_permission,
+                        ..delegation
+                    },
+                )?,
+                None => save_grant_state(
+                    ctx.deps.storage,
+                    &PermissionInfo {
+                        action: action.clone(),
+                        actor: delegator.clone(),
+                        permission: Permission::Local(parent_permission),
+                    },
+                )?,
+            };
+        }
+
+        let parent_version = grant_version(ctx.deps.storage, &action, &delegator)?;
+        bump_grant_version(ctx.deps.storage, &action, &delegate)?;
+        DELEGATIONS.save(
+            ctx.deps.storage,
+            (action.as_str(), delegate.as_str()),
+            &Delegation {
+                delegator: delegator.clone(),
+                permission: permission.clone(),
+                parent_version,
+            },
+        )?;
+
+        Ok(Response::default().add_attributes(vec![
+            ("action", "delegate_permission"),
+            ("delegator", delegator.as_str()),
+            ("delegate", delegate.as_str()),
+            ("delegated_action", action.as_str()),
+            ("permission", permission.to_string().as_str()),
+        ]))
+    }
+
+    /// Execute handler for revoking a delegation, restricted to the delegator or the owner
+    pub fn execute_revoke_delegation(
+        &self,
+        ctx: ExecuteContext,
+        action: String,
+        delegate: AndrAddr,
+    ) -> Result<Response, ContractError> {
+        let delegate = delegate.get_raw_address(&ctx.deps.as_ref())?.to_string();
+        let delegation = DELEGATIONS
+            .may_load(ctx.deps.storage, (action.as_str(), delegate.as_str()))?
+            .ok_or(ContractError::InvalidPermissionDelegation {
+                msg: format!("No delegation of {action} to {delegate}"),
+            })?;
+        ensure!(
+            ctx.info.sender.as_str() == delegation.delegator
+                || Self::is_contract_owner(self, ctx.deps.storage, ctx.info.sender.as_str())?,
+            ContractError::Unauthorized {}
+        );
+
+        refund_delegated_uses(ctx.deps.storage, &action, &delegation)?;
+        DELEGATIONS.remove(ctx.deps.storage, (action.as_str(), delegate.as_str()));
+        bump_grant_version(ctx.deps.storage, &action, &delegate)?;
+
+        Ok(Response::default().add_attributes(vec![
+            ("action", "revoke_delegation"),
+            ("delegate", delegate.as_str()),
+            ("delegated_action", action.as_str()),
+        ]))
+    }
+
     /// Queries all permissions for a given actor
     pub fn query_permissions(
         &self,
@@ -649,6 +1027,488 @@ impl ADOContract {
 
         Ok(PermissionedActorsResponse { actors })
     }
+
+    pub fn query_roles(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<RolesResponse, ContractError> {
+        let min = start_after.as_deref().map(Bound::exclusive);
+        let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+        let roles = ROLES
+            .range(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, role)| role))
+            .collect::<StdResult<Vec<RoleInfo>>>()?;
+        Ok(RolesResponse { roles })
+    }
+
+    pub fn query_role_members(
+        &self,
+        deps: Deps,
+        role: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<RoleMembersResponse, ContractError> {
+        let min = start_after.as_deref().map(Bound::exclusive);
+        let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+        let members = ROLE_MEMBERS
+            .prefix(role.as_str())
+            .keys(deps.storage, min, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<String>>>()?;
+        Ok(RoleMembersResponse { members })
+    }
+
+    pub fn query_actor_roles(
+        &self,
+        deps: Deps,
+        actor: AndrAddr,
+    ) -> Result<ActorRolesResponse, ContractError> {
+        let actor = actor.get_raw_address(&deps)?;
+        let roles = ACTOR_ROLES
+            .prefix(actor.as_str())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<String>>>()?;
+        Ok(ActorRolesResponse { roles })
+    }
+
+    pub fn query_delegation_chain(
+        &self,
+        deps: Deps,
+        action: String,
+        actor: AndrAddr,
+    ) -> Result<DelegationChainResponse, ContractError> {
+        let actor = actor.get_raw_address(&deps)?;
+        let chain = delegation_chain(deps.storage, &action, actor.as_str())?
+            .into_iter()
+            .scan(actor.to_string(), |delegate, delegation| {
+                let info = DelegationInfo {
+                    action: action.clone(),
+                    delegator: delegation.delegator.clone(),
+                    delegate: delegate.clone(),
+                    permission: delegation.permission,
+                };
+                *delegate = delegation.delegator;
+                Some(info)
+            })
+            .collect();
+        Ok(DelegationChainResponse { chain })
+    }
+
+    pub fn query_permission_quota(
+        &self,
+        deps: Deps,
+        env: Env,
+        action: String,
+        actor: AndrAddr,
+    ) -> Result<PermissionQuotaResponse, ContractError> {
+        let actor = actor.get_raw_address(&deps)?;
+        let candidates = candidate_permissions(deps, &action, actor.as_str())?;
+        // As when executing, the first permission that authorises the actor is the one used
+        let permission = candidates
+            .iter()
+            .find(|permission| permission.is_permissioned(&env, true))
+            .or(candidates.first())
+            .ok_or(ContractError::InvalidPermission {
+                msg: format!("No permission for {actor} to perform {action}"),
+            })?;
+        match (&permission, permission.quota_remaining(&env)) {
+            (LocalPermission::Quota { max_uses, .. }, Some((remaining_uses, resets_at))) => {
+                Ok(PermissionQuotaResponse {
+                    max_uses: *max_uses,
+                    remaining_uses,
+                    resets_at,
+                })
+            }
+            _ => Err(ContractError::InvalidPermission {
+                msg: format!("The permission of {actor} to perform {action} is not a quota"),
+            }),
+        }
+    }
+}
+
+fn load_role(storage: &dyn Storage, role: &str) -> Result<RoleInfo, ContractError> {
+    ROLES
+        .may_load(storage, role)?
+        .ok_or(ContractError::RoleNotFound {
+            role: role.to_string(),
+        })
+}
+
+/// Checks the permissions of the actor's roles that grant the given action
+///
+/// Returns `None` if none of the actor's roles grant the action,
+/// otherwise whether any of the roles authorise the actor, consuming a use if required
+fn check_role_permissions(
+    deps: DepsMut,
+    env: &Env,
+    action: &str,
+    actor: &str,
+    permissioned_action: bool,
+) -> Result<Option<bool>, ContractError> {
+    let roles = ACTOR_ROLES
+        .prefix(actor)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+
+    let mut matched = false;
+    for role in roles {
+        let role_info = ROLES.load(deps.storage, role.as_str())?;
+        if !role_info
+            .actions
+            .iter()
+            .any(|role_action| role_action == action)
+        {
+            continue;
+        }
+        matched = true;
+
+        let mut permission = ROLE_MEMBERS.load(deps.storage, (role.as_str(), actor))?;
+        if handle_local_permission(env, &mut permission, permissioned_action).is_ok() {
+            if permissioned_action {
+                permission.consume_use(env)?;
+            }
+            ROLE_MEMBERS.save(deps.storage, (role.as_str(), actor), &permission)?;
+            return Ok(Some(true));
+        }
+    }
+
+    Ok(matched.then_some(false))
+}
+
+/// Returns the permissions that may authorise the actor for the given action, in the order they are checked by `is_permissioned`
+///
+/// These are the permissions of the actor's roles that grant the action, followed by the actor's own permission,
+/// its current delegation if it has no permission of its own, or otherwise the wildcard permission.
+fn candidate_permissions(
+    deps: Deps,
+    action: &str,
+    actor: &str,
+) -> Result<Vec<LocalPermission>, ContractError> {
+    let mut candidates = vec![];
+    for role in ACTOR_ROLES
+        .prefix(actor)
+        .keys(deps.storage, None, None, Order::Ascending)
+    {
+        let role = role?;
+        let role_info = ROLES.load(deps.storage, role.as_str())?;
+        if role_info
+            .actions
+            .iter()
+            .any(|role_action| role_action == action)
+        {
+            candidates.push(ROLE_MEMBERS.load(deps.storage, (role.as_str(), actor))?);
+        }
+    }
+
+    if let Some(mut permission) = ADOContract::get_permission(deps.storage, action, actor)? {
+        candidates.push(permission.get_permission(deps, actor)?);
+    } else if let Some(delegation) = delegation_chain(deps.storage, action, actor)?.first() {
+        candidates.push(delegation.permission.clone());
+    } else if let Some(mut permission) =
+        ADOContract::get_permission(deps.storage, action, WILDCARD)?
+    {
+        candidates.push(permission.get_permission(deps, WILDCARD)?);
+    }
+    Ok(candidates)
+}
+
+fn grant_version(storage: &dyn Storage, action: &str, actor: &str) -> StdResult<u64> {
+    Ok(GRANT_VERSIONS
+        .may_load(storage, (action, actor))?
+        .unwrap_or_default())
+}
+
+/// Marks the grant of an actor for an action as changed, revoking any delegations made from it
+fn bump_grant_version(storage: &mut dyn Storage, action: &str, actor: &str) -> StdResult<()> {
+    let version = grant_version(storage, action, actor)?;
+    GRANT_VERSIONS.save(storage, (action, actor), &(version + 1))
+}
+
+/// Saves the updated state of a grant, such as its remaining uses or window.
+///
+/// The grant's version is left unchanged so that the delegations made from it are kept.
+fn save_grant_state(storage: &mut dyn Storage, info: &PermissionInfo) -> StdResult<()> {
+    permissions().save(storage, &(info.action.clone() + &info.actor), info)
+}
+
+/// Returns the delegations from the actor up to the delegator holding the permission directly.
+///
+/// The chain is empty if the actor has no delegation or if any grant in the chain has changed since its delegation was made.
+fn delegation_chain(
+    storage: &dyn Storage,
+    action: &str,
+    actor: &str,
+) -> Result<Vec<Delegation>, ContractError> {
+    let mut chain = vec![];
+    let mut delegate = actor.to_string();
+    while chain.len() < MAX_DELEGATION_DEPTH {
+        let Some(delegation) = DELEGATIONS.may_load(storage, (action, delegate.as_str()))? else {
+            return Ok(vec![]);
+        };
+        if grant_version(storage, action, &delegation.delegator)? != delegation.parent_version {
+            return Ok(vec![]);
+        }
+        let delegator = delegation.delegator.clone();
+        chain.push(delegation);
+        if permissions().has(storage, &(action.to_string() + &delegator)) {
+            return Ok(chain);
+        }
+        delegate = delegator;
+    }
+    Ok(vec![])
+}
+
+/// Checks the actor's delegated permission for the given action
+///
+/// Returns `None` if the actor has no current delegation, otherwise whether the delegation authorises the actor, consuming a use if required
+fn check_delegated_permission(
+    deps: DepsMut,
+    env: &Env,
+    action: &str,
+    actor: &str,
+    permissioned_action: bool,
+) -> Result<Option<bool>, ContractError> {
+    let chain = delegation_chain(deps.storage, action, actor)?;
+    let Some(delegation) = chain.first().cloned() else {
+        return Ok(None);
+    };
+
+    let mut permission = delegation.permission.clone();
+    if handle_local_permission(env, &mut permission, permissioned_action).is_err() {
+        return Ok(Some(false));
+    }
+    if permissioned_action {
+        if !use_delegator_windows(deps.storage, env, action, &chain)? {
+            return Ok(Some(false));
+        }
+        permission.consume_use(env)?;
+    }
+    DELEGATIONS.save(
+        deps.storage,
+        (action, actor),
+        &Delegation {
+            permission,
+            ..delegation
+        },
+    )?;
+    Ok(Some(true))
+}
+
+/// Uses the window of each windowed grant in a delegation chain,
+/// so that delegating a windowed permission to several delegates cannot raise the rate the action is performed at
+///
+/// Returns `false` without using any window if one of them has not elapsed
+fn use_delegator_windows(
+    storage: &mut dyn Storage,
+    env: &Env,
+    action: &str,
+    chain: &[Delegation],
+) -> Result<bool, ContractError> {
+    let is_windowed = |permission: &LocalPermission| {
+        matches!(
+            permission,
+            LocalPermission::Whitelisted {
+                window: Some(_),
+                ..
+            }
+        )
+    };
+
+    // Each delegator's grant is the next delegation in the chain, the last delegator holds the permission directly
+    let mut delegations = vec![];
+    for (idx, delegation) in chain.iter().enumerate().skip(1) {
+        if is_windowed(&delegation.permission) {
+            let mut delegation = delegation.clone();
+            if handle_local_permission(env, &mut delegation.permission, true).is_err() {
+                return Ok(false);
+            }
+            delegations.push((chain[idx - 1].delegator.as_str(), delegation));
+        }
+    }
+    let Some(last) = chain.last() else {
+        return Ok(true);
+    };
+    let key = action.to_string() + &last.delegator;
+    let mut direct = None;
+    if let Some(mut info) = permissions().may_load(storage, &key)? {
+        if let Permission::Local(permission) = &mut info.permission {
+            if is_windowed(permission) {
+                if handle_local_permission(env, permission, true).is_err() {
+                    return Ok(false);
+                }
+                direct = Some(info);
+            }
+        }
+    }
+
+    for (delegate, delegation) in delegations {
+        DELEGATIONS.save(storage, (action, delegate), &delegation)?;
+    }
+    if let Some(direct) = direct {
+        save_grant_state(storage, &direct)?;
+    }
+    Ok(true)
+}
+
+/// Returns the unused uses of a limited delegation to the grant it was delegated from
+///
+/// Nothing is returned if the delegator's grant has changed since the delegation was made
+fn refund_delegated_uses(
+    storage: &mut dyn Storage,
+    action: &str,
+    delegation: &Delegation,
+) -> Result<(), ContractError> {
+    let LocalPermission::Limited { uses: unused, .. } = delegation.permission else {
+        return Ok(());
+    };
+    if unused == 0
+        || grant_version(storage, action, &delegation.delegator)? != delegation.parent_version
+    {
+        return Ok(());
+    }
+
+    let key = action.to_string() + &delegation.delegator;
+    if let Some(mut info) = permissions().may_load(storage, &key)? {
+        if let Permission::Local(LocalPermission::Limited { uses, .. }) = &mut info.permission {
+            *uses = uses.saturating_add(unused);
+            save_grant_state(storage, &info)?;
+        }
+    } else if let Some(mut parent) =
+        DELEGATIONS.may_load(storage, (action, delegation.delegator.as_str()))?
+    {
+        if let LocalPermission::Limited { uses, .. } = &mut parent.permission {
+            *uses = uses.saturating_add(unused);
+            DELEGATIONS.save(storage, (action, delegation.delegator.as_str()), &parent)?;
+        }
+    }
+    Ok(())
+}
+
+fn schedule_times(
+    env: &Env,
+    permission: &LocalPermission,
+) -> (Option<Milliseconds>, Option<Milliseconds>) {
+    let schedule = match permission {
+        LocalPermission::Blacklisted { schedule } => schedule,
+        LocalPermission::Limited { schedule, .. } => schedule,
+        LocalPermission::Whitelisted { schedule, .. } => schedule,
+        LocalPermission::Quota { schedule, .. } => schedule,
+    };
+    (
+        schedule
+            .start
+            .as_ref()
+            .map(|start| start.get_time(&env.block)),
+        schedule.end.as_ref().map(|end| end.get_time(&env.block)),
+    )
+}
+
+/// Ensures that a delegated permission grants no more than the delegator's permission
+fn ensure_narrower_permission(
+    env: &Env,
+    parent: &LocalPermission,
+    delegated: &LocalPermission,
+) -> Result<(), ContractError> {
+    let invalid = |msg: &str| ContractError::InvalidPermissionDelegation {
+        msg: msg.to_string(),
+    };
+    match (parent, delegated) {
+        (LocalPermission::Blacklisted { .. }, _) => return Err(ContractError::Unauthorized {}),
+        (_, LocalPermission::Blacklisted { .. }) => {
+            return Err(invalid("Blacklists cannot be delegated"))
+        }
+        (
+            LocalPermission::Limited { uses, .. },
+            LocalPermission::Limited {
+                uses: delegated_uses,
+                ..
+            },
+        ) => {
+            ensure!(
+                *delegated_uses > 0 && delegated_uses <= uses,
+                invalid("Delegated uses must be between one and the delegator's remaining uses")
+            );
+        }
+        (LocalPermission::Limited { .. }, _) => {
+            return Err(invalid(
+                "A limited permission can only be delegated with limited uses",
+            ))
+        }
+        (LocalPermission::Quota { .. }, _) => {
+            return Err(invalid("Quota permissions cannot be delegated"))
+        }
+        (
+            LocalPermission::Whitelisted {
+                window: Some(_), ..
+            },
+            LocalPermission::Quota { .. },
+        ) => {
+            return Err(invalid(
+                "A windowed permission cannot be delegated as a quota",
+            ))
+        }
+        (
+            LocalPermission::Whitelisted {
+                window: Some(window),
+                ..
+            },
+            LocalPermission::Whitelisted {
+                window: delegated_window,
+                ..
+            },
+        ) => {
+            ensure!(
+                delegated_window.is_some_and(|delegated_window| delegated_window >= *window),
+                invalid("The delegated window cannot be shorter than the delegator's")
+            );
+        }
+        _ => {}
+    }
+
+    let (start, end) = schedule_times(env, parent);
+    let (delegated_start, delegated_end) = schedule_times(env, delegated);
+    ensure!(
+        start.is_none_or(|start| delegated_start.is_some_and(|delegated| delegated >= start)),
+        invalid("The delegated permission cannot start before the delegator's")
+    );
+    ensure!(
+        end.is_none_or(|end| delegated_end.is_some_and(|delegated| delegated <= end)),
+        invalid("The delegated permission cannot end after the delegator's")
+    );
+    Ok(())
+}
+
+/// Fixes the schedule of a permission to absolute times and resets its usage,
+/// rejecting quotas that would allow unlimited uses
+pub fn verify_local_permission(
+    env: &Env,
+    permission: LocalPermission,
+) -> Result<LocalPermission, ContractError> {
+    let (start, end) = permission.validate_times(env)?;
+    let verified_schedule = Schedule::new(Some(Expiry::AtTime(start)), end.map(Expiry::AtTime));
+
+    Ok(match permission {
+        // Last used should always be set at None in the beginning
+        LocalPermission::Whitelisted { window, .. } => {
+            LocalPermission::whitelisted(verified_schedule, window, None)
+        }
+        LocalPermission::Blacklisted { .. } => LocalPermission::blacklisted(verified_schedule),
+        LocalPermission::Limited { uses, .. } => LocalPermission::limited(verified_schedule, uses),
+        LocalPermission::Quota {
+            max_uses, window, ..
+        } => {
+            ensure!(
+                max_uses > 0 && !window.is_zero(),
+                ContractError::InvalidPermission {
+                    msg: "Quotas require at least one use per non-zero window".to_string()
+                }
+            );
+            LocalPermission::quota(verified_schedule, max_uses, window)
+        }
+    })
 }
 
 fn handle_local_permission(
@@ -670,13 +1530,14 @@ fn handle_local_permission(
 
 fn consume_and_save_limited_permission(
     deps: DepsMut,
+    env: &Env,
     local_permission: &mut LocalPermission,
     action_string: &str,
     actor_string: &str,
     permissioned_action: bool,
 ) -> Result<(), ContractError> {
-    if let LocalPermission::Limited { .. } = local_permission {
-        local_permission.consume_use()?;
+    if local_permission.is_use_limited() {
+        local_permission.consume_use(env)?;
 
         if permissioned_action {
             // Save updated permission info
@@ -2177,4 +3038,539 @@ mod tests {
             "Should fail after wildcard permission is removed"
         );
     }
+
+    #[test]
+    fn test_roles() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let contract = ADOContract::default();
+        let owner = Addr::unchecked(OWNER);
+        contract.owner.save(deps.as_mut().storage, &owner).unwrap();
+        let minter = deps.api.addr_make("minter");
+        let other = deps.api.addr_make("other");
+        for action in ["mint", "burn"] {
+            contract
+                .permission_action(deps.as_mut().storage, action, None)
+                .unwrap();
+        }
+
+        let execute = |deps: DepsMut, sender: &Addr, msg: PermissioningMessage| {
+            let ctx = ExecuteContext::new(deps, message_info(sender, &[]), mock_env());
+            contract.execute(ctx, AndromedaMsg::Permissioning(msg))
+        };
+        let set_role = PermissioningMessage::SetRole {
+            role: "minter".to_string(),
+            actions: vec!["mint".to_string()],
+            permission: LocalPermission::limited(Schedule::new(None, None), 2),
+        };
+        let err = execute(deps.as_mut(), &minter, set_role.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), &owner, set_role).unwrap();
+
+        let assign_role = |role: &str| PermissioningMessage::AssignRole {
+            role: role.to_string(),
+            actors: vec![AndrAddr::from_string(minter.to_string())],
+        };
+        let err = execute(deps.as_mut(), &owner, assign_role("pauser")).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RoleNotFound {
+                role: "pauser".to_string()
+            }
+        );
+        execute(deps.as_mut(), &owner, assign_role("minter")).unwrap();
+
+        // Only the role's actions are granted, uses are tracked per member
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "burn", minter.as_str())
+            .is_err());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", other.as_str())
+            .is_err());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+        assert!(contract
+            .is_permissioned_strict(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_err());
+
+        // Direct grants are used once the role's uses are exhausted
+        ADOContract::set_permission(
+            deps.as_mut().storage,
+            "mint",
+            minter.as_str(),
+            Permission::Local(LocalPermission::default()),
+        )
+        .unwrap();
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+        assert!(contract
+            .is_permissioned_strict(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+
+        let roles = contract.query_roles(deps.as_ref(), None, None).unwrap();
+        assert_eq!(roles.roles.len(), 1);
+        assert_eq!(roles.roles[0].actions, vec!["mint".to_string()]);
+        let members = contract
+            .query_role_members(deps.as_ref(), "minter".to_string(), None, None)
+            .unwrap();
+        assert_eq!(members.members, vec![minter.to_string()]);
+        let actor_roles = contract
+            .query_actor_roles(deps.as_ref(), AndrAddr::from_string(minter.to_string()))
+            .unwrap();
+        assert_eq!(actor_roles.roles, vec!["minter".to_string()]);
+
+        // Revoking the role falls back to the direct grant
+        execute(
+            deps.as_mut(),
+            &owner,
+            PermissioningMessage::RevokeRole {
+                role: "minter".to_string(),
+                actors: vec![AndrAddr::from_string(minter.to_string())],
+            },
+        )
+        .unwrap();
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+
+        execute(deps.as_mut(), &owner, assign_role("minter")).unwrap();
+        execute(
+            deps.as_mut(),
+            &owner,
+            PermissioningMessage::RemoveRole {
+                role: "minter".to_string(),
+            },
+        )
+        .unwrap();
+        let actor_roles = contract
+            .query_actor_roles(deps.as_ref(), AndrAddr::from_string(minter.to_string()))
+            .unwrap();
+        assert!(actor_roles.roles.is_empty());
+        assert!(contract
+            .query_roles(deps.as_ref(), None, None)
+            .unwrap()
+            .roles
+            .is_empty());
+    }
+
+    #[test]
+    fn test_permission_delegation() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let contract = ADOContract::default();
+        let owner = Addr::unchecked(OWNER);
+        contract.owner.save(deps.as_mut().storage, &owner).unwrap();
+        let manager = deps.api.addr_make("manager");
+        let delegate = deps.api.addr_make("delegate");
+        let sub_delegate = deps.api.addr_make("sub_delegate");
+        contract
+            .permission_action(deps.as_mut().storage, "mint", None)
+            .unwrap();
+        ADOContract::set_permission(
+            deps.as_mut().storage,
+            "mint",
+            manager.as_str(),
+            Permission::Local(LocalPermission::limited(Schedule::new(None, None), 5)),
+        )
+        .unwrap();
+
+        let execute = |deps: DepsMut, sender: &Addr, msg: PermissioningMessage| {
+            let ctx = ExecuteContext::new(deps, message_info(sender, &[]), mock_env());
+            contract.execute(ctx, AndromedaMsg::Permissioning(msg))
+        };
+        let delegate_msg =
+            |to: &Addr, permission: LocalPermission| PermissioningMessage::DelegatePermission {
+                action: "mint".to_string(),
+                delegate: AndrAddr::from_string(to.to_string()),
+                permission,
+            };
+
+        // Delegations cannot be wider than the delegator's permission
+        let err = execute(
+            deps.as_mut(),
+            &manager,
+            delegate_msg(
+                &delegate,
+                LocalPermission::limited(Schedule::new(None, None), 6),
+            ),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidPermissionDelegation { .. }
+        ));
+        let err = execute(
+            deps.as_mut(),
+            &manager,
+            delegate_msg(&delegate, LocalPermission::default()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidPermissionDelegation { .. }
+        ));
+        let err = execute(
+            deps.as_mut(),
+            &delegate,
+            delegate_msg(
+                &sub_delegate,
+                LocalPermission::limited(Schedule::new(None, None), 1),
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Delegated uses are deducted from the delegator
+        execute(
+            deps.as_mut(),
+            &manager,
+            delegate_msg(
+                &delegate,
+                LocalPermission::limited(Schedule::new(None, None), 3),
+            ),
+        )
+        .unwrap();
+        let permission =
+            ADOContract::get_permission(deps.as_ref().storage, "mint", manager.as_str()).unwrap();
+        assert_eq!(
+            permission,
+            Some(Permission::Local(LocalPermission::limited(
+                Schedule::new(None, None),
+                2
+            )))
+        );
+
+        // Delegates can delegate further within their own delegation
+        execute(
+            deps.as_mut(),
+            &delegate,
+            delegate_msg(
+                &sub_delegate,
+                LocalPermission::limited(Schedule::new(None, None), 1),
+            ),
+        )
+        .unwrap();
+        let chain = contract
+            .query_delegation_chain(
+                deps.as_ref(),
+                "mint".to_string(),
+                AndrAddr::from_string(sub_delegate.to_string()),
+            )
+            .unwrap()
+            .chain;
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].delegate, sub_delegate.to_string());
+        assert_eq!(chain[0].delegator, delegate.to_string());
+        assert_eq!(chain[1].delegator, manager.to_string());
+
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", sub_delegate.as_str())
+            .is_ok());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", sub_delegate.as_str())
+            .is_err());
+        assert!(contract
+            .is_permissioned_strict(deps.as_mut(), env.clone(), "mint", delegate.as_str())
+            .is_ok());
+
+        // Only the delegator or the owner can revoke a delegation
+        let revoke_msg = PermissioningMessage::RevokeDelegation {
+            action: "mint".to_string(),
+            delegate: AndrAddr::from_string(sub_delegate.to_string()),
+        };
+        let err = execute(deps.as_mut(), &manager, revoke_msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), &owner, revoke_msg).unwrap();
+
+        // A delegate holds one delegation per action, which other delegators cannot replace
+        let other = deps.api.addr_make("other");
+        ADOContract::set_permission(
+            deps.as_mut().storage,
+            "mint",
+            other.as_str(),
+            Permission::Local(LocalPermission::limited(Schedule::new(None, None), 1)),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            &other,
+            delegate_msg(
+                &delegate,
+                LocalPermission::limited(Schedule::new(None, None), 1),
+            ),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidPermissionDelegation { .. }
+        ));
+
+        // Unused delegated uses are returned to the delegator when the delegation is replaced or revoked
+        let manager_uses =
+            |deps: Deps| match ADOContract::get_permission(deps.storage, "mint", manager.as_str())
+                .unwrap()
+            {
+                Some(Permission::Local(LocalPermission::Limited { uses, .. })) => uses,
+                permission => panic!("Unexpected permission {permission:?}"),
+            };
+        assert_eq!(manager_uses(deps.as_ref()), 2);
+        execute(
+            deps.as_mut(),
+            &manager,
+            delegate_msg(
+                &delegate,
+                LocalPermission::limited(Schedule::new(None, None), 2),
+            ),
+        )
+        .unwrap();
+        assert_eq!(manager_uses(deps.as_ref()), 1);
+        execute(
+            deps.as_mut(),
+            &manager,
+            PermissioningMessage::RevokeDelegation {
+                action: "mint".to_string(),
+                delegate: AndrAddr::from_string(delegate.to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(manager_uses(deps.as_ref()), 3);
+        execute(
+            deps.as_mut(),
+            &manager,
+            delegate_msg(
+                &delegate,
+                LocalPermission::limited(Schedule::new(None, None), 1),
+            ),
+        )
+        .unwrap();
+
+        // Changing the delegator's permission revokes the delegations made from it
+        ADOContract::set_permission(
+            deps.as_mut().storage,
+            "mint",
+            manager.as_str(),
+            Permission::Local(LocalPermission::default()),
+        )
+        .unwrap();
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", delegate.as_str())
+            .is_err());
+        let chain = contract
+            .query_delegation_chain(
+                deps.as_ref(),
+                "mint".to_string(),
+                AndrAddr::from_string(delegate.to_string()),
+            )
+            .unwrap()
+            .chain;
+        assert!(chain.is_empty());
+    }
+
+    #[test]
+    fn test_windowed_permission_delegation() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let contract = ADOContract::default();
+        let owner = Addr::unchecked(OWNER);
+        contract.owner.save(deps.as_mut().storage, &owner).unwrap();
+        let manager = deps.api.addr_make("manager");
+        let delegates = [deps.api.addr_make("first"), deps.api.addr_make("second")];
+        let window = Some(Milliseconds::from_seconds(60));
+        contract
+            .permission_action(deps.as_mut().storage, "mint", None)
+            .unwrap();
+        ADOContract::set_permission(
+            deps.as_mut().storage,
+            "mint",
+            manager.as_str(),
+            Permission::Local(LocalPermission::whitelisted(
+                Schedule::new(None, None),
+                window,
+                None,
+            )),
+        )
+        .unwrap();
+        for delegate in &delegates {
+            let ctx = ExecuteContext::new(deps.as_mut(), message_info(&manager, &[]), env.clone());
+            contract
+                .execute(
+                    ctx,
+                    AndromedaMsg::Permissioning(PermissioningMessage::DelegatePermission {
+                        action: "mint".to_string(),
+                        delegate: AndrAddr::from_string(delegate.to_string()),
+                        permission: LocalPermission::whitelisted(
+                            Schedule::new(None, None),
+                            window,
+                            None,
+                        ),
+                    }),
+                )
+                .unwrap();
+        }
+
+        // Delegates share the delegator's window
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", delegates[0].as_str())
+            .is_ok());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", delegates[1].as_str())
+            .is_err());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", manager.as_str())
+            .is_err());
+
+        env.block.time = env.block.time.plus_seconds(60);
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", delegates[1].as_str())
+            .is_ok());
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env, "mint", delegates[0].as_str())
+            .is_err());
+    }
+
+    #[test]
+    fn test_quota_permission() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let contract = ADOContract::default();
+        let owner = Addr::unchecked(OWNER);
+        contract.owner.save(deps.as_mut().storage, &owner).unwrap();
+        let minter = deps.api.addr_make("minter");
+        contract
+            .permission_action(deps.as_mut().storage, "mint", None)
+            .unwrap();
+
+        let set_quota = |deps: DepsMut, max_uses: u32, window: Milliseconds| {
+            let ctx = ExecuteContext::new(deps, message_info(&owner, &[]), mock_env());
+            contract.execute(
+                ctx,
+                AndromedaMsg::Permissioning(PermissioningMessage::SetPermission {
+                    actors: vec![AndrAddr::from_string(minter.to_string())],
+                    action: "mint".to_string(),
+                    permission: Permission::Local(LocalPermission::quota(
+                        Schedule::new(None, None),
+                        max_uses,
+                        window,
+                    )),
+                }),
+            )
+        };
+        let err = set_quota(deps.as_mut(), 0, Milliseconds::from_seconds(60)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPermission { .. }));
+        set_quota(deps.as_mut(), 2, Milliseconds::from_seconds(60)).unwrap();
+
+        let quota = |deps: Deps, env: &Env| {
+            contract
+                .query_permission_quota(
+                    deps,
+                    env.clone(),
+                    "mint".to_string(),
+                    AndrAddr::from_string(minter.to_string()),
+                )
+                .unwrap()
+        };
+        assert_eq!(
+            quota(deps.as_ref(), &env),
+            PermissionQuotaResponse {
+                max_uses: 2,
+                remaining_uses: 2,
+                resets_at: None,
+            }
+        );
+
+        for _ in 0..2 {
+            assert!(contract
+                .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+                .is_ok());
+        }
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_err());
+        let reset = Milliseconds::from_nanos(env.block.time.nanos()).plus_seconds(60);
+        assert_eq!(
+            quota(deps.as_ref(), &env),
+            PermissionQuotaResponse {
+                max_uses: 2,
+                remaining_uses: 0,
+                resets_at: Some(reset),
+            }
+        );
+
+        // The quota refills once the window has elapsed
+        env.block.time = env.block.time.plus_seconds(60);
+        assert!(contract
+            .is_permissioned_strict(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+        assert_eq!(quota(deps.as_ref(), &env).remaining_uses, 1);
+    }
+
+    #[test]
+    fn test_role_quota_permission() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let contract = ADOContract::default();
+        let owner = Addr::unchecked(OWNER);
+        contract.owner.save(deps.as_mut().storage, &owner).unwrap();
+        let minter = deps.api.addr_make("minter");
+        let other = deps.api.addr_make("other");
+        contract
+            .permission_action(deps.as_mut().storage, "mint", None)
+            .unwrap();
+
+        let execute = |deps: DepsMut, msg: PermissioningMessage| {
+            let ctx = ExecuteContext::new(deps, message_info(&owner, &[]), mock_env());
+            contract.execute(ctx, AndromedaMsg::Permissioning(msg))
+        };
+        execute(
+            deps.as_mut(),
+            PermissioningMessage::SetRole {
+                role: "minter".to_string(),
+                actions: vec!["mint".to_string()],
+                permission: LocalPermission::quota(
+                    Schedule::new(None, None),
+                    2,
+                    Milliseconds::from_seconds(60),
+                ),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            PermissioningMessage::AssignRole {
+                role: "minter".to_string(),
+                actors: vec![AndrAddr::from_string(minter.to_string())],
+            },
+        )
+        .unwrap();
+
+        let quota = |deps: Deps, actor: &Addr| {
+            contract.query_permission_quota(
+                deps,
+                env.clone(),
+                "mint".to_string(),
+                AndrAddr::from_string(actor.to_string()),
+            )
+        };
+        // The quota of the member's role is reported as it is used when executing
+        assert!(contract
+            .is_permissioned(deps.as_mut(), env.clone(), "mint", minter.as_str())
+            .is_ok());
+        let reset = Milliseconds::from_nanos(env.block.time.nanos()).plus_seconds(60);
+        assert_eq!(
+            quota(deps.as_ref(), &minter).unwrap(),
+            PermissionQuotaResponse {
+                max_uses: 2,
+                remaining_uses: 1,
+                resets_at: Some(reset),
+            }
+        );
+
+        let err = quota(deps.as_ref(), &other).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPermission { .. }));
+    }
 }
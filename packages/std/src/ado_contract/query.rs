@@ -21,7 +21,7 @@ impl ADOContract {
     pub fn query(
         &self,
         deps: Deps,
-        _env: Env,
+        env: Env,
         msg: impl Serialize,
     ) -> Result<Binary, ContractError> {
         let msg = to_json_binary(&msg)?;
@@ -73,6 +73,23 @@ impl ADOContract {
                     limit,
                     order_by,
                 )?),
+                AndromedaQuery::Roles { start_after, limit } => {
+                    encode_binary(&self.query_roles(deps, start_after, limit)?)
+                }
+                AndromedaQuery::RoleMembers {
+                    role,
+                    start_after,
+                    limit,
+                } => encode_binary(&self.query_role_members(deps, role, start_after, limit)?),
+                AndromedaQuery::ActorRoles { actor } => {
+                    encode_binary(&self.query_actor_roles(deps, actor)?)
+                }
+                AndromedaQuery::DelegationChain { action, actor } => {
+                    encode_binary(&self.query_delegation_chain(deps, action, actor)?)
+                }
+                AndromedaQuery::PermissionQuota { action, actor } => {
+                    encode_binary(&self.query_permission_quota(deps, env, action, actor)?)
+                }
                 #[cfg(feature = "rates")]
                 AndromedaQuery::Rates { action } => encode_binary(&self.get_rates(deps, action)?),
 
@@ -2,7 +2,10 @@ use std::fmt::{Display, Formatter, Result as FMTResult};
 
 use crate::error::ContractError;
 use crate::os::vfs::{vfs_resolve_symlink, PATH_REGEX, PROTOCOL_PATH_REGEX};
-use crate::{ado_contract::ADOContract, os::vfs::vfs_resolve_path};
+use crate::{
+    ado_contract::ADOContract,
+    os::vfs::{vfs_resolve_path, vfs_resolve_path_for},
+};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Api, Deps, QuerierWrapper, Storage};
 use lazy_static::lazy_static;
@@ -96,6 +99,27 @@ impl AndrAddr {
         &self,
         deps: &Deps,
         vfs_contract: impl Into<String>,
+    ) -> Result<Addr, ContractError> {
+        self.resolve_raw_address(deps, vfs_contract, None)
+    }
+
+    /// Retrieves the raw address represented by the AndrAddr on behalf of the given actor.
+    ///
+    /// VFS paths are resolved with the actor's access, allowing paths through directories restricted to the actor to be resolved.
+    pub fn get_raw_address_for(&self, deps: &Deps, actor: &str) -> Result<Addr, ContractError> {
+        if !self.is_vfs_path() {
+            return Ok(deps.api.addr_validate(&self.0)?);
+        }
+        let contract = ADOContract::default();
+        let vfs_contract = contract.get_vfs_address(deps.storage, &deps.querier)?;
+        self.resolve_raw_address(deps, vfs_contract, Some(actor))
+    }
+
+    fn resolve_raw_address(
+        &self,
+        deps: &Deps,
+        vfs_contract: impl Into<String>,
+        actor: Option<&str>,
     ) -> Result<Addr, ContractError> {
         match self.is_vfs_path() {
             false => Ok(deps.api.addr_validate(&self.0)?),
@@ -105,7 +129,13 @@ impl AndrAddr {
                 let valid_vfs_path =
                     self.local_path_to_vfs_path(deps.storage, &deps.querier, vfs_contract.clone())?;
                 let vfs_addr = Addr::unchecked(vfs_contract);
-                match vfs_resolve_path(valid_vfs_path.clone(), vfs_addr, &deps.querier) {
+                let resolved = match actor {
+                    Some(actor) => {
+                        vfs_resolve_path_for(valid_vfs_path.clone(), actor, vfs_addr, &deps.querier)
+                    }
+                    None => vfs_resolve_path(valid_vfs_path.clone(), vfs_addr, &deps.querier),
+                };
+                match resolved {
                     Ok(addr) => Ok(addr),
                     Err(_) => {
                         // If the path is cross-chain then we return it as is
@@ -184,6 +214,36 @@ impl AndrAddr {
         }
     }
 
+    /// Creates an address that is routed through the given chains in order before reaching the given path
+    ///
+    /// E.g. `["osmosis", "juno"]` and `/home/user/app` would return `ibc://osmosis/ibc://juno/home/user/app`
+    pub fn from_route(route: &[impl AsRef<str>], path: impl Into<String>) -> AndrAddr {
+        let path = route.iter().rev().fold(path.into(), |path, chain| {
+            format!(
+                "ibc://{}/{}",
+                chain.as_ref(),
+                path.strip_prefix('/').unwrap_or(&path)
+            )
+        });
+        AndrAddr(path)
+    }
+
+    /// Gets the chains a cross-chain address is routed through, in order
+    ///
+    /// E.g. `ibc://osmosis/ibc://juno/home/user/app` would return `["osmosis", "juno"]`
+    ///
+    /// A local path or human readable address will return an empty route
+    pub fn get_route(&self) -> Vec<&str> {
+        let mut route = vec![];
+        let mut rest = self.0.as_str();
+        while let Some(stripped) = rest.strip_prefix("ibc://") {
+            let end = stripped.find('/').unwrap_or(stripped.len());
+            route.push(&stripped[..end]);
+            rest = stripped.get(end + 1..).unwrap_or_default();
+        }
+        route
+    }
+
     /// Gets the protocol for a given AndrAddr if it exists
     ///
     /// E.g. `ibc://cosmoshub-4/user/app/component` would return `ibc`
@@ -437,6 +497,24 @@ mod tests {
         assert_eq!(addr.get_raw_path(), "/chain/home/app/component");
     }
 
+    #[test]
+    fn test_route() {
+        let addr = AndrAddr::from_route(&["osmosis", "juno"], "/home/user/app");
+        assert_eq!(addr, "ibc://osmosis/ibc://juno/home/user/app");
+        assert_eq!(addr.get_route(), vec!["osmosis", "juno"]);
+        assert_eq!(addr.get_chain(), Some("osmosis"));
+        assert_eq!(addr.get_raw_path(), "ibc://juno/home/user/app");
+
+        let addr = AndrAddr::from_route(&["juno"], OWNER);
+        assert_eq!(addr, format!("ibc://juno/{OWNER}"));
+        assert_eq!(addr.get_route(), vec!["juno"]);
+
+        let addr = AndrAddr::from_route(&[] as &[&str], "/home/user/app");
+        assert_eq!(addr, "/home/user/app");
+        assert!(addr.get_route().is_empty());
+        assert!(AndrAddr(OWNER.to_string()).get_route().is_empty());
+    }
+
     #[test]
     fn test_get_root_dir() {
         let addr = AndrAddr("/home/user1".to_string());
@@ -1,18 +1,22 @@
 use crate::ado_contract::ADOContract;
 use crate::common::encode_binary;
+use crate::common::expiration::Expiry;
 use crate::error::ContractError;
 use crate::os::aos_querier::AOSQuerier;
 use crate::os::kernel::{Cw20HookMsg, ExecuteMsg as KernelExecuteMsg};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Coin, ContractInfoResponse, CosmosMsg, Deps, Empty, MessageInfo,
-    QuerierWrapper, QueryRequest, ReplyOn, SubMsg, Uint128, WasmMsg, WasmQuery,
+    ensure, to_json_binary, Addr, Binary, Coin, ContractInfoResponse, CosmosMsg, Deps, Empty,
+    MessageInfo, QuerierWrapper, QueryRequest, ReplyOn, SubMsg, Uint128, WasmMsg, WasmQuery,
 };
 use cw20::Cw20ExecuteMsg;
 
 use super::addresses::AndrAddr;
 use super::ADO_DB_KEY;
 
+/// The minimum gas reserved for each AMP hop when a packet gas budget is enforced
+pub const AMP_HOP_GAS_COST: u64 = 100_000;
+
 /// Exposed for ease of serialisation.
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -21,16 +25,112 @@ pub enum ExecuteMsg {
     AMPReceive(AMPPkt),
 }
 
+/// How the kernel handles an IBC message whose packet times out or is acknowledged with an error
+#[cw_serde]
+pub enum IBCFailurePolicy {
+    /// Refunds any funds to the original sender
+    RefundOrigin,
+    /// Refunds any funds to the message's recovery address
+    RefundRecoveryAddr,
+    /// Sends the packet once more, if the retry fails any funds are refunded to the recovery address if provided, otherwise to the original sender
+    RetryOnce,
+}
+
 #[cw_serde]
 #[derive(Default)]
 pub struct IBCConfig {
     pub recovery_addr: Option<AndrAddr>,
+    /// The timeout of the message's IBC packets, relative to the time the packet is sent or absolute.
+    /// Defaults to seven days.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Expiry>,
+    /// Defaults to `IBCFailurePolicy::RefundOrigin`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_policy: Option<IBCFailurePolicy>,
 }
 
 impl IBCConfig {
     #[inline]
     pub fn new(recovery_addr: Option<AndrAddr>) -> IBCConfig {
-        IBCConfig { recovery_addr }
+        IBCConfig {
+            recovery_addr,
+            timeout: None,
+            failure_policy: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Expiry) -> IBCConfig {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_failure_policy(mut self, failure_policy: IBCFailurePolicy) -> IBCConfig {
+        self.failure_policy = Some(failure_policy);
+        self
+    }
+
+    /// Gets the failure policy, defaulting to `IBCFailurePolicy::RefundOrigin`
+    pub fn get_failure_policy(&self) -> IBCFailurePolicy {
+        self.failure_policy
+            .clone()
+            .unwrap_or(IBCFailurePolicy::RefundOrigin)
+    }
+}
+
+/// The comparison applied between a queried value and the expected value of an [`AMPCondition`]
+#[cw_serde]
+pub enum ConditionOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+
+/// A condition evaluated by the kernel before an AMP message is dispatched.
+///
+/// The `target` is queried with the given `query` and the value found at `path` (dot notation, e.g. `data.nested.0.value`)
+/// within the response is compared against `expected` using the given operator.
+/// Ordering operators require both values to be non-negative numbers.
+#[cw_serde]
+pub struct AMPCondition {
+    /// The contract to query, must be on the current chain
+    pub target: AndrAddr,
+    /// The binary encoded query message
+    pub query: Binary,
+    /// The path to the compared value within the query response, an empty path compares the whole response
+    pub path: String,
+    pub operator: ConditionOperator,
+    /// The expected value, strings are compared without their surrounding quotes
+    pub expected: String,
+    /// Whether the message should be skipped instead of failing when the condition does not hold, defaults to false
+    #[serde(default)]
+    pub skip_on_unmet: bool,
+}
+
+impl AMPCondition {
+    pub fn new(
+        target: impl Into<String>,
+        query: Binary,
+        path: impl Into<String>,
+        operator: ConditionOperator,
+        expected: impl Into<String>,
+    ) -> AMPCondition {
+        AMPCondition {
+            target: AndrAddr::from_string(target),
+            query,
+            path: path.into(),
+            operator,
+            expected: expected.into(),
+            skip_on_unmet: false,
+        }
+    }
+
+    /// Skips the message rather than failing when the condition does not hold
+    pub fn skip_on_unmet(mut self) -> AMPCondition {
+        self.skip_on_unmet = true;
+        self
     }
 }
 
@@ -48,6 +148,9 @@ pub struct AMPMsgConfig {
     /// Whether to send the message directly to the given recipient
     pub direct: bool,
     pub ibc_config: Option<IBCConfig>,
+    /// An optional condition that must hold for the message to be dispatched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<AMPCondition>,
 }
 
 impl AMPMsgConfig {
@@ -64,9 +167,16 @@ impl AMPMsgConfig {
             gas_limit,
             direct: false,
             ibc_config,
+            condition: None,
         }
     }
 
+    /// Adds a condition that must hold for the message to be dispatched
+    pub fn with_condition(mut self, condition: AMPCondition) -> AMPMsgConfig {
+        self.condition = Some(condition);
+        self
+    }
+
     /// Converts the current AMP message to be a direct message to the given contract
     pub fn as_direct_msg(self) -> AMPMsgConfig {
         AMPMsgConfig {
@@ -75,6 +185,7 @@ impl AMPMsgConfig {
             gas_limit: self.gas_limit,
             direct: true,
             ibc_config: self.ibc_config,
+            condition: self.condition,
         }
     }
 }
@@ -88,6 +199,7 @@ impl Default for AMPMsgConfig {
             gas_limit: None,
             direct: false,
             ibc_config: None,
+            condition: None,
         }
     }
 }
@@ -202,9 +314,7 @@ impl AMPMsg {
             msg.config.ibc_config = Some(ibc_config);
             msg
         } else if let Some(recovery_addr) = recovery_addr {
-            let ibc_config = Some(IBCConfig {
-                recovery_addr: Some(recovery_addr),
-            });
+            let ibc_config = Some(IBCConfig::new(Some(recovery_addr)));
             let mut msg = self.clone();
             msg.config.ibc_config = ibc_config;
             msg
@@ -222,6 +332,9 @@ pub struct AMPCtx {
     pub id: Option<String>,
     #[serde(default)]
     pub previous_hops: Vec<CrossChainHop>,
+    /// The remaining gas budget of the packet, enforced by the kernel across nested AMP hops
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_budget: Option<u64>,
 }
 
 impl AMPCtx {
@@ -237,9 +350,45 @@ impl AMPCtx {
             previous_sender: previous_sender.into(),
             id: None,
             previous_hops: vec![],
+            gas_budget: None,
         }
     }
 
+    /// Sets the gas budget shared by every hop of the packet
+    pub fn with_gas_budget(mut self, gas_budget: u64) -> AMPCtx {
+        self.gas_budget = Some(gas_budget);
+        self
+    }
+
+    /// Reserves the gas for a single hop from the remaining gas budget and returns the reserved amount.
+    ///
+    /// The hop reserves the given gas limit if provided, otherwise an even share of the remaining budget
+    /// between the `messages` that are still to be dispatched, including this one.
+    /// At least `AMP_HOP_GAS_COST` is reserved for every hop, so nested AMP chains always exhaust the budget.
+    /// Returns `None` if the packet has no gas budget.
+    pub fn reserve_gas(
+        &mut self,
+        gas_limit: Option<u64>,
+        messages: usize,
+    ) -> Result<Option<u64>, ContractError> {
+        let Some(remaining) = self.gas_budget else {
+            return Ok(None);
+        };
+        let required = gas_limit
+            .unwrap_or(remaining / messages.max(1) as u64)
+            .max(AMP_HOP_GAS_COST);
+        ensure!(
+            required <= remaining,
+            ContractError::GasBudgetExhausted {
+                required,
+                remaining
+            }
+        );
+
+        self.gas_budget = Some(remaining - required);
+        Ok(Some(required))
+    }
+
     /// Gets the original sender of a message
     pub fn get_origin(&self) -> String {
         self.origin.clone()
@@ -330,6 +479,14 @@ pub struct AMPPkt {
     /// Any messages associated with the packet
     pub messages: Vec<AMPMsg>,
     pub ctx: AMPCtx,
+    /// Whether the messages should be executed as a single atomic bundle.
+    /// If any message in an atomic bundle fails, all of them are reverted.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub atomic: bool,
+}
+
+pub(crate) fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl AMPPkt {
@@ -342,12 +499,23 @@ impl AMPPkt {
         AMPPkt {
             messages,
             ctx: AMPCtx::new(origin, previous_sender, None),
+            atomic: false,
         }
     }
 
     /// Creates a new AMP Packet
     pub fn new_with_ctx(ctx: AMPCtx, messages: Vec<AMPMsg>) -> AMPPkt {
-        AMPPkt { messages, ctx }
+        AMPPkt {
+            messages,
+            ctx,
+            atomic: false,
+        }
+    }
+
+    /// Marks the packet as an atomic bundle, all messages succeed or none do
+    pub fn as_atomic(mut self) -> Self {
+        self.atomic = true;
+        self
     }
 
     pub fn with_origin(&self, origin: impl Into<String>) -> AMPPkt {
@@ -521,6 +689,7 @@ impl AMPPkt {
         Self {
             messages: vec![],
             ctx,
+            atomic: false,
         }
     }
 }
@@ -718,6 +887,53 @@ mod tests {
         assert_eq!(memo, "{\"messages\":[],\"ctx\":{\"origin\":\"cosmwasm1rq0a63huffeydw057846xy5m5htjgqg67hcsygantzv4t5wlzz9qpdp0pz\",\"origin_username\":null,\"previous_sender\":\"cosmwasm1n006e54tz4ln667t8p8j9mzk0v7jvlg7hwm568ultag7c94z3n2se70nmk\",\"id\":null,\"previous_hops\":[]}}".to_string());
     }
 
+    #[test]
+    fn test_atomic_to_json() {
+        let msg = AMPPkt::new(ORIGIN, PREVIOUS_SENDER, vec![]).as_atomic();
+
+        let memo = msg.to_json();
+        assert_eq!(memo, "{\"messages\":[],\"ctx\":{\"origin\":\"cosmwasm1rq0a63huffeydw057846xy5m5htjgqg67hcsygantzv4t5wlzz9qpdp0pz\",\"origin_username\":null,\"previous_sender\":\"cosmwasm1n006e54tz4ln667t8p8j9mzk0v7jvlg7hwm568ultag7c94z3n2se70nmk\",\"id\":null,\"previous_hops\":[]},\"atomic\":true}".to_string());
+
+        // Packets without the atomic flag default to non-atomic
+        let pkt: AMPPkt =
+            cosmwasm_std::from_json(AMPPkt::new(ORIGIN, PREVIOUS_SENDER, vec![]).to_json())
+                .unwrap();
+        assert!(!pkt.atomic);
+    }
+
+    #[test]
+    fn test_reserve_gas() {
+        // Packets without a budget are unrestricted
+        let mut ctx = AMPCtx::new(ORIGIN, PREVIOUS_SENDER, None);
+        assert_eq!(ctx.reserve_gas(Some(500_000), 1).unwrap(), None);
+
+        let mut ctx = ctx.with_gas_budget(1_000_000);
+        assert_eq!(ctx.reserve_gas(Some(400_000), 3).unwrap(), Some(400_000));
+        // Every hop reserves at least the hop cost
+        assert_eq!(ctx.reserve_gas(Some(1), 2).unwrap(), Some(AMP_HOP_GAS_COST));
+        assert_eq!(ctx.gas_budget, Some(500_000));
+
+        let err = ctx.reserve_gas(Some(600_000), 1).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::GasBudgetExhausted {
+                required: 600_000,
+                remaining: 500_000
+            }
+        );
+
+        // Hops without a gas limit share the remaining budget with the messages still to be dispatched
+        assert_eq!(ctx.reserve_gas(None, 2).unwrap(), Some(250_000));
+        assert_eq!(ctx.reserve_gas(None, 1).unwrap(), Some(250_000));
+        assert_eq!(
+            ctx.reserve_gas(None, 1).unwrap_err(),
+            ContractError::GasBudgetExhausted {
+                required: AMP_HOP_GAS_COST,
+                remaining: 0
+            }
+        );
+    }
+
     #[test]
     fn test_to_ibc_hooks_memo() {
         let msg = AMPPkt::new(ORIGIN, PREVIOUS_SENDER, vec![]);
@@ -28,8 +28,20 @@ pub fn call_action(
         .and_then(|ado_type| {
             AOSQuerier::action_fee_getter(&deps.querier, &adodb_addr, &ado_type, action).ok()
         })
-        .map(|_| {
-            ADOContract::default().pay_fee(deps.storage, &deps.querier, action.to_owned(), payee)
+        .map(|fee| {
+            // Funds are only needed to calculate fees charging a percentage of funds
+            let funds = if fee.is_some_and(|fee| fee.percent_of_funds.is_some()) {
+                info.funds.clone()
+            } else {
+                vec![]
+            };
+            ADOContract::default().pay_fee(
+                deps.storage,
+                &deps.querier,
+                action.to_owned(),
+                payee,
+                funds,
+            )
         }) {
         Some(fee_msg) => Ok(Response::default().add_submessage(fee_msg?)),
         None => Ok(Response::default()),
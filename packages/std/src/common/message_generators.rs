@@ -244,6 +244,7 @@ mod tests {
             exit_at_error: false,
             direct: false,
             ibc_config: None,
+            condition: None,
         }
     )]
     #[case(
@@ -257,6 +258,7 @@ mod tests {
             exit_at_error: true,
             direct: false,
             ibc_config: None,
+            condition: None,
         }
     )]
     fn test_create_cw20_send_msg(
@@ -2,7 +2,11 @@ use cosmwasm_std::{ensure, Deps};
 use cw2::get_contract_version;
 use semver::Version;
 
-use crate::error::{from_semver, ContractError};
+use crate::{
+    ado_contract::ADOContract,
+    error::{from_semver, ContractError},
+    os::aos_querier::AOSQuerier,
+};
 /// Ensure compatibility when migrating from the previous version.
 ///
 /// min_version specifies the oldest version that is still compatible.
@@ -21,6 +25,38 @@ pub fn ensure_compatibility(deps: &Deps, min_version: &str) -> Result<(), Contra
     Ok(())
 }
 
+/// Ensure the ADODB has a registered migration from the current version directly to the new version.
+///
+/// Prevents skipping intermediate versions whose migrations must be run in order.
+/// Only enforced for ADO types with migrations registered in the ADODB, other ADOs and ADOs that cannot reach the ADODB rely on [`ensure_compatibility`].
+pub fn ensure_migration_path(
+    deps: &Deps,
+    ado_type: &str,
+    new_version: &str,
+) -> Result<(), ContractError> {
+    let prev = get_contract_version(deps.storage)?;
+    let Some(adodb_address) = ADOContract::default()
+        .get_kernel_address(deps.storage)
+        .and_then(|kernel_address| AOSQuerier::adodb_address_getter(&deps.querier, &kernel_address))
+        .ok()
+    else {
+        return Ok(());
+    };
+    let steps = AOSQuerier::migration_path_getter(
+        &deps.querier,
+        &adodb_address,
+        ado_type,
+        &prev.version,
+        new_version,
+    )?;
+
+    ensure!(
+        steps.len() <= 1,
+        ContractError::InvalidMigration { prev: prev.version }
+    );
+    Ok(())
+}
+
 #[test]
 fn test_ensure_compatibility() {
     let mut deps = crate::testing::mock_querier::mock_dependencies_custom(&[]);
@@ -33,3 +69,70 @@ fn test_ensure_compatibility() {
         }
     )
 }
+
+#[test]
+fn test_ensure_migration_path() {
+    use crate::testing::mock_querier::MOCK_KERNEL_CONTRACT;
+    use cosmwasm_std::Addr;
+
+    let mut deps = crate::testing::mock_querier::mock_dependencies_custom(&[]);
+    ADOContract::default()
+        .kernel_address
+        .save(&mut deps.storage, &Addr::unchecked(MOCK_KERNEL_CONTRACT))
+        .unwrap();
+
+    cw2::set_contract_version(&mut deps.storage, "crowdfund", "1.1.0").unwrap();
+    ensure_migration_path(&deps.as_ref(), "crowdfund", "1.2.0").unwrap();
+
+    // Migrating from 1.0.0 to 1.2.0 has to step through 1.1.0 first
+    cw2::set_contract_version(&mut deps.storage, "crowdfund", "1.0.0").unwrap();
+    let res = ensure_migration_path(&deps.as_ref(), "crowdfund", "1.2.0").unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidMigration {
+            prev: "1.0.0".to_string()
+        }
+    );
+    ensure_migration_path(&deps.as_ref(), "crowdfund", "1.1.0").unwrap();
+}
+
+#[test]
+fn test_migrate_rejects_skipped_version() {
+    use crate::testing::mock_querier::MOCK_KERNEL_CONTRACT;
+    use cosmwasm_std::{testing::mock_env, Addr};
+
+    let mut deps = crate::testing::mock_querier::mock_dependencies_custom(&[]);
+    ADOContract::default()
+        .kernel_address
+        .save(&mut deps.storage, &Addr::unchecked(MOCK_KERNEL_CONTRACT))
+        .unwrap();
+    cw2::set_contract_version(&mut deps.storage, "crowdfund", "1.0.0").unwrap();
+
+    let res = ADOContract::default()
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            "crates.io:andromeda-crowdfund",
+            "1.2.0",
+        )
+        .unwrap_err();
+    assert_eq!(
+        res,
+        ContractError::InvalidMigration {
+            prev: "1.0.0".to_string()
+        }
+    );
+
+    ADOContract::default()
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            "crates.io:andromeda-crowdfund",
+            "1.1.0",
+        )
+        .unwrap();
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().version,
+        "1.1.0"
+    );
+}
@@ -10,6 +10,9 @@ pub enum ReplyId {
     RegisterUsername = 105,
     IBCTransfer = 106,
     IBCTransferWithMsg = 107,
+    AMPBundle = 108,
+    ScheduledAMPMsg = 109,
+    RecordDenomTraces = 110,
     // App
     ClaimOwnership = 200,
     AssignApp = 201,
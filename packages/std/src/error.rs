@@ -56,6 +56,12 @@ pub enum ContractError {
     #[error("UnmetCondition")]
     UnmetCondition {},
 
+    #[error("Packet gas budget exhausted, required {required} but {remaining} remaining")]
+    GasBudgetExhausted { required: u64, remaining: u64 },
+
+    #[error("Rate limit exceeded for {scope}")]
+    RateLimitExceeded { scope: String },
+
     #[error("InvalidOrigin")]
     InvalidOrigin {},
 
@@ -98,6 +104,15 @@ pub enum ContractError {
     #[error("NoActorsProvided")]
     NoActorsProvided {},
 
+    #[error("RoleNotFound: {role}")]
+    RoleNotFound { role: String },
+
+    #[error("InvalidRole: {msg}")]
+    InvalidRole { msg: String },
+
+    #[error("InvalidPermissionDelegation: {msg}")]
+    InvalidPermissionDelegation { msg: String },
+
     #[error("InvalidDelegation")]
     InvalidDelegation {},
 
@@ -1,12 +1,12 @@
 use std::str::FromStr;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{ensure, Addr, Api, Uint128};
+use cosmwasm_std::{ensure, Addr, Api, Decimal, Uint128};
 use schemars::JsonSchema;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use crate::{ado_base::ownership::OwnershipMessage, error::ContractError};
+use crate::{ado_base::ownership::OwnershipMessage, common::Milliseconds, error::ContractError};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -40,16 +40,106 @@ pub enum ExecuteMsg {
         ado_type: String,
         publisher: String,
     },
+    /// Flags a published version as deprecated, it can still be instantiated
+    DeprecateVersion {
+        ado_type: String,
+        version: String,
+        reason: String,
+    },
+    /// Blocks new instantiations of a published version, existing instances can still migrate to it
+    YankVersion {
+        ado_type: String,
+        version: String,
+        reason: Option<String>,
+    },
+    /// Clears any deprecation or yank of a published version
+    RestoreVersion {
+        ado_type: String,
+        version: String,
+    },
+    /// Declares that instances of the source versions can migrate directly to the target version
+    AddMigrationPath {
+        ado_type: String,
+        from: Vec<String>,
+        to: String,
+        requires_msg: bool,
+    },
+    RemoveMigrationPath {
+        ado_type: String,
+        from: Vec<String>,
+        to: String,
+    },
     // Base message
     Ownership(OwnershipMessage),
 }
 
 #[cw_serde]
+#[derive(Default)]
 pub struct ActionFee {
     pub action: String,
     pub asset: String,
     pub amount: Uint128,
     pub receiver: Option<Addr>,
+    /// Other assets the fee can be paid in, used in order if the payee cannot pay in `asset`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternative_assets: Vec<FeeAsset>,
+    /// Percentage of the funds sent in `asset` with a payable action, charged on top of the fixed amount
+    ///
+    /// When paid in an alternative asset the percentage is converted at the ratio of the fixed amounts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent_of_funds: Option<Decimal>,
+    /// Discounts for payers included in an address list, the first matching tier is applied
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tiers: Vec<FeeTier>,
+    /// Number of calls each payer can make free of charge per period
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub free_calls: Option<FreeCalls>,
+}
+
+/// Validates an asset in the format "cw20:address" or "native:denom"
+pub fn validate_asset_string(api: &dyn Api, asset: &str) -> Result<(), ContractError> {
+    let asset_split = asset.split(':').collect::<Vec<&str>>();
+    // Ensure asset is in the format "cw20:address" or "native:denom"
+    // This is double validated as the asset type in the ADODB contract for fees is validated as cw20:* or native:*
+    ensure!(
+        asset_split.len() == 2 && !asset_split.is_empty(),
+        ContractError::InvalidAsset {
+            asset: asset.to_string()
+        }
+    );
+    let asset_type = asset_split[0];
+    ensure!(
+        asset_type == "cw20" || asset_type == "native",
+        ContractError::InvalidAsset {
+            asset: asset.to_string()
+        }
+    );
+
+    if asset_type == "cw20" {
+        api.addr_validate(asset_split[1])?;
+    }
+
+    Ok(())
+}
+
+#[cw_serde]
+pub struct FeeAsset {
+    pub asset: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct FeeTier {
+    /// Address list ADO the payer must be whitelisted in
+    pub address_list: Addr,
+    /// Discount applied to the fee, a discount of 1 waives the fee
+    pub discount: Decimal,
+}
+
+#[cw_serde]
+pub struct FreeCalls {
+    pub calls: u32,
+    pub period: Milliseconds,
 }
 
 impl ActionFee {
@@ -59,16 +149,54 @@ impl ActionFee {
             asset,
             amount,
             receiver: None,
+            alternative_assets: vec![],
+            percent_of_funds: None,
+            tiers: vec![],
+            free_calls: None,
         }
     }
 
     pub fn with_receive(&self, receiver: Addr) -> Self {
         Self {
-            action: self.action.clone(),
-            asset: self.asset.clone(),
-            amount: self.amount,
             receiver: Some(receiver),
+            ..self.clone()
+        }
+    }
+
+    /// Validates the assets, tiers and free calls of an action fee
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        self.validate_asset(api)?;
+        for fee_asset in &self.alternative_assets {
+            validate_asset_string(api, &fee_asset.asset)?;
+        }
+        if let Some(percent) = self.percent_of_funds {
+            ensure!(percent <= Decimal::one(), ContractError::InvalidRate {});
+            ensure!(
+                self.alternative_assets.is_empty() || !self.amount.is_zero(),
+                ContractError::InvalidParameter {
+                    error: Some(
+                        "Alternative assets require a non-zero amount to convert the percentage of funds"
+                            .to_string()
+                    )
+                }
+            );
+        }
+        for tier in &self.tiers {
+            api.addr_validate(tier.address_list.as_str())?;
+            ensure!(
+                tier.discount <= Decimal::one(),
+                ContractError::InvalidRate {}
+            );
+        }
+        if let Some(free_calls) = &self.free_calls {
+            ensure!(
+                free_calls.calls > 0 && !free_calls.period.is_zero(),
+                ContractError::InvalidParameter {
+                    error: Some("Free calls require a non-zero amount and period".to_string())
+                }
+            );
         }
+        Ok(())
     }
 
     /// Valiades the provided asset for an action fee
@@ -76,28 +204,7 @@ impl ActionFee {
     /// If the asset type is cw20 the address is also validated
     /// TODO: Add denom validation in future cosmwasm version
     pub fn validate_asset(&self, api: &dyn Api) -> Result<(), ContractError> {
-        let asset_split = self.asset.split(':').collect::<Vec<&str>>();
-        // Ensure asset is in the format "cw20:address" or "native:denom"
-        // This is double validated as the asset type in the ADODB contract for fees is validated as cw20:* or native:*
-        ensure!(
-            asset_split.len() == 2 && !asset_split.is_empty(),
-            ContractError::InvalidAsset {
-                asset: self.asset.clone()
-            }
-        );
-        let asset_type = asset_split[0];
-        ensure!(
-            asset_type == "cw20" || asset_type == "native",
-            ContractError::InvalidAsset {
-                asset: self.asset.clone()
-            }
-        );
-
-        if asset_type == "cw20" {
-            api.addr_validate(asset_split[1])?;
-        }
-
-        Ok(())
+        validate_asset_string(api, &self.asset)
     }
 
     /// Gets the asset string without the asset type
@@ -125,10 +232,40 @@ pub struct ADOMetadata {
     pub latest_version: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub enum VersionStatus {
+    #[default]
+    Active,
+    Deprecated {
+        reason: String,
+    },
+    Yanked {
+        reason: Option<String>,
+    },
+}
+
+#[cw_serde]
+pub struct ADOVersionInfo {
+    pub version: String,
+    pub code_id: u64,
+    pub status: VersionStatus,
+}
+
+#[cw_serde]
+pub struct MigrationStep {
+    pub version: String,
+    pub code_id: u64,
+    /// Whether the migration to this version requires a migrate message payload
+    pub requires_msg: bool,
+}
+
 #[cw_serde]
 #[cfg_attr(not(target_arch = "wasm32"), derive(cw_orch::QueryFns))]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    /// Gets the code ID for an ADO version, the version may be exact, `latest` or a semver range such as `splitter@^2.1`.
+    /// Yanked versions are never returned.
     #[returns(u64)]
     CodeId { key: String },
     // #[returns(Vec<u64>)]
@@ -151,6 +288,22 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    #[returns(Vec<ADOVersionInfo>)]
+    #[serde(rename = "ado_versions_with_status")]
+    ADOVersionsWithStatus {
+        ado_type: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Gets the shortest sequence of registered migrations from one version to another
+    ///
+    /// Empty if no migrations are registered for the ADO type.
+    #[returns(Vec<MigrationStep>)]
+    MigrationPath {
+        ado_type: String,
+        from: String,
+        to: String,
+    },
     // #[returns(Vec<String>)]
     // #[serde(rename = "unpublished_ado_versions")]
     // UnpublishedADOVersions { ado_type: String },
@@ -333,4 +486,48 @@ mod tests {
             ActionFee::new("action".to_string(), "invalid".to_string(), Uint128::zero());
         assert!(action_fee.validate_asset(deps.as_ref().api).is_err());
     }
+
+    #[test]
+    fn test_action_fee_validate() {
+        let deps = mock_dependencies();
+        let mut action_fee = ActionFee {
+            alternative_assets: vec![FeeAsset {
+                asset: "native:uandr".to_string(),
+                amount: Uint128::new(20),
+            }],
+            percent_of_funds: Some(Decimal::percent(10)),
+            tiers: vec![FeeTier {
+                address_list: deps.api.addr_make("address_list"),
+                discount: Decimal::percent(50),
+            }],
+            free_calls: Some(FreeCalls {
+                calls: 5,
+                period: Milliseconds::from_seconds(86_400),
+            }),
+            ..ActionFee::new(
+                "action".to_string(),
+                "native:uusd".to_string(),
+                Uint128::new(10),
+            )
+        };
+        assert!(action_fee.validate(deps.as_ref().api).is_ok());
+
+        action_fee.tiers[0].discount = Decimal::percent(150);
+        assert!(action_fee.validate(deps.as_ref().api).is_err());
+        action_fee.tiers[0].discount = Decimal::percent(50);
+
+        action_fee.alternative_assets[0].asset = "invalid".to_string();
+        assert!(action_fee.validate(deps.as_ref().api).is_err());
+        action_fee.alternative_assets[0].asset = "native:uandr".to_string();
+
+        action_fee.amount = Uint128::zero();
+        assert!(action_fee.validate(deps.as_ref().api).is_err());
+        action_fee.amount = Uint128::new(10);
+
+        action_fee.free_calls = Some(FreeCalls {
+            calls: 0,
+            period: Milliseconds::from_seconds(86_400),
+        });
+        assert!(action_fee.validate(deps.as_ref().api).is_err());
+    }
 }
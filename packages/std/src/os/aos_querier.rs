@@ -2,7 +2,7 @@ use crate::ado_base::permissioning::LocalPermission;
 use crate::amp::{ADO_DB_KEY, IBC_REGISTRY_KEY, VFS_KEY};
 use crate::error::ContractError;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{from_json, Addr, ChannelResponse, IbcQuery, QuerierWrapper};
+use cosmwasm_std::{from_json, Addr, ChannelResponse, IbcQuery, QuerierWrapper, Uint128};
 use cw_storage_plus::Path;
 use lazy_static::__Deref;
 use serde::de::DeserializeOwned;
@@ -11,12 +11,14 @@ use std::str::from_utf8;
 #[cfg(feature = "rates")]
 use crate::ado_base::rates::LocalRate;
 
-use super::adodb::{ADOVersion, ActionFee, QueryMsg as ADODBQueryMsg};
+use super::adodb::{ADOVersion, ActionFee, MigrationStep, QueryMsg as ADODBQueryMsg};
 use super::ibc_registry::{
-    hops_to_path, path_to_hops, DenomInfo, DenomInfoResponse, Hop, QueryMsg as IBCRegistryQueryMsg,
+    hops_to_path, path_to_hops, AssetMetadata, AssetMetadataResponse, DenomInfo, DenomInfoResponse,
+    Hop, QueryMsg as IBCRegistryQueryMsg,
 };
 use super::kernel::ChannelInfo;
 use super::TRANSFER_PORT;
+use crate::common::denom::Asset;
 
 #[cw_serde]
 pub struct AOSQuerier();
@@ -133,6 +135,22 @@ impl AOSQuerier {
         Ok(code_id)
     }
 
+    pub fn migration_path_getter(
+        querier: &QuerierWrapper,
+        adodb_addr: &Addr,
+        ado_type: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<MigrationStep>, ContractError> {
+        let query = ADODBQueryMsg::MigrationPath {
+            ado_type: ado_type.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        let steps: Vec<MigrationStep> = querier.query_wasm_smart(adodb_addr, &query)?;
+        Ok(steps)
+    }
+
     /// Queries the kernel's raw storage for the VFS's address
     pub fn vfs_address_getter(
         querier: &QuerierWrapper,
@@ -287,6 +305,35 @@ impl AOSQuerier {
         Ok(denom_info_response.denom_info)
     }
 
+    /// Queries the IBC registry for the metadata of an asset
+    pub fn asset_metadata_getter(
+        querier: &QuerierWrapper,
+        ibc_registry_addr: &Addr,
+        asset: &Asset,
+    ) -> Result<AssetMetadata, ContractError> {
+        let query = IBCRegistryQueryMsg::AssetMetadata {
+            asset: asset.clone(),
+        };
+        let response: AssetMetadataResponse =
+            querier.query_wasm_smart(ibc_registry_addr, &query)?;
+        Ok(response.metadata)
+    }
+
+    /// Formats an amount of an asset as a human readable amount using the asset's metadata in the IBC registry.
+    /// Returns `None` if the IBC registry has no metadata for the asset.
+    pub fn format_asset_amount(
+        querier: &QuerierWrapper,
+        kernel_addr: &Addr,
+        asset: &Asset,
+        amount: Uint128,
+    ) -> Option<String> {
+        let ibc_registry_addr =
+            AOSQuerier::kernel_address_getter(querier, kernel_addr, IBC_REGISTRY_KEY).ok()?;
+        let metadata =
+            AOSQuerier::asset_metadata_getter(querier, &ibc_registry_addr, asset).ok()?;
+        Some(metadata.format_amount(amount))
+    }
+
     pub fn get_counterparty_denom(
         querier: &QuerierWrapper,
         denom_trace: &DenomInfo,
@@ -1,8 +1,12 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Uint128};
 use cw20::Cw20ReceiveMsg;
 
-use crate::{ado_base::ownership::OwnershipMessage, amp::AndrAddr};
+use crate::{
+    ado_base::ownership::OwnershipMessage,
+    amp::AndrAddr,
+    common::{expiration::Expiry, Milliseconds},
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -25,9 +29,13 @@ pub enum ExecuteMsg {
     /// 1. The balance of the ADO contract itself
     /// 2. The balance of the App contract for the ADO
     /// 3. The provided payee address
+    ///
+    /// The funds sent with the action are only required for fees charging a percentage of funds.
     PayFee {
         payee: Addr,
         action: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        funds: Vec<Coin>,
     },
     /// Withdraw native funds from the Andromeda economics module.
     ///
@@ -45,6 +53,25 @@ pub enum ExecuteMsg {
         asset: String,
     },
     Receive(Cw20ReceiveMsg),
+    /// Sponsors fees for the given ADOs from the sender's balance, replacing any previous sponsorship by the sender.
+    ///
+    /// The sender must own every sponsored ADO. Sponsored fees are paid before the payee's balance is used.
+    RegisterSponsorship {
+        ados: Vec<AndrAddr>,
+        /// The sponsored actions, all actions are sponsored if not provided
+        actions: Option<Vec<String>>,
+        /// The sponsored users, all users are sponsored if not provided
+        users: Option<Vec<AndrAddr>>,
+        /// The maximum amount of an asset sponsored for each user, assets without a cap are not limited
+        #[serde(default)]
+        user_caps: Vec<AssetCap>,
+        expiration: Option<Expiry>,
+    },
+    RemoveSponsorship {},
+    /// Claims the fee revenue accrued by the sender, all assets are claimed if none are provided
+    ClaimRevenue {
+        assets: Option<Vec<String>>,
+    },
     // Base message
     Ownership(OwnershipMessage),
 }
@@ -57,6 +84,48 @@ pub enum Cw20HookMsg {
     Deposit { address: Option<AndrAddr> },
 }
 
+/// A cap on the amount of an asset, in the format "native:denom" or "cw20:address"
+#[cw_serde]
+pub struct AssetCap {
+    pub asset: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct Sponsorship {
+    pub ados: Vec<Addr>,
+    pub actions: Option<Vec<String>>,
+    pub users: Option<Vec<Addr>>,
+    pub user_caps: Vec<AssetCap>,
+    pub expiration: Option<Milliseconds>,
+}
+
+#[cw_serde]
+pub struct AssetAmount {
+    pub asset: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct RevenueResponse {
+    /// Total revenue earned by the recipient
+    pub earned: Vec<AssetAmount>,
+    /// Revenue that has not been claimed yet
+    pub unclaimed: Vec<AssetAmount>,
+}
+
+#[cw_serde]
+pub struct RevenueBucket {
+    pub start: Milliseconds,
+    pub end: Milliseconds,
+    pub revenue: Vec<AssetAmount>,
+}
+
+#[cw_serde]
+pub struct RevenueReportResponse {
+    pub buckets: Vec<RevenueBucket>,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -65,6 +134,35 @@ pub enum QueryMsg {
     /// Returns a `Uint128` representing the current balance
     #[returns(BalanceResponse)]
     Balance { asset: String, address: AndrAddr },
+    #[returns(Option<Sponsorship>)]
+    Sponsorship { sponsor: AndrAddr },
+    /// Queries the fee revenue of a fee recipient, assets are in the format "native:denom" or "cw20:address"
+    #[returns(RevenueResponse)]
+    Revenue { recipient: AndrAddr },
+    /// Queries the fee revenue of an ADO type, optionally restricted to a single action
+    #[returns(Vec<AssetAmount>)]
+    #[serde(rename = "ado_type_revenue")]
+    ADOTypeRevenue {
+        ado_type: String,
+        action: Option<String>,
+    },
+    /// Queries the revenue of a fee recipient between `start` and `end`, grouped into buckets of `bucket_size`.
+    ///
+    /// Revenue is recorded daily, `bucket_size` must be a multiple of a day.
+    #[returns(RevenueReportResponse)]
+    RevenueReport {
+        recipient: AndrAddr,
+        start: Milliseconds,
+        end: Milliseconds,
+        bucket_size: Milliseconds,
+    },
+    /// Queries the amount of an asset a sponsor has paid on behalf of a user, in the format "native:denom" or "cw20:address"
+    #[returns(Uint128)]
+    SponsoredAmount {
+        sponsor: AndrAddr,
+        user: AndrAddr,
+        asset: String,
+    },
     // Base queries
     #[returns(crate::ado_base::version::VersionResponse)]
     Version {},
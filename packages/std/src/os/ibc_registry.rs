@@ -1,9 +1,10 @@
 use crate::{
     amp::{messages::AMPPkt, AndrAddr},
+    common::denom::Asset,
     error::ContractError,
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{ensure, Addr};
+use cosmwasm_std::{ensure, Addr, Uint128};
 use sha2::{Digest, Sha256};
 use strum_macros::AsRefStr;
 
@@ -41,6 +42,70 @@ pub struct IBCDenomInfo {
     pub denom_info: DenomInfo,
 }
 
+/// A denom trace as it exists on a given chain
+#[cw_serde]
+pub struct DenomTrace {
+    /// The chain the denom exists on
+    pub chain: String,
+    /// The denom on the chain, `ibc/<hash>` or the base denom if the trace has no path
+    pub denom: String,
+    pub denom_info: DenomInfo,
+}
+
+/// The maximum number of decimals an asset can have
+pub const MAX_ASSET_DECIMALS: u8 = 18;
+
+/// Human readable metadata of an asset
+#[cw_serde]
+pub struct AssetMetadata {
+    pub decimals: u8,
+    pub symbol: String,
+    /// The display name of the asset
+    pub display: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+}
+
+impl AssetMetadata {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        ensure!(
+            self.decimals <= MAX_ASSET_DECIMALS,
+            ContractError::InvalidParameter {
+                error: Some(format!(
+                    "Decimals cannot be greater than {MAX_ASSET_DECIMALS}"
+                )),
+            }
+        );
+        ensure!(
+            !self.symbol.trim().is_empty() && !self.display.trim().is_empty(),
+            ContractError::InvalidParameter {
+                error: Some("Symbol and display name cannot be empty".to_string()),
+            }
+        );
+        ensure!(
+            self.logo_uri
+                .as_ref()
+                .is_none_or(|logo_uri| !logo_uri.trim().is_empty()),
+            ContractError::InvalidParameter {
+                error: Some("Logo URI cannot be empty".to_string()),
+            }
+        );
+        Ok(())
+    }
+
+    /// Formats an amount in the asset's smallest unit as a human readable amount, e.g. `1.5 ANDR`
+    pub fn format_amount(&self, amount: Uint128) -> String {
+        let unit = 10u128.pow(self.decimals as u32);
+        let whole = amount.u128() / unit;
+        let fraction = amount.u128() % unit;
+        if fraction == 0 {
+            return format!("{whole} {}", self.symbol);
+        }
+        let fraction = format!("{fraction:0>width$}", width = self.decimals as usize);
+        format!("{whole}.{} {}", fraction.trim_end_matches('0'), self.symbol)
+    }
+}
+
 #[cw_serde]
 #[derive(AsRefStr)]
 #[cfg_attr(not(target_arch = "wasm32"), derive(cw_orch::ExecuteFns))]
@@ -51,6 +116,21 @@ pub enum ExecuteMsg {
     StoreDenomInfo {
         ibc_denom_info: Vec<IBCDenomInfo>,
     },
+    /// Records the denom traces of an ICS20 transfer relayed by the kernel.
+    /// Only the kernel can record denom traces.
+    RecordDenomTraces {
+        traces: Vec<DenomTrace>,
+    },
+    /// Sets the metadata of an asset.
+    /// New entries can be published by permissioned addresses, existing entries can only be updated by their publisher or the owner.
+    SetAssetMetadata {
+        asset: Asset,
+        metadata: AssetMetadata,
+    },
+    /// Removes the metadata of an asset, restricted to the entry's publisher or the owner
+    RemoveAssetMetadata {
+        asset: Asset,
+    },
 }
 
 /// Ensures that the denom starts with 'ibc/'
@@ -150,6 +230,18 @@ pub enum QueryMsg {
         limit: Option<u64>, // Defaults to 100,
         start_after: Option<u64>,
     },
+    /// Returns the `ibc/<hash>` denom known for the given base denom and trace path
+    #[returns(IBCDenomResponse)]
+    #[serde(rename = "ibc_denom")]
+    IBCDenom { base_denom: String, path: String },
+    /// Returns every chain the base denom is known on along with its denoms on that chain, one for each trace path
+    #[returns(DenomChainsResponse)]
+    DenomChains { base_denom: String },
+    #[returns(AssetMetadataResponse)]
+    AssetMetadata { asset: Asset },
+    /// Returns the metadata of the given assets, assets without metadata are omitted
+    #[returns(AssetMetadataBatchResponse)]
+    AssetMetadataBatch { assets: Vec<Asset> },
 }
 
 #[cw_serde]
@@ -161,3 +253,26 @@ pub struct DenomInfoResponse {
 pub struct AllDenomInfoResponse {
     pub denom_info: Vec<DenomInfo>,
 }
+
+#[cw_serde]
+pub struct IBCDenomResponse {
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct DenomChainsResponse {
+    pub chains: Vec<DenomTrace>,
+}
+
+#[cw_serde]
+pub struct AssetMetadataResponse {
+    /// The asset in `native:<denom>` or `cw20:<address>` format
+    pub asset: String,
+    pub metadata: AssetMetadata,
+    pub publisher: Addr,
+}
+
+#[cw_serde]
+pub struct AssetMetadataBatchResponse {
+    pub assets: Vec<AssetMetadataResponse>,
+}
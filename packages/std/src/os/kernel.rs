@@ -1,12 +1,13 @@
 use crate::{
     ado_base::ownership::OwnershipMessage,
     amp::{
-        messages::{AMPMsg, AMPPkt, CrossChainHop},
+        messages::{AMPMsg, AMPPkt, CrossChainHop, IBCConfig},
         AndrAddr,
     },
+    common::{expiration::Expiry, Milliseconds},
 };
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary, Coin};
+use cosmwasm_std::{Addr, Binary, Coin, Timestamp};
 use cw20::Cw20ReceiveMsg;
 
 pub const CROSS_CHAIN_ENABLED: &str = "cross_chain_enabled";
@@ -56,6 +57,8 @@ pub enum ExecuteMsg {
     Send {
         message: AMPMsg,
     },
+    /// Relays the message of an ICS20 transfer once the transfer is acknowledged, restricted to the trigger address.
+    /// Failed or timed out transfers are reported with an error acknowledgement and handled according to the message's failure policy.
     TriggerRelay {
         packet_sequence: u64,
         channel_id: String,
@@ -72,6 +75,9 @@ pub enum ExecuteMsg {
         msg: Binary,
         owner: Option<AndrAddr>,
         chain: Option<String>,
+        /// Replaces `${VARIABLE}` references in the message with environment variables before instantiating the ADO
+        #[serde(default, skip_serializing_if = "crate::amp::messages::is_false")]
+        resolve_env: bool,
     },
     /// Assigns a given channel to the given chain
     AssignChannels {
@@ -80,8 +86,42 @@ pub enum ExecuteMsg {
         chain: String,
         kernel_address: String,
     },
-    /// Recovers funds from failed IBC messages
-    Recover {},
+    /// Recovers funds from failed IBC messages.
+    ///
+    /// Only funds of the given denom are recovered if one is provided.
+    /// Funds are sent to the sender unless a recipient is provided, `ibc://` recipients receive the funds over ICS20.
+    Recover {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        denom: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        recipient: Option<AndrAddr>,
+    },
+    /// Stores an AMP message to be released once the given expiry is reached.
+    /// Any funds attached to the message are held by the kernel until then.
+    ScheduleMessage {
+        message: AMPMsg,
+        expiry: Expiry,
+    },
+    /// Releases due scheduled messages in the order they became due, callable by anyone
+    ProcessScheduledMessages {
+        limit: Option<u32>,
+    },
+    /// Cancels a scheduled message and refunds its funds, restricted to the original sender
+    CancelScheduledMessage {
+        id: u64,
+    },
+    /// Enables packet tracing and keeps at most `retention` packet traces, older traces are pruned first.
+    /// Tracing is disabled if no retention is provided, existing traces are kept until pruned.
+    /// Restricted to the kernel owner.
+    SetTraceRetention {
+        retention: Option<u64>,
+    },
+    /// Sets the rate limit for the given scope, the limit is removed if none is provided.
+    /// Restricted to the kernel owner.
+    SetRateLimit {
+        scope: RateLimitScope,
+        limit: Option<RateLimit>,
+    },
     /// Update Current Chain
     UpdateChainName {
         chain_name: String,
@@ -98,6 +138,22 @@ pub enum ExecuteMsg {
     UnsetEnv {
         variable: String,
     },
+    /// Sets a typed environment variable within the given scope.
+    /// Global variables are restricted to the kernel owner and are also readable through `GetEnv`,
+    /// app variables to the app's owner and user variables to the user.
+    ///
+    /// Instantiation messages sent through `Create` can reference variables as `${VARIABLE}`,
+    /// they are resolved from the creating app's scope, then the owner's user scope and finally the global scope.
+    SetScopedEnv {
+        scope: EnvScope,
+        variable: String,
+        value: EnvValue,
+    },
+    /// Removes a typed environment variable from the given scope
+    UnsetScopedEnv {
+        scope: EnvScope,
+        variable: String,
+    },
     // Only accessible to key contracts
     Internal(InternalMsg),
     // Base message
@@ -118,6 +174,15 @@ pub enum InternalMsg {
         address: String,
         chain: String,
     },
+    // Restricted to the kernel itself
+    ExecuteBundle {
+        packet: AMPPkt,
+    },
+    /// Requests the address of a path from the kernel of another chain, restricted to VFS
+    ResolvePathCrossChain {
+        path: String,
+        chain: String,
+    },
 }
 
 #[cw_serde]
@@ -136,6 +201,40 @@ pub struct ChainNameResponse {
 #[cw_serde]
 pub struct RecoveriesResponse {
     pub recoveries: Vec<Coin>,
+    #[serde(default)]
+    pub records: Vec<RecoveryRecord>,
+}
+
+#[cw_serde]
+pub enum RecoveryReason {
+    /// The ICS20 transfer was acknowledged with an error
+    TransferFailed,
+    /// The ICS20 transfer timed out
+    TransferTimeout,
+    /// The message sent along with transferred funds failed on this chain
+    ExecutionFailed,
+    /// Recorded before recovery records were introduced, the origin of the funds is unknown
+    Legacy,
+}
+
+/// Funds held by the kernel for an address following a failed IBC message
+#[cw_serde]
+pub struct RecoveryRecord {
+    pub id: u64,
+    pub owner: Addr,
+    pub amount: Coin,
+    pub reason: RecoveryReason,
+    /// The chain the funds were sent to or received from
+    pub chain: Option<String>,
+    pub channel: Option<String>,
+    /// The sequence of the ICS20 packet, if known
+    pub sequence: Option<u64>,
+    pub timestamp: Timestamp,
+}
+
+#[cw_serde]
+pub struct RecoveryRecordsResponse {
+    pub records: Vec<RecoveryRecord>,
 }
 
 #[cw_serde]
@@ -154,6 +253,135 @@ pub struct EnvResponse {
     pub value: Option<String>,
 }
 
+#[cw_serde]
+pub enum EnvScope {
+    /// Variables visible to every ADO
+    Global {},
+    /// Variables of an app, used by ADOs created by the app
+    App { address: AndrAddr },
+    /// Variables within a user's VFS namespace, used by ADOs owned by the user
+    User { username: String },
+}
+
+#[cw_serde]
+pub enum EnvValue {
+    String(String),
+    Number(i64),
+    Address(Addr),
+    Path(AndrAddr),
+    /// A JSON encoded value, referencing the variable inserts the decoded value
+    Json(String),
+}
+
+impl std::fmt::Display for EnvValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EnvValue::String(value) | EnvValue::Json(value) => write!(f, "{value}"),
+            EnvValue::Number(value) => write!(f, "{value}"),
+            EnvValue::Address(value) => write!(f, "{value}"),
+            EnvValue::Path(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ScopedEnvResponse {
+    pub value: Option<EnvValue>,
+}
+
+#[cw_serde]
+pub struct ScheduledMessage {
+    pub id: u64,
+    /// The address that scheduled the message, used as the message's origin once released
+    pub sender: Addr,
+    pub message: AMPMsg,
+    /// The time at which the message becomes due
+    pub execute_at: Milliseconds,
+}
+
+#[cw_serde]
+pub struct ScheduledMessagesResponse {
+    pub messages: Vec<ScheduledMessage>,
+}
+
+#[cw_serde]
+pub enum PacketTraceStatus {
+    /// The message was dispatched by the kernel and has not replied yet, failures of messages that exit at an error revert the trace alongside the transaction
+    Dispatched,
+    Success,
+    Error,
+}
+
+#[cw_serde]
+pub struct PacketTraceEntry {
+    pub recipient: AndrAddr,
+    pub funds: Vec<Coin>,
+    /// The cross chain hops the packet had taken when the entry was recorded
+    pub previous_hops: Vec<CrossChainHop>,
+    pub status: PacketTraceStatus,
+    pub error: Option<String>,
+    pub timestamp: Milliseconds,
+}
+
+#[cw_serde]
+pub struct PacketTrace {
+    /// The packet ID as provided by `AMPCtx::get_id`
+    pub id: String,
+    /// Order in which the trace was first recorded, used for pagination and retention
+    pub sequence: u64,
+    pub origin: String,
+    pub entries: Vec<PacketTraceEntry>,
+}
+
+#[cw_serde]
+pub struct PacketTracesResponse {
+    pub traces: Vec<PacketTrace>,
+}
+
+#[cw_serde]
+pub enum RateLimitScope {
+    /// The default limit for every origin address, each origin is counted separately
+    Origins {},
+    /// The limit for a single origin address, overrides the default origin limit
+    ///
+    /// Senders on other chains whose funds are relayed by the kernel are identified as `ibc://<chain>/<address>`
+    Origin { address: String },
+    /// The limit for messages relayed to the given recipient, counted across all origins
+    Destination { address: AndrAddr },
+}
+
+#[cw_serde]
+pub struct RateLimit {
+    /// The length of a window in blocks, windows start at multiples of this length
+    pub window: u64,
+    /// The maximum amount of messages relayed within a window
+    pub max_messages: Option<u64>,
+    /// The maximum amount of each denom relayed within a window, denoms that are not listed are not limited
+    #[serde(default)]
+    pub max_funds: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RateLimitCounter {
+    /// The first block of the counted window
+    pub window_start: u64,
+    pub messages: u64,
+    pub funds: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RateLimitStatus {
+    pub limit: RateLimit,
+    /// The counter of the current window
+    pub counter: RateLimitCounter,
+}
+
+#[cw_serde]
+pub struct RateLimitStatusResponse {
+    pub origin: Option<RateLimitStatus>,
+    pub destination: Option<RateLimitStatus>,
+}
+
 #[cw_serde]
 #[cfg_attr(not(target_arch = "wasm32"), derive(cw_orch::QueryFns))]
 #[derive(QueryResponses)]
@@ -168,6 +396,15 @@ pub enum QueryMsg {
     ChainNameByChannel { channel: String },
     #[returns(RecoveriesResponse)]
     Recoveries { addr: Addr },
+    /// Paginated recovery records of all addresses, optionally filtered by denom and chain.
+    /// `start_after` is a record ID.
+    #[returns(RecoveryRecordsResponse)]
+    AllRecoveries {
+        denom: Option<String>,
+        chain: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     #[returns(ChainNameResponse)]
     ChainName {},
     // Base queries
@@ -182,6 +419,28 @@ pub enum QueryMsg {
     PendingPackets { channel_id: Option<String> },
     #[returns(EnvResponse)]
     GetEnv { variable: String },
+    #[returns(ScopedEnvResponse)]
+    GetScopedEnv { scope: EnvScope, variable: String },
+    #[returns(ScheduledMessagesResponse)]
+    ScheduledMessages {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Option<PacketTrace>)]
+    PacketTrace { id: String },
+    /// Paginated traces of packets from the given origin, `start_after` is a trace sequence
+    #[returns(PacketTracesResponse)]
+    PacketTraces {
+        origin: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// The rate limits and current counters that apply to the given origin and destination
+    #[returns(RateLimitStatusResponse)]
+    RateLimitStatus {
+        origin: Option<String>,
+        destination: Option<AndrAddr>,
+    },
 }
 
 #[cw_serde]
@@ -211,6 +470,10 @@ pub enum IbcExecuteMsg {
         username: String,
         address: String,
     },
+    /// Resolves a path on the receiving chain, the address is returned in the acknowledgement
+    ResolvePath {
+        path: String,
+    },
 }
 
 #[cw_serde]
@@ -223,6 +486,29 @@ pub struct Ics20PacketInfo {
     // The restricted wallet will probably already have access to this
     pub channel: String,
     pub pending: bool,
+    /// The IBC configuration of the message, the recovery address is stored resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ibc_config: Option<IBCConfig>,
+    /// Whether the transfer has already been retried following its failure policy
+    #[serde(default)]
+    pub retried: bool,
+    /// Set when the funds are being forwarded through this chain as an intermediate hop
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forwarded: Option<ForwardedFunds>,
+}
+
+/// Routing data for funds forwarded by an intermediate kernel
+#[cw_serde]
+pub struct ForwardedFunds {
+    /// The original sender on the origin chain
+    pub original_sender: String,
+    pub original_sender_username: Option<AndrAddr>,
+    pub previous_hops: Vec<CrossChainHop>,
+    /// The ICS20 channel the funds arrived on
+    pub channel: String,
+    /// The original sender's address on this chain, resolved from their username.
+    /// Funds are recovered for this address if the onward transfer fails, otherwise they are returned along `previous_hops`.
+    pub recovery_addr: Option<Addr>,
 }
 
 #[cw_serde]
@@ -230,4 +516,10 @@ pub struct RefundData {
     pub original_sender: String,
     pub funds: Coin,
     pub channel: String,
+    /// Funds of multi-hop packets are recovered for this address on this chain instead of being returned over `channel`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery_addr: Option<Addr>,
+    /// The hops the funds took to reach this chain, funds of multi-hop packets without a recovery address retrace them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_hops: Vec<CrossChainHop>,
 }
@@ -3,6 +3,7 @@ mod tests {
 
     use crate::error::ContractError;
     use crate::os::ibc_registry::*;
+    use cosmwasm_std::Uint128;
 
     struct ValidateDenomTestCase {
         name: String,
@@ -153,4 +154,34 @@ mod tests {
         let path = hops_to_path(hops);
         assert_eq!(path, "");
     }
+
+    #[test]
+    fn test_asset_metadata() {
+        let metadata = AssetMetadata {
+            decimals: 6,
+            symbol: "ANDR".to_string(),
+            display: "Andromeda".to_string(),
+            logo_uri: None,
+        };
+        assert!(metadata.validate().is_ok());
+        assert_eq!(metadata.format_amount(Uint128::new(1_500_000)), "1.5 ANDR");
+        assert_eq!(metadata.format_amount(Uint128::new(2_000_000)), "2 ANDR");
+        assert_eq!(metadata.format_amount(Uint128::new(1)), "0.000001 ANDR");
+
+        let invalid = AssetMetadata {
+            decimals: MAX_ASSET_DECIMALS + 1,
+            ..metadata.clone()
+        };
+        assert!(invalid.validate().is_err());
+        let invalid = AssetMetadata {
+            symbol: " ".to_string(),
+            ..metadata.clone()
+        };
+        assert!(invalid.validate().is_err());
+        let invalid = AssetMetadata {
+            logo_uri: Some("".to_string()),
+            ..metadata
+        };
+        assert!(invalid.validate().is_err());
+    }
 }
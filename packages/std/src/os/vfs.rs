@@ -1,4 +1,9 @@
-use crate::{ado_base::ownership::OwnershipMessage, amp::AndrAddr, error::ContractError};
+use crate::{
+    ado_base::{ownership::OwnershipMessage, permissioning::Permission},
+    amp::AndrAddr,
+    common::Milliseconds,
+    error::ContractError,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{ensure, Addr, Api, QuerierWrapper};
 use regex::Regex;
@@ -163,6 +168,25 @@ pub enum ExecuteMsg {
         symlink: AndrAddr,
         parent_address: Option<AndrAddr>,
     },
+    /// Removes a path along with the paths beneath it, restricted in the same way as `AddPath`
+    RemovePath {
+        name: String,
+        parent_address: Option<AndrAddr>,
+    },
+    /// Renames a path, the paths beneath it are kept
+    RenamePath {
+        name: String,
+        #[schemars(regex = "COMPONENT_NAME_REGEX")]
+        new_name: String,
+        parent_address: Option<AndrAddr>,
+    },
+    /// Moves a path and the paths beneath it to a new parent.
+    /// The new parent must be the sender unless the sender is the Kernel or VFS owner.
+    MovePath {
+        name: String,
+        parent_address: Option<AndrAddr>,
+        new_parent_address: AndrAddr,
+    },
     // Registers a child, currently only accessible by an App Contract
     AddChild {
         #[schemars(regex = "COMPONENT_NAME_REGEX")]
@@ -183,10 +207,110 @@ pub enum ExecuteMsg {
         chain: String,
         address: String,
     },
+    /// Transfers the sender's username and the paths under their home directory to the recipient
+    TransferUsername {
+        recipient: String,
+    },
+    /// Releases the sender's username, making it immediately available to others
+    ReleaseUsername {},
+    /// Extends the sender's username registration by the registration period, allowed until the grace period ends
+    RenewUsername {},
+    // Restricted to VFS owner
+    SetUsernameConfig {
+        config: UsernameConfig,
+    },
+    /// Grants the actors access to a directory, restricted to the directory's own address, Kernel and VFS owner
+    SetDirectoryPermission {
+        directory: AndrAddr,
+        actors: Vec<AndrAddr>,
+        access: DirectoryAccess,
+        permission: Permission,
+    },
+    RemoveDirectoryPermission {
+        directory: AndrAddr,
+        actors: Vec<AndrAddr>,
+        access: DirectoryAccess,
+    },
+    /// Restricts resolving paths within a directory to actors with resolve access
+    ///
+    /// Restriction only limits which actors messages can be routed through the directory for.
+    /// Paths are stored publicly and can be read by anyone, they are not kept confidential.
+    SetDirectoryRestricted {
+        directory: AndrAddr,
+        restricted: bool,
+    },
+    /// Sets the sender's primary path, which must resolve to the sender
+    SetPrimaryPath {
+        path: AndrAddr,
+    },
+    RemovePrimaryPath {},
+    /// Requests the address of a remote path from its chain, the cached address is updated once the Kernel receives it.
+    /// Local symlinks to remote paths are followed.
+    RefreshRemotePath {
+        path: AndrAddr,
+    },
+    // Restricted to Kernel
+    CacheRemotePath {
+        chain: String,
+        path: String,
+        address: String,
+    },
     // Base message
     Ownership(OwnershipMessage),
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct UsernameConfig {
+    /// How long a registration lasts before it needs to be renewed, registrations do not expire if not set
+    pub registration_period: Option<Milliseconds>,
+    /// How long an expired username is reserved for renewal by its owner before it can be claimed by others
+    pub grace_period: Milliseconds,
+}
+
+#[cw_serde]
+pub enum UsernameStatus {
+    Active,
+    /// Expired but only renewable by its owner, the username does not resolve
+    Grace,
+    Available,
+}
+
+#[cw_serde]
+pub struct UsernameInfoResponse {
+    pub username: String,
+    pub owner: Option<Addr>,
+    pub expiration: Option<Milliseconds>,
+    pub status: UsernameStatus,
+}
+
+#[cw_serde]
+pub enum DirectoryAccess {
+    /// Allows adding, removing, renaming and moving paths within the directory
+    Write,
+    /// Allows resolving paths within the directory when it is restricted
+    Resolve,
+}
+
+impl DirectoryAccess {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Write => "write",
+            Self::Resolve => "resolve",
+        }
+    }
+}
+
+#[cw_serde]
+pub struct EffectivePermissionsResponse {
+    /// The directory's address, which owns the directory
+    pub directory: Addr,
+    pub is_owner: bool,
+    pub restricted: bool,
+    pub can_write: bool,
+    pub can_resolve: bool,
+}
+
 #[cw_serde]
 pub struct SubDirBound {
     address: Addr,
@@ -211,9 +335,41 @@ pub struct SubDirResponse {
     pub subdirs: Vec<PathInfo>,
 }
 
+#[cw_serde]
+pub struct RemotePathInfo {
+    pub address: String,
+    pub cached_at: Milliseconds,
+}
+
+#[cw_serde]
+pub struct CrossChainPathResponse {
+    pub chain: String,
+    pub path: String,
+    pub address: String,
+    pub cached_at: Milliseconds,
+    /// The time since the address was cached
+    pub age: Milliseconds,
+    /// Whether the age exceeds the provided or default maximum age
+    pub stale: bool,
+}
+
+#[cw_serde]
+pub struct TreeNode {
+    /// The path relative to the queried path
+    pub path: String,
+    pub depth: u32,
+    pub info: PathInfo,
+}
+
+#[cw_serde]
+pub struct TreeResponse {
+    pub nodes: Vec<TreeNode>,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    /// Resolves a path, paths through restricted directories can only be resolved with `ResolvePathFor`
     #[returns(Addr)]
     ResolvePath { path: AndrAddr },
     #[returns(SubDirResponse)]
@@ -225,12 +381,45 @@ pub enum QueryMsg {
     },
     #[returns(Vec<String>)]
     Paths { addr: Addr },
+    /// Returns the address' primary path if it still resolves to the address
+    #[returns(Option<String>)]
+    ReverseResolve { addr: Addr },
+    /// Lists the paths beneath a path depth first, paginated by the relative path of the last node
+    #[returns(TreeResponse)]
+    Tree {
+        path: AndrAddr,
+        depth: Option<u32>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     #[returns(String)]
     GetUsername { address: Addr },
+    #[returns(UsernameInfoResponse)]
+    UsernameInfo { username: String },
+    #[returns(UsernameConfig)]
+    UsernameConfig {},
     #[returns(String)]
     GetLibrary { address: Addr },
     #[returns(AndrAddr)]
     ResolveSymlink { path: AndrAddr },
+    /// Gets the cached address of a remote path, following local symlinks to remote paths
+    #[returns(CrossChainPathResponse)]
+    ResolvePathCrossChain {
+        path: AndrAddr,
+        max_age: Option<Milliseconds>,
+    },
+    /// Resolves a path, ensuring the actor may resolve each directory along it
+    ///
+    /// The actor is not authenticated, this is used to route messages on behalf of the actor and does not keep paths confidential.
+    #[returns(Addr)]
+    ResolvePathFor { path: AndrAddr, actor: AndrAddr },
+    #[returns(crate::ado_base::permissioning::PermissionsResponse)]
+    DirectoryPermissions { directory: AndrAddr },
+    #[returns(EffectivePermissionsResponse)]
+    EffectivePermissions {
+        directory: AndrAddr,
+        actor: AndrAddr,
+    },
     // Base queries
     #[returns(crate::ado_base::version::VersionResponse)]
     Version {},
@@ -258,6 +447,24 @@ pub fn vfs_resolve_path(
     }
 }
 
+/// Queries the provided VFS contract address to resolve the given path on behalf of the given actor
+pub fn vfs_resolve_path_for(
+    path: impl Into<String>,
+    actor: impl Into<String>,
+    vfs_contract: impl Into<String>,
+    querier: &QuerierWrapper,
+) -> Result<Addr, ContractError> {
+    let query = QueryMsg::ResolvePathFor {
+        path: AndrAddr::from_string(path.into()),
+        actor: AndrAddr::from_string(actor.into()),
+    };
+    let addr = querier.query_wasm_smart::<Addr>(vfs_contract, &query);
+    match addr {
+        Ok(addr) => Ok(addr),
+        Err(_) => Err(ContractError::InvalidAddress {}),
+    }
+}
+
 /// Queries the provided VFS contract address to resolve the given path
 pub fn vfs_resolve_symlink(
     path: impl Into<String>,
@@ -1,10 +1,15 @@
 use crate::{
-    ado_base::AndromedaQuery,
+    ado_base::{permissioning::LocalPermission, AndromedaQuery},
     ado_contract::ADOContract,
     amp::{ADO_DB_KEY, ECONOMICS_KEY, IBC_REGISTRY_KEY, OSMOSIS_ROUTER_KEY, VFS_KEY},
+    common::{denom::Asset, Milliseconds},
     os::{
-        adodb::{ActionFee, QueryMsg as ADODBQueryMsg},
-        ibc_registry::{DenomInfo, QueryMsg as IBCRegistryQueryMsg},
+        adodb::{
+            ActionFee, FeeAsset, FeeTier, FreeCalls, MigrationStep, QueryMsg as ADODBQueryMsg,
+        },
+        ibc_registry::{
+            AssetMetadata, AssetMetadataResponse, DenomInfo, QueryMsg as IBCRegistryQueryMsg,
+        },
         kernel::{ChannelInfo, QueryMsg as KernelQueryMsg},
         vfs::QueryMsg as VFSQueryMsg,
         IBC_VERSION, TRANSFER_PORT,
@@ -47,6 +52,8 @@ pub const MOCK_ADODB_CONTRACT: &str =
     "cosmwasm1czczgckw8ffqfdrv864vmr5re5rqn2cvw8t0pttyeanmxvdv99cq9fqslh";
 /// Mock IBC Registry Contract Address
 pub const MOCK_IBC_REGISTRY_CONTRACT: &str = "ibc_registry_contract";
+/// A native denom with metadata in the mock IBC registry
+pub const MOCK_METADATA_DENOM: &str = "uandr";
 // Mock ADO Publisher
 pub const MOCK_ADO_PUBLISHER: &str =
     "cosmwasm1a3dv29r4mu0kruar9fjpxrggxggqpnaw5cj5rq4nytpdg2pxtskqpdxmwr";
@@ -71,6 +78,8 @@ pub const FAKE_VFS_PATH: &str = "/f";
 pub const FAKE_ADODB_KEY: &str = "fake_adodb_key";
 /// A valid action
 pub const MOCK_ACTION: &str = "action";
+/// An action with a tiered fee
+pub const MOCK_TIERED_ACTION: &str = "tiered_action";
 pub const UNWHITELISTED_ADDRESS: &str = "unwhitelisted_address";
 pub const RATES_EXCLUDED_ADDRESS: &str = "rates_excluded_address";
 
@@ -227,6 +236,7 @@ impl MockAndromedaQuerier {
                     MOCK_KERNEL_CONTRACT => self.handle_kernel_raw_query(key, false),
                     MOCK_FAKE_KERNEL_CONTRACT => self.handle_kernel_raw_query(key, true),
                     MOCK_ADODB_CONTRACT => self.handle_adodb_raw_query(key),
+                    MOCK_ADDRESS_LIST_CONTRACT => self.handle_address_list_raw_query(key),
                     MOCK_CW20_CONTRACT => self.handle_cw20_owner_query(key),
                     MOCK_ANCHOR_CONTRACT => self.handle_anchor_owner_query(key),
                     MOCK_UANDR => self.handle_cw20_owner_query(key),
@@ -306,13 +316,17 @@ impl MockAndromedaQuerier {
 
     /// Handles all VFS queries.
     ///
-    /// Returns the path provided for `ResolvePath` queries, or an error for`FAKE_PATH`.
+    /// Returns the path provided for `ResolvePath` and `ResolvePathFor` queries, or an error for`FAKE_PATH`.
     fn handle_vfs_query(&self, msg: &Binary) -> QuerierResult {
         match from_json(msg).unwrap() {
-            VFSQueryMsg::ResolvePath { path } => match path.as_str() {
-                FAKE_VFS_PATH => SystemResult::Ok(ContractResult::Err("Invalid Path".to_string())),
-                _ => SystemResult::Ok(ContractResult::Ok(to_json_binary(&path).unwrap())),
-            },
+            VFSQueryMsg::ResolvePath { path } | VFSQueryMsg::ResolvePathFor { path, .. } => {
+                match path.as_str() {
+                    FAKE_VFS_PATH => {
+                        SystemResult::Ok(ContractResult::Err("Invalid Path".to_string()))
+                    }
+                    _ => SystemResult::Ok(ContractResult::Ok(to_json_binary(&path).unwrap())),
+                }
+            }
             VFSQueryMsg::ResolveSymlink { path } => match path.as_str() {
                 FAKE_VFS_PATH => SystemResult::Ok(ContractResult::Err("Invalid Path".to_string())),
                 _ => SystemResult::Ok(ContractResult::Ok(to_json_binary(&path).unwrap())),
@@ -350,6 +364,8 @@ impl MockAndromedaQuerier {
     /// Returns `"ADOType"` for `ADOType` queries with code ID 1 and an error otherwise.
     ///
     /// Returns an error for `CodeId` queries with key `FAKE_ADODB_KEY` and 1 otherwise.
+    ///
+    /// Returns a two step path for `MigrationPath` queries from `1.0.0` and a direct path otherwise.
     fn handle_adodb_query(&self, msg: &Binary) -> QuerierResult {
         match from_json(msg).unwrap() {
             ADODBQueryMsg::ADOType { code_id } => match code_id {
@@ -362,6 +378,22 @@ impl MockAndromedaQuerier {
                 FAKE_ADODB_KEY => SystemResult::Ok(ContractResult::Err("Invalid Key".to_string())),
                 _ => SystemResult::Ok(ContractResult::Ok(to_json_binary(&1).unwrap())),
             },
+            ADODBQueryMsg::MigrationPath { from, to, .. } => {
+                let mut steps = vec![];
+                if from == "1.0.0" && to != "1.1.0" {
+                    steps.push(MigrationStep {
+                        version: "1.1.0".to_string(),
+                        code_id: 1,
+                        requires_msg: false,
+                    });
+                }
+                steps.push(MigrationStep {
+                    version: to,
+                    code_id: 1,
+                    requires_msg: false,
+                });
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&steps).unwrap()))
+            }
             _ => SystemResult::Ok(ContractResult::Err("Not implemented".to_string())),
         }
     }
@@ -371,6 +403,8 @@ impl MockAndromedaQuerier {
     /// Returns the denom info for `MOCK_OSMO_IBC_DENOM_ON_ANDR` and an error otherwise.
     ///
     /// Returns an error for any other denom.
+    ///
+    /// Returns the metadata of `MOCK_METADATA_DENOM` for `AssetMetadata` queries and an error otherwise.
     fn handle_ibc_registry_query(&self, msg: &Binary) -> QuerierResult {
         match from_json(msg).unwrap() {
             IBCRegistryQueryMsg::DenomInfo { denom } => match denom.as_str() {
@@ -383,6 +417,24 @@ impl MockAndromedaQuerier {
                 )),
                 _ => SystemResult::Ok(ContractResult::Err("Invalid Denom".to_string())),
             },
+            IBCRegistryQueryMsg::AssetMetadata { asset } => match asset {
+                Asset::NativeToken(denom) if denom == MOCK_METADATA_DENOM => {
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&AssetMetadataResponse {
+                            asset: asset.to_string(),
+                            metadata: AssetMetadata {
+                                decimals: 6,
+                                symbol: "ANDR".to_string(),
+                                display: "Andromeda".to_string(),
+                                logo_uri: None,
+                            },
+                            publisher: Addr::unchecked(MOCK_IBC_REGISTRY_CONTRACT),
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => SystemResult::Ok(ContractResult::Err("Asset metadata not found".to_string())),
+            },
             _ => SystemResult::Ok(ContractResult::Err("Not implemented".to_string())),
         }
     }
@@ -504,6 +556,22 @@ impl MockAndromedaQuerier {
         panic!("Unsupported query for contract: {contract_addr}");
     }
 
+    /// Handles raw permission queries for the address list contract.
+    ///
+    /// Returns a whitelisted permission for any actor other than `UNWHITELISTED_ADDRESS`.
+    pub fn handle_address_list_raw_query(&self, key: &Binary) -> QuerierResult {
+        let key_str = String::from_utf8(key.to_vec()).unwrap();
+        if !key_str.contains("permissioning") {
+            panic!("Unsupported query for address list: {key_str}");
+        }
+        let res = if key_str.contains(UNWHITELISTED_ADDRESS) {
+            Binary::default()
+        } else {
+            to_json_binary(&LocalPermission::default()).unwrap()
+        };
+        SystemResult::Ok(ContractResult::Ok(res))
+    }
+
     pub fn handle_kernel_raw_query(&self, key: &Binary, fake: bool) -> QuerierResult {
         let key_vec = key.as_slice();
         let key_str = String::from_utf8(key_vec.to_vec()).unwrap();
@@ -593,7 +661,29 @@ impl MockAndromedaQuerier {
             let key = split.last();
             match key {
                 Some(key) => {
-                    if key.contains("ADOTypeaction") {
+                    if key.contains("ADOTypetiered_action") {
+                        let fee = ActionFee {
+                            alternative_assets: vec![FeeAsset {
+                                asset: "native:uandr".to_string(),
+                                amount: Uint128::from(20u128),
+                            }],
+                            percent_of_funds: Some(cosmwasm_std::Decimal::percent(10)),
+                            tiers: vec![FeeTier {
+                                address_list: Addr::unchecked(MOCK_ADDRESS_LIST_CONTRACT),
+                                discount: cosmwasm_std::Decimal::percent(50),
+                            }],
+                            free_calls: Some(FreeCalls {
+                                calls: 1,
+                                period: Milliseconds::from_seconds(86_400),
+                            }),
+                            ..ActionFee::new(
+                                MOCK_TIERED_ACTION.to_string(),
+                                "native:uusd".to_string(),
+                                Uint128::from(10u128),
+                            )
+                        };
+                        SystemResult::Ok(ContractResult::Ok(to_json_binary(&fee).unwrap()))
+                    } else if key.contains("ADOTypeaction") {
                         SystemResult::Ok(ContractResult::Ok(
                             to_json_binary(&ActionFee::new(
                                 MOCK_ACTION.to_string(),
@@ -186,6 +186,7 @@ fn test_conditional_splitter() {
                     asset: "native:uandr".to_string(),
                     amount: Uint128::from(10u128),
                     receiver: Some(owner.clone()),
+                    ..Default::default()
                 }],
             },
             andr.get_wallet("admin").clone(),
@@ -87,6 +87,7 @@ fn test_kernel_ibc_execute_only() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
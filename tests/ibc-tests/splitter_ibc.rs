@@ -285,6 +285,7 @@ fn test_splitter_ibc_update_recipients() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
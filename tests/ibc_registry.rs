@@ -1,9 +1,14 @@
 use andromeda_app_contract::mock::mock_andromeda_app;
 use andromeda_ibc_registry::mock::mock_andromeda_ibc_registry;
 use andromeda_std::{
+    common::denom::Asset,
     error::ContractError,
-    os::ibc_registry::{AllDenomInfoResponse, DenomInfo, DenomInfoResponse, IBCDenomInfo},
+    os::ibc_registry::{
+        AllDenomInfoResponse, AssetMetadata, DenomInfo, DenomInfoResponse, DenomTrace,
+        IBCDenomInfo, IBCDenomResponse,
+    },
 };
+use andromeda_testing::MockContract;
 use andromeda_testing::{
     ibc_registry::MockIbcRegistry, mock::mock_app, mock_builder::MockAndromedaBuilder,
 };
@@ -120,3 +125,170 @@ fn test_ibc_registry() {
         .unwrap();
     assert_eq!(err, ContractError::Unauthorized {});
 }
+
+#[test]
+fn test_ibc_registry_record_denom_traces() {
+    let mut router = mock_app(None);
+    let andr = MockAndromedaBuilder::new(&mut router, "admin")
+        .with_wallets(vec![("user1", vec![])])
+        .with_contracts(vec![("ibc_registry", mock_andromeda_ibc_registry())])
+        .build(&mut router);
+    let user1 = andr.get_wallet("user1").clone();
+    let kernel = andr.kernel.addr().clone();
+    let ibc_registry: MockIbcRegistry = andr.ibc_registry;
+
+    let denom_info = DenomInfo::new("uandr".to_string(), "transfer/channel-1".to_string());
+    let traces = vec![
+        DenomTrace {
+            chain: "andromeda".to_string(),
+            denom: "uandr".to_string(),
+            denom_info: DenomInfo::new("uandr".to_string(), "".to_string()),
+        },
+        DenomTrace {
+            chain: "juno".to_string(),
+            denom: denom_info.get_ibc_denom(),
+            denom_info: denom_info.clone(),
+        },
+    ];
+
+    // Only the kernel can record denom traces
+    let err: ContractError = ibc_registry
+        .execute_record_denom_traces(&mut router, user1, traces.clone())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    ibc_registry
+        .execute_record_denom_traces(&mut router, kernel.clone(), traces.clone())
+        .unwrap();
+
+    let query_res = ibc_registry.query_denom_info(&mut router, denom_info.get_ibc_denom());
+    assert_eq!(
+        query_res,
+        DenomInfoResponse {
+            denom_info: denom_info.clone()
+        }
+    );
+    let query_res = ibc_registry.query_ibc_denom(
+        &mut router,
+        "uandr".to_string(),
+        "transfer/channel-1".to_string(),
+    );
+    assert_eq!(
+        query_res,
+        IBCDenomResponse {
+            denom: denom_info.get_ibc_denom()
+        }
+    );
+    let query_res = ibc_registry.query_denom_chains(&mut router, "uandr".to_string());
+    assert_eq!(query_res.chains, vec![traces[1].clone(), traces[0].clone()]);
+
+    // A denom reaching the same chain through another path is recorded alongside the first
+    let other_denom_info = DenomInfo::new("uandr".to_string(), "transfer/channel-2".to_string());
+    let other_trace = DenomTrace {
+        chain: "juno".to_string(),
+        denom: other_denom_info.get_ibc_denom(),
+        denom_info: other_denom_info,
+    };
+    ibc_registry
+        .execute_record_denom_traces(&mut router, kernel.clone(), vec![other_trace.clone()])
+        .unwrap();
+    let query_res = ibc_registry.query_denom_chains(&mut router, "uandr".to_string());
+    assert_eq!(
+        query_res.chains,
+        vec![traces[1].clone(), other_trace, traces[0].clone()]
+    );
+
+    // Traces must match their denom
+    let err: ContractError = ibc_registry
+        .execute_record_denom_traces(
+            &mut router,
+            kernel,
+            vec![DenomTrace {
+                chain: "juno".to_string(),
+                denom: "uandr".to_string(),
+                denom_info,
+            }],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::InvalidDenom {
+            msg: Some("The denom should start with 'ibc/'".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_ibc_registry_asset_metadata() {
+    let mut router = mock_app(None);
+    let andr = MockAndromedaBuilder::new(&mut router, "admin")
+        .with_wallets(vec![("service_address", vec![]), ("user1", vec![])])
+        .with_contracts(vec![("ibc_registry", mock_andromeda_ibc_registry())])
+        .build(&mut router);
+    let admin = andr.admin_address.clone();
+    let service_address = andr.get_wallet("service_address").clone();
+    let user1 = andr.get_wallet("user1").clone();
+    let ibc_registry: MockIbcRegistry = andr.ibc_registry;
+
+    let asset = Asset::NativeToken("uandr".to_string());
+    let metadata = AssetMetadata {
+        decimals: 6,
+        symbol: "ANDR".to_string(),
+        display: "Andromeda".to_string(),
+        logo_uri: Some("https://andromedaprotocol.io/logo.png".to_string()),
+    };
+
+    // Only permissioned addresses can publish new entries
+    let err: ContractError = ibc_registry
+        .execute_set_asset_metadata(&mut router, user1.clone(), asset.clone(), metadata.clone())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    ibc_registry
+        .execute_set_asset_metadata(
+            &mut router,
+            service_address.clone(),
+            asset.clone(),
+            metadata.clone(),
+        )
+        .unwrap();
+    let query_res = ibc_registry.query_asset_metadata(&mut router, asset.clone());
+    assert_eq!(query_res.asset, "native:uandr");
+    assert_eq!(query_res.metadata, metadata);
+    assert_eq!(query_res.publisher, service_address);
+
+    // The owner can update any entry, the publisher is kept
+    let updated = AssetMetadata {
+        logo_uri: None,
+        ..metadata
+    };
+    ibc_registry
+        .execute_set_asset_metadata(&mut router, admin, asset.clone(), updated.clone())
+        .unwrap();
+
+    let query_res = ibc_registry.query_asset_metadata_batch(
+        &mut router,
+        vec![asset.clone(), Asset::NativeToken("uusd".to_string())],
+    );
+    assert_eq!(query_res.assets.len(), 1);
+    assert_eq!(query_res.assets[0].metadata, updated);
+    assert_eq!(query_res.assets[0].publisher, service_address);
+
+    let err: ContractError = ibc_registry
+        .execute_remove_asset_metadata(&mut router, user1, asset.clone())
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ibc_registry
+        .execute_remove_asset_metadata(&mut router, service_address, asset.clone())
+        .unwrap();
+    let query_res = ibc_registry.query_asset_metadata_batch(&mut router, vec![asset]);
+    assert!(query_res.assets.is_empty());
+}
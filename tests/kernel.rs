@@ -570,6 +570,7 @@ fn test_kernel_send_to_vfs() {
                 gas_limit: None,
                 direct: true,
                 ibc_config: None,
+                condition: None,
             }),
         )
         .unwrap_err()
@@ -244,6 +244,7 @@ fn test_kernel_ibc_execute_only() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -542,6 +543,7 @@ fn test_kernel_ibc_execute_only_with_username() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -947,6 +949,7 @@ fn test_kernel_ibc_execute_only_multi_hop() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -1240,6 +1243,7 @@ fn test_kernel_ibc_funds_only() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -1766,6 +1770,7 @@ fn test_kernel_ibc_funds_only_multi_hop() {
             gas_limit: None,
             direct: true,
             ibc_config: None,
+            condition: None,
         },
     };
     let kernel_juno_send_request = kernel_juno
@@ -1787,6 +1792,7 @@ fn test_kernel_ibc_funds_only_multi_hop() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -2087,6 +2093,7 @@ fn test_kernel_ibc_funds_and_execute_msg() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -2371,6 +2378,7 @@ fn test_kernel_ibc_funds_only_unhappy() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },
@@ -2705,6 +2713,7 @@ fn test_kernel_ibc_funds_and_execute_msg_unhappy() {
                         gas_limit: None,
                         direct: true,
                         ibc_config: None,
+                        condition: None,
                     },
                 },
             },